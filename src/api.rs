@@ -1,39 +1,267 @@
+use axum::response::IntoResponse;
 use tracing::{Level, event};
 
+/// Settings of the HTTP API configurable via CLI or ENV arguments.
+#[derive(Debug, Clone)]
+pub(crate) struct ApiConfig {
+    /// Delay after submission at which webhook tasks without an execution time
+    /// are executed. If not set, webhook tasks require an execution time.
+    pub(crate) webhook_delay: Option<std::time::Duration>,
+    /// Delay after submission at which hash tasks without an execution time are
+    /// executed. If not set, hash tasks require an execution time.
+    pub(crate) hash_delay: Option<std::time::Duration>,
+    /// Body of webhook tasks that don't specify one. If not set, webhook tasks
+    /// require a body.
+    pub(crate) webhook_body: Option<String>,
+    /// Maximum length in bytes of the URL of a webhook task.
+    pub(crate) max_webhook_url_len: usize,
+    /// Maximum length in bytes of the body of a webhook task.
+    pub(crate) max_webhook_body_len: usize,
+    /// Upper bounds for the cost parameters of hash tasks.
+    pub(crate) hash_limits: crate::hashing::HashLimits,
+    /// Whether batch requests are all-or-nothing. If not set, the items of a
+    /// batch succeed or fail individually.
+    pub(crate) batch_atomic: bool,
+    /// Policies for delivering webhook tasks inherited via their tags.
+    pub(crate) tag_policies: crate::policy::TagPolicies,
+    /// Maximum number of tasks stored per task type. If not set, the number of
+    /// tasks is unbounded.
+    pub(crate) max_rows_per_table: Option<u64>,
+    /// Secrets for issuing and verifying signed links authorizing the creation
+    /// of tasks of a specific shape. If not set, signed links are unavailable.
+    pub(crate) links: Option<crate::links::Links>,
+    /// Token with which admins authenticate to admin endpoints. If not set,
+    /// admin endpoints are unavailable.
+    pub(crate) admin_token: Option<crate::links::BearerToken>,
+    /// Key with which clients authenticate to the endpoints managing tasks. If
+    /// not set, these endpoints are open to everyone.
+    pub(crate) api_key: Option<crate::links::BearerToken>,
+    /// Time by which execution times may lie in the past and still be accepted,
+    /// to tolerate clients whose clocks are slightly behind.
+    pub(crate) clock_skew_tolerance: std::time::Duration,
+    /// Precision of the fractional seconds of times in responses.
+    pub(crate) time_precision: TimePrecision,
+    /// Notifier of created tasks. If not set, no notifications are sent.
+    pub(crate) on_task_created: Option<CreationNotifier>,
+    /// Calendar of business days recurring webhook tasks may be restricted to.
+    pub(crate) business_calendar: std::sync::Arc<crate::calendar::BusinessCalendar>,
+    /// Values at which the graded components of the health score drop to zero.
+    pub(crate) health_thresholds: HealthThresholds,
+    /// Task types that may be created and are handled.
+    pub(crate) task_types: TaskTypes,
+}
+
+/// Task types enabled in this deployment. Tasks of disabled types can't be
+/// created, and existing ones aren't queued for handling upon start.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TaskTypes {
+    pub(crate) webhook: bool,
+    pub(crate) hash: bool,
+}
+
+impl TaskTypes {
+    /// Returns whether tasks of type `type_name` are enabled.
+    pub(crate) fn is_enabled(self, type_name: &str) -> bool {
+        match type_name {
+            "webhook" => self.webhook,
+            "hash" => self.hash,
+            _ => false,
+        }
+    }
+}
+
+/// Precision of the fractional seconds with which times are formatted in
+/// responses. Times are stored with the precision they were supplied or
+/// computed with regardless.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum TimePrecision {
+    Seconds,
+    Millis,
+    Nanos,
+}
+
+/// Fields of a task holding times, which are reformatted in responses.
+const TASK_TIME_FIELDS: [&str; 4] = ["execution_time", "created_at", "started_at", "completed_at"];
+
+impl TimePrecision {
+    /// Formats `time` in RFC 3339 with this precision, keeping its offset.
+    pub(crate) fn format<Tz: chrono::TimeZone>(self, time: &chrono::DateTime<Tz>) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        let format = match self {
+            TimePrecision::Seconds => chrono::SecondsFormat::Secs,
+            TimePrecision::Millis => chrono::SecondsFormat::Millis,
+            TimePrecision::Nanos => chrono::SecondsFormat::Nanos,
+        };
+        time.to_rfc3339_opts(format, false)
+    }
+
+    /// Reformats the stored RFC 3339 `time` with this precision. Leaves `time`
+    /// untouched if it can't be parsed.
+    fn reformat(self, time: &mut String) {
+        if let Ok(t) = chrono::DateTime::parse_from_rfc3339(time) {
+            *time = self.format(&t);
+        }
+    }
+
+    /// Reformats a task's `execution_time` and those of its transition times
+    /// that are set.
+    fn reformat_task(
+        self,
+        execution_time: &mut String,
+        transition_times: [&mut Option<String>; 3],
+    ) {
+        self.reformat(execution_time);
+        for time in transition_times.into_iter().flatten() {
+            self.reformat(time);
+        }
+    }
+
+    /// Reformats the time fields of the JSON representation of a task, either
+    /// bare or wrapped in an object naming its type.
+    fn reformat_value(self, task: &mut serde_json::Value) {
+        let Some(fields) = task.as_object_mut() else {
+            return;
+        };
+        for field in TASK_TIME_FIELDS {
+            if let Some(serde_json::Value::String(time)) = fields.get_mut(field) {
+                self.reformat(time);
+            }
+            for wrapped in ["webhook", "hash"] {
+                if let Some(serde_json::Value::String(time)) =
+                    fields.get_mut(wrapped).and_then(|w| w.get_mut(field))
+                {
+                    self.reformat(time);
+                }
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct ApiCtx {
-    db_pool: sqlx::sqlite::SqlitePool,
+    db_pool: crate::db::DbPool,
     send_task: tokio::sync::mpsc::Sender<(tokio::time::Duration, Task)>,
+    send_cmd: tokio::sync::mpsc::Sender<crate::worker::WorkerCmd>,
+    host_limiter: std::sync::Arc<crate::worker::HostLimiter>,
+    task_limiter: std::sync::Arc<crate::worker::TaskLimiter>,
+    outbound: Option<std::sync::Arc<crate::worker::OutboundQueue>>,
+    throughput: std::sync::Arc<crate::worker::Throughput>,
+    scheduling_error: std::sync::Arc<crate::worker::SchedulingError>,
+    cancel_registry: std::sync::Arc<crate::worker::CancelRegistry>,
+    worker_alive: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    metrics: std::sync::Arc<crate::metrics::Metrics>,
+    disk_space: Option<std::sync::Arc<crate::maintenance::DiskSpace>>,
+    create_limits: std::sync::Arc<CreateRateLimits>,
+    config: ApiConfig,
 }
 
 impl ApiCtx {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
-        db_pool: sqlx::sqlite::SqlitePool,
+        db_pool: crate::db::DbPool,
         send_task: tokio::sync::mpsc::Sender<(tokio::time::Duration, Task)>,
+        send_cmd: tokio::sync::mpsc::Sender<crate::worker::WorkerCmd>,
+        host_limiter: std::sync::Arc<crate::worker::HostLimiter>,
+        task_limiter: std::sync::Arc<crate::worker::TaskLimiter>,
+        outbound: Option<std::sync::Arc<crate::worker::OutboundQueue>>,
+        throughput: std::sync::Arc<crate::worker::Throughput>,
+        scheduling_error: std::sync::Arc<crate::worker::SchedulingError>,
+        cancel_registry: std::sync::Arc<crate::worker::CancelRegistry>,
+        worker_alive: std::sync::Arc<std::sync::atomic::AtomicBool>,
+        metrics: std::sync::Arc<crate::metrics::Metrics>,
+        disk_space: Option<std::sync::Arc<crate::maintenance::DiskSpace>>,
+        create_limits: std::sync::Arc<CreateRateLimits>,
+        config: ApiConfig,
     ) -> Self {
-        ApiCtx { db_pool, send_task }
+        ApiCtx {
+            db_pool,
+            send_task,
+            send_cmd,
+            host_limiter,
+            task_limiter,
+            outbound,
+            throughput,
+            scheduling_error,
+            cancel_registry,
+            worker_alive,
+            metrics,
+            disk_space,
+            create_limits,
+            config,
+        }
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+/// Custom headers sent along with every request of a webhook task, mapping
+/// header names to values.
+pub(crate) type WebhookHeaders = std::collections::BTreeMap<String, String>;
+
+#[derive(Debug, Clone, serde::Serialize, sqlx::FromRow)]
 #[serde(rename_all = "snake_case")]
 pub(crate) struct ApiWebhook {
     pub(crate) id: String,
     pub(crate) state: String,
     pub(crate) execution_time: String,
+    pub(crate) method: String,
     pub(crate) url: String,
     pub(crate) body: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) idempotency_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) cron: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) calendar: Option<String>,
+    pub(crate) priority: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) shard_key: Option<String>,
+    pub(crate) max_retries: i64,
+    pub(crate) initial_backoff_ms: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) timeout_ms: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) max_latency_ms: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) signing_secret: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) headers: Option<sqlx::types::Json<WebhookHeaders>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) tags: Option<sqlx::types::Json<Vec<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) last_status_code: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) last_response_body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) last_latency_ms: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) created_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) started_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) completed_at: Option<String>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, sqlx::FromRow)]
 #[serde(rename_all = "snake_case")]
 pub(crate) struct ApiHash {
     pub(crate) id: String,
     pub(crate) state: String,
     pub(crate) execution_time: String,
     pub(crate) secret: String,
+    pub(crate) algorithm: String,
+    pub(crate) params: sqlx::types::Json<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) result: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) created_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) started_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) completed_at: Option<String>,
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, serde::Serialize)]
 #[serde(rename_all = "snake_case")]
 pub(crate) enum Task {
@@ -41,706 +269,6897 @@ pub(crate) enum Task {
     Hash(ApiHash),
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+impl Task {
+    pub(crate) fn id(&self) -> &str {
+        match self {
+            Task::Webhook(wh) => &wh.id,
+            Task::Hash(h) => &h.id,
+        }
+    }
+
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            Task::Webhook(_) => "webhook",
+            Task::Hash(_) => "hash",
+        }
+    }
+
+    pub(crate) fn execution_time(&self) -> &str {
+        match self {
+            Task::Webhook(wh) => &wh.execution_time,
+            Task::Hash(h) => &h.execution_time,
+        }
+    }
+
+    /// Returns the task's priority. Hash tasks have no priority of their own and
+    /// rank with the default priority of webhook tasks.
+    pub(crate) fn priority(&self) -> i64 {
+        match self {
+            Task::Webhook(wh) => wh.priority,
+            Task::Hash(_) => DEFAULT_PRIORITY,
+        }
+    }
+
+    /// Reformats the task's times with `precision` for a response.
+    fn with_time_precision(mut self, precision: TimePrecision) -> Self {
+        let (execution_time, transition_times) = match &mut self {
+            Task::Webhook(wh) => (
+                &mut wh.execution_time,
+                [&mut wh.created_at, &mut wh.started_at, &mut wh.completed_at],
+            ),
+            Task::Hash(h) => (
+                &mut h.execution_time,
+                [&mut h.created_at, &mut h.started_at, &mut h.completed_at],
+            ),
+        };
+        precision.reformat_task(execution_time, transition_times);
+        self
+    }
+}
+
+/// Number of seconds after which clients are asked to retry creating a task
+/// while the worker is not running.
+const WORKER_DOWN_RETRY_AFTER_SECS: u64 = 30;
+
+/// All states a task can be in.
+pub(crate) const TASK_STATES: [&str; 6] =
+    ["todo", "held", "in_progress", "failed", "done", "cancelled"];
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub(crate) enum ReqPostTasksNew {
     #[serde(alias = "Webhook", alias = "WebHook")]
-    Webhook {
-        execution_time: String,
-        url: String,
-        body: String,
-    },
+    Webhook(ReqWebhook),
     #[serde(alias = "Hash")]
     Hash {
-        execution_time: String,
+        #[serde(flatten)]
+        execution_time: ReqExecutionTime,
         secret: String,
+        #[serde(default)]
+        algorithm: crate::hashing::Algorithm,
+        params: Option<serde_json::Value>,
     },
 }
 
+impl ReqPostTasksNew {
+    fn type_name(&self) -> &'static str {
+        match self {
+            ReqPostTasksNew::Webhook(_) => "webhook",
+            ReqPostTasksNew::Hash { .. } => "hash",
+        }
+    }
+}
+
+/// The user-supplied fields of a new webhook task.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct ReqWebhook {
+    #[serde(flatten)]
+    execution_time: ReqExecutionTime,
+    method: Option<String>,
+    url: String,
+    body: Option<String>,
+    idempotency_key: Option<ReqIdempotencyKey>,
+    headers: Option<WebhookHeaders>,
+    max_retries: Option<u32>,
+    initial_backoff_ms: Option<u64>,
+    timeout_ms: Option<u64>,
+    max_latency_ms: Option<u64>,
+    signing_secret: Option<String>,
+    cron: Option<String>,
+    calendar: Option<String>,
+    priority: Option<u32>,
+    shard_key: Option<String>,
+    tags: Option<Vec<String>>,
+}
+
+/// The user-supplied idempotency key of a new webhook task, sent along with
+/// every delivery attempt of the task: either `true` for a key derived from the
+/// task's ID, `false` for no key, or a custom key.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub(crate) enum ReqIdempotencyKey {
+    Derived(bool),
+    Custom(String),
+}
+
+/// HTTP methods webhook tasks may use for their requests.
+const WEBHOOK_METHODS: [&str; 5] = ["GET", "POST", "PUT", "PATCH", "DELETE"];
+
+/// Maximum number of custom headers of a webhook task.
+const MAX_WEBHOOK_HEADERS: usize = 64;
+
+/// Makes sure the custom `headers` of a webhook task are non-empty ASCII and
+/// valid as HTTP header names and values, respectively.
+fn validate_headers(headers: &WebhookHeaders) -> std::result::Result<(), String> {
+    if headers.len() > MAX_WEBHOOK_HEADERS {
+        return Err(format!(
+            "field 'headers' must contain at most {MAX_WEBHOOK_HEADERS} headers"
+        ));
+    }
+    for (name, value) in headers {
+        if name.is_empty()
+            || !name.is_ascii()
+            || reqwest::header::HeaderName::from_bytes(name.as_bytes()).is_err()
+        {
+            return Err(format!(
+                "field 'headers' contains invalid header name '{name}'"
+            ));
+        }
+        if value.is_empty()
+            || !value.is_ascii()
+            || reqwest::header::HeaderValue::from_str(value).is_err()
+        {
+            return Err(format!(
+                "field 'headers' contains invalid value for header '{name}'"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Maximum length of a custom idempotency key.
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+/// The user-supplied fields determining when a new task is executed: either
+/// a concrete `execution_time`, or a range from `execution_time_min` to
+/// `execution_time_max` within which the execution time is picked at random.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct ReqExecutionTime {
+    #[serde(rename = "execution_time")]
+    exact: Option<String>,
+    #[serde(rename = "execution_time_min")]
+    min: Option<String>,
+    #[serde(rename = "execution_time_max")]
+    max: Option<String>,
+}
+
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, serde::Serialize)]
 #[serde(untagged, rename_all = "snake_case")]
 pub(crate) enum RespPostTasksNew {
-    Failure { msg: String },
-    Success { id: String },
+    Failure {
+        msg: String,
+    },
+    /// The ID of the created task, along with the task as stored, unless the
+    /// request was a repeated one.
+    Success {
+        id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        task: Option<Task>,
+    },
 }
 
+#[derive(Debug)]
 enum ApiTimeError {
     NotRfc3339(String),
     InPast(String),
+    Missing(String),
+    InvalidRange(String),
 }
 
 impl std::fmt::Display for ApiTimeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
         match self {
-            ApiTimeError::NotRfc3339(e) | ApiTimeError::InPast(e) => write!(f, "{e}"),
+            ApiTimeError::NotRfc3339(e)
+            | ApiTimeError::InPast(e)
+            | ApiTimeError::Missing(e)
+            | ApiTimeError::InvalidRange(e) => write!(f, "{e}"),
         }
     }
 }
 
-/// Parse the user-supplied datetime string according to RFC 3339 and, upon
-/// success, verify that the obtained datetime lies in the future as of now.
+/// Parse the user-supplied datetime string `time` according to RFC 3339. The
+/// error names the datetime's origin `what`, e.g., a field.
+fn parse_rfc3339(
+    what: &str,
+    time: &str,
+) -> std::result::Result<chrono::DateTime<chrono::FixedOffset>, ApiTimeError> {
+    chrono::DateTime::parse_from_rfc3339(time).map_err(|_| {
+        ApiTimeError::NotRfc3339(format!(
+            "{what} must contain a valid RFC 3339 datetime, \
+            including timezone, e.g.: '2026-01-30T15:30:00.123456789-06:00'"
+        ))
+    })
+}
+
+/// Parse the user-supplied datetime string in field `field` according to RFC
+/// 3339 and, upon success, verify that the obtained datetime lies in the
+/// future as of now.
 fn validate_execution_time(
+    field: &str,
     execution_time: &str,
+    tolerance: std::time::Duration,
 ) -> std::result::Result<chrono::DateTime<chrono::FixedOffset>, ApiTimeError> {
-    let Ok(execution_time) = chrono::DateTime::parse_from_rfc3339(execution_time) else {
-        return Err(ApiTimeError::NotRfc3339(
-            "field 'execution_time' must contain a valid RFC 3339 datetime, \
-            including timezone, e.g.: '2026-01-30T15:30:00.123456789-06:00'"
+    let execution_time = parse_rfc3339(&format!("field '{field}'"), execution_time)?;
+
+    // Determine how far into the future the earliest accepted execution time for
+    // this task lies. Times that passed no longer ago than `tolerance` are still
+    // accepted, in which case the task is handled right away.
+    let tolerance_millis = i64::try_from(tolerance.as_millis()).unwrap_or(i64::MAX);
+    if (execution_time - chrono::Utc::now().fixed_offset())
+        .num_milliseconds()
+        .saturating_add(tolerance_millis)
+        <= 0
+    {
+        return Err(ApiTimeError::InPast(format!(
+            "field '{field}' must contain a datetime that lies in the future"
+        )));
+    }
+
+    Ok(execution_time)
+}
+
+/// Validates the user-supplied range of execution times and picks a random
+/// execution time within it, with microsecond granularity. Both ends of the
+/// range are inclusive.
+fn pick_execution_time(
+    execution_time_min: &str,
+    execution_time_max: &str,
+    tolerance: std::time::Duration,
+) -> std::result::Result<chrono::DateTime<chrono::FixedOffset>, ApiTimeError> {
+    let min = validate_execution_time("execution_time_min", execution_time_min, tolerance)?;
+    let max = validate_execution_time("execution_time_max", execution_time_max, tolerance)?;
+    if min >= max {
+        return Err(ApiTimeError::InvalidRange(
+            "field 'execution_time_min' must contain a datetime that lies before \
+            the one in field 'execution_time_max'"
                 .to_string(),
         ));
-    };
+    }
 
-    // Determine how far into the future the earliest accepted execution time for
-    // this task lies.
-    if (execution_time - chrono::Utc::now().fixed_offset()).num_milliseconds() <= 0 {
-        return Err(ApiTimeError::InPast(
-            "field 'execution_time' must contain a datetime that lies in the future".to_string(),
+    let Some(span_micros) = (max - min).num_microseconds() else {
+        return Err(ApiTimeError::InvalidRange(
+            "range from 'execution_time_min' to 'execution_time_max' is too large".to_string(),
         ));
+    };
+    let offset_micros = rand::Rng::gen_range(&mut rand::thread_rng(), 0..=span_micros);
+
+    Ok(min + chrono::TimeDelta::microseconds(offset_micros))
+}
+
+/// Determines the execution time of a new task. If the user supplied one, it
+/// is validated via `validate_execution_time`. If the user supplied a range
+/// instead, a random execution time within it is picked. Otherwise, the task
+/// is executed after the configured `default_delay` for its type, if one is
+/// configured.
+fn resolve_execution_time(
+    req: &ReqExecutionTime,
+    default_delay: Option<std::time::Duration>,
+    tolerance: std::time::Duration,
+) -> std::result::Result<chrono::DateTime<chrono::FixedOffset>, ApiTimeError> {
+    match (req.exact.as_deref(), req.min.as_deref(), req.max.as_deref()) {
+        (Some(execution_time), None, None) => {
+            return validate_execution_time("execution_time", execution_time, tolerance);
+        }
+        (None, Some(min), Some(max)) => return pick_execution_time(min, max, tolerance),
+        (None, None, None) => {}
+        (Some(_), _, _) => {
+            return Err(ApiTimeError::InvalidRange(
+                "field 'execution_time' can't be combined with fields 'execution_time_min' \
+                and 'execution_time_max'"
+                    .to_string(),
+            ));
+        }
+        (None, _, _) => {
+            return Err(ApiTimeError::InvalidRange(
+                "fields 'execution_time_min' and 'execution_time_max' must be supplied together"
+                    .to_string(),
+            ));
+        }
     }
 
-    Ok(execution_time)
+    let Some(default_delay) = default_delay else {
+        return Err(ApiTimeError::Missing(
+            "field 'execution_time' must be supplied, as no default delay is configured \
+            for this task type"
+                .to_string(),
+        ));
+    };
+
+    chrono::TimeDelta::from_std(default_delay)
+        .ok()
+        .and_then(|delay| chrono::Utc::now().fixed_offset().checked_add_signed(delay))
+        .ok_or_else(|| {
+            ApiTimeError::InPast(
+                "default delay for this task type doesn't yield a valid execution time".to_string(),
+            )
+        })
 }
 
-#[allow(clippy::too_many_lines)]
-/// Handles the case that the submitted task is a webhook task.
-async fn post_tasks_new_webhook(
-    api_ctx: ApiCtx,
-    execution_time: String,
-    url: String,
-    body: String,
-) -> (axum::http::StatusCode, axum::Json<RespPostTasksNew>) {
-    // Parse field 'execution_time' from RFC 3339 format and validate it.
-    let execution_time = match validate_execution_time(&execution_time) {
-        Ok(t) => t,
-        Err(e) => {
-            return (
-                axum::http::StatusCode::BAD_REQUEST,
-                axum::Json(RespPostTasksNew::Failure {
-                    msg: format!("Malformed 'webhook': {e}"),
-                }),
-            );
+/// Parses field `execution_time` of a new webhook task from RFC 3339 format and
+/// validates it, picks one from the supplied range, or falls back to the
+/// default delay for webhook tasks. Recurring tasks without any of these fields
+/// first fire at the next time their `cron` expression matches, on a business
+/// day if `business_days` is set.
+fn resolve_webhook_execution_time(
+    config: &ApiConfig,
+    execution_time: &ReqExecutionTime,
+    cron: Option<&str>,
+    business_days: bool,
+) -> std::result::Result<chrono::DateTime<chrono::FixedOffset>, String> {
+    let calendar = business_days.then_some(&*config.business_calendar);
+    match cron {
+        Some(cron)
+            if execution_time.exact.is_none()
+                && execution_time.min.is_none()
+                && execution_time.max.is_none() =>
+        {
+            crate::worker::next_cron_run(cron, calendar, chrono::Utc::now())
+                .map(|t| t.fixed_offset())
         }
+        Some(cron) => {
+            crate::worker::next_cron_run(cron, calendar, chrono::Utc::now())?;
+            resolve_execution_time(
+                execution_time,
+                config.webhook_delay,
+                config.clock_skew_tolerance,
+            )
+            .map_err(|e| e.to_string())
+        }
+        None => resolve_execution_time(
+            execution_time,
+            config.webhook_delay,
+            config.clock_skew_tolerance,
+        )
+        .map_err(|e| e.to_string()),
+    }
+}
+
+/// Makes sure field 'body' of a new webhook task is not empty, or falls back to
+/// the default body for webhook tasks.
+fn resolve_webhook_body(
+    config: &ApiConfig,
+    body: Option<String>,
+) -> std::result::Result<String, String> {
+    match body.filter(|b| !b.is_empty()) {
+        Some(b) => Ok(b),
+        None => config.webhook_body.clone().ok_or_else(|| {
+            "Malformed 'webhook': field 'body' must contain a request body, \
+            as no default body is configured"
+                .to_string()
+        }),
+    }
+}
+
+/// Makes sure field 'calendar' of a new webhook task names a known calendar
+/// and is only set for recurring tasks. Returns the normalized name.
+fn validate_calendar(
+    calendar: Option<String>,
+    cron: Option<&str>,
+) -> std::result::Result<Option<String>, String> {
+    let Some(calendar) = calendar.map(|c| c.trim().to_lowercase()) else {
+        return Ok(None);
+    };
+    if calendar != crate::calendar::BUSINESS {
+        return Err(format!(
+            "field 'calendar' needs to be '{}'",
+            crate::calendar::BUSINESS
+        ));
+    }
+    if cron.is_none() {
+        return Err("field 'calendar' requires field 'cron' to be set".to_string());
+    }
+    Ok(Some(calendar))
+}
+
+/// Makes sure field 'priority' of a new webhook task is within bounds, or
+/// falls back to the default priority.
+fn resolve_priority(priority: Option<u32>) -> std::result::Result<i64, String> {
+    match priority {
+        Some(p) if p > MAX_PRIORITY => Err(format!(
+            "Malformed 'webhook': field 'priority' must lie between 0 and {MAX_PRIORITY}"
+        )),
+        Some(p) => Ok(i64::from(p)),
+        None => Ok(DEFAULT_PRIORITY),
+    }
+}
+
+/// Maximum length in bytes of the shard key of a webhook task.
+const MAX_SHARD_KEY_LEN: usize = 256;
+
+/// Trims surrounding whitespace off field `shard_key` of a new webhook task
+/// and makes sure it is neither empty nor too long.
+fn normalize_shard_key(shard_key: Option<String>) -> std::result::Result<Option<String>, String> {
+    let Some(shard_key) = shard_key.map(|k| k.trim().to_string()) else {
+        return Ok(None);
     };
-    let execution_time_str = execution_time.to_rfc3339();
+    if shard_key.is_empty() || shard_key.len() > MAX_SHARD_KEY_LEN {
+        return Err(format!(
+            "Malformed 'webhook': field 'shard_key' must contain between 1 and \
+            {MAX_SHARD_KEY_LEN} bytes"
+        ));
+    }
+    Ok(Some(shard_key))
+}
 
-    // Make sure field 'url' is not empty.
+/// Parses the URL of a webhook task and returns it in normalized form. URLs
+/// without a scheme (e.g. `example.com:8080/hook`) default to `http`. Only
+/// URLs with scheme `http` or `https` and a host are accepted.
+fn normalize_url(url: &str) -> std::result::Result<String, String> {
+    let url = url.trim();
     if url.is_empty() {
-        return (
-            axum::http::StatusCode::BAD_REQUEST,
-            axum::Json(RespPostTasksNew::Failure {
-                msg: "Malformed 'webhook': field 'url' must contain a URL".to_string(),
-            }),
-        );
+        return Err("field 'url' must contain a URL".to_string());
     }
 
-    // Prepend 'http://' to URL if it doesn't start with it already.
-    let url = if url.starts_with("http://") || url.starts_with("https://") {
-        url
+    let parsed = if url.contains("://") {
+        reqwest::Url::parse(url)
     } else {
-        format!("http://{url}")
-    };
+        reqwest::Url::parse(&format!("http://{url}"))
+    }
+    .map_err(|e| format!("field 'url' must contain a valid URL: {e}"))?;
 
-    // Make sure field 'body' is not empty.
-    if body.is_empty() {
-        return (
-            axum::http::StatusCode::BAD_REQUEST,
-            axum::Json(RespPostTasksNew::Failure {
-                msg: "Malformed 'webhook': field 'body' must contain a request body".to_string(),
-            }),
-        );
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!(
+            "field 'url' must use scheme 'http' or 'https', not '{}'",
+            parsed.scheme()
+        ));
+    }
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err("field 'url' must contain a host".to_string());
     }
 
-    // Generate a new UUIDv7 for this task.
-    let id = uuid::Uuid::now_v7();
-    let id_str = id.to_string();
-
-    // Insert new webhook task into database.
-    match sqlx::query!(
-        "INSERT INTO webhooks ( id, state, execution_time, url, body ) \
-        VALUES ( $1, $2, $3, $4, $5 );",
-        id_str,
-        "todo",
-        execution_time_str,
+    Ok(parsed.into())
+}
+
+/// Validates a new webhook task and prepares it for insertion into the
+/// database. Returns the message to report to the user if the task is invalid.
+fn prepare_webhook(config: &ApiConfig, req: ReqWebhook) -> std::result::Result<ApiWebhook, String> {
+    let ReqWebhook {
+        execution_time,
+        method,
         url,
         body,
+        idempotency_key,
+        headers,
+        max_retries,
+        initial_backoff_ms,
+        timeout_ms,
+        max_latency_ms,
+        signing_secret,
+        cron,
+        calendar,
+        priority,
+        shard_key,
+        tags,
+    } = req;
+
+    let calendar = validate_calendar(calendar, cron.as_deref())
+        .map_err(|e| format!("Malformed 'webhook': {e}"))?;
+    let execution_time = resolve_webhook_execution_time(
+        config,
+        &execution_time,
+        cron.as_deref(),
+        calendar.is_some(),
     )
-    .execute(&api_ctx.db_pool)
-    .await
-    {
-        Ok(_) => {}
-        Err(e) => match e {
-            sqlx::Error::Database(err_db) if err_db.is_unique_violation() => {
-                event!(
-                    Level::WARN,
-                    "Uniqueness criterion for UUIDv7 violated: {} already in database",
-                    id.to_string(),
-                );
-                return (
-                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                    axum::Json(RespPostTasksNew::Failure {
-                        msg: "Task with generated ID already exists in database".to_string(),
-                    }),
-                );
-            }
-            _ => {
-                event!(
-                    Level::WARN,
-                    "Inserting new webhook task into database failed: {e}"
-                );
-                return (
-                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                    axum::Json(RespPostTasksNew::Failure {
-                        msg: "Inserting new webhook task into database failed".to_string(),
-                    }),
-                );
-            }
-        },
+    .map_err(|e| format!("Malformed 'webhook': {e}"))?;
+
+    // Make sure field 'method' is one of the supported HTTP methods, or fall
+    // back to POST.
+    let method = method.map_or_else(|| "POST".to_string(), |m| m.to_uppercase());
+    if !WEBHOOK_METHODS.contains(&method.as_str()) {
+        return Err(format!(
+            "Malformed 'webhook': field 'method' needs to be one of: '{}'",
+            WEBHOOK_METHODS.join("', '")
+        ));
     }
 
-    let Ok(dur_from_now_millis) =
-        u64::try_from((execution_time - chrono::Utc::now().fixed_offset()).num_milliseconds())
-    else {
-        return (
-            axum::http::StatusCode::BAD_REQUEST,
-            axum::Json(RespPostTasksNew::Failure {
-                msg: "Malformed 'webhook': field 'execution_time' must contain a \
-                datetime that lies in the future"
-                    .to_string(),
-            }),
-        );
-    };
+    let url = normalize_url(&url).map_err(|e| format!("Malformed 'webhook': {e}"))?;
+    let body = resolve_webhook_body(config, body)?;
+    let priority = resolve_priority(priority)?;
 
-    if api_ctx
-        .send_task
-        .send((
-            tokio::time::Duration::from_millis(dur_from_now_millis),
-            Task::Webhook(ApiWebhook {
-                id: id_str,
-                state: "todo".to_string(),
-                execution_time: execution_time_str,
-                url,
-                body,
-            }),
-        ))
-        .await
-        .is_err()
-    {
-        event!(
-            Level::WARN,
-            "Sending new webhook task to delay queue failed"
-        );
-        return (
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            axum::Json(RespPostTasksNew::Failure {
-                msg: "Sending new webhook task to delay queue failed".to_string(),
-            }),
-        );
+    // Make sure the URL and body fit into the configured maximum lengths, so that
+    // the storage per task stays bounded.
+    check_webhook_lengths(config, &url, &body)?;
+
+    // Make sure all custom headers are valid. No headers are stored as none.
+    let headers = headers.filter(|h| !h.is_empty());
+    if let Some(headers) = &headers {
+        validate_headers(headers).map_err(|e| format!("Malformed 'webhook': {e}"))?;
     }
 
-    (
-        axum::http::StatusCode::CREATED,
-        axum::Json(RespPostTasksNew::Success { id: id.to_string() }),
+    // Normalize the tags, so that tags differing only in case or surrounding
+    // whitespace are considered the same. No tags are stored as none.
+    let tags = tags
+        .as_deref()
+        .map(normalize_tags)
+        .transpose()?
+        .filter(|t| !t.is_empty());
+
+    // Make sure the retry policy is within bounds, or inherit it from the policy
+    // of the task's tags, or fall back to the defaults.
+    let policy = crate::policy::Policy {
+        max_retries,
+        initial_backoff_ms,
+        timeout_ms,
+    }
+    .resolve(
+        config
+            .tag_policies
+            .for_tags(tags.as_deref().unwrap_or_default()),
     )
-}
+    .map_err(|e| format!("Malformed 'webhook': {e}"))?;
 
-#[allow(clippy::too_many_lines)]
-/// Handles the case that the submitted task is a hash task.
-async fn post_tasks_new_hash(
-    api_ctx: ApiCtx,
-    execution_time: String,
-    secret: String,
-) -> (axum::http::StatusCode, axum::Json<RespPostTasksNew>) {
-    // Parse field 'execution_time' from RFC 3339 format and validate it.
-    let execution_time = match validate_execution_time(&execution_time) {
-        Ok(t) => t,
-        Err(e) => {
-            return (
-                axum::http::StatusCode::BAD_REQUEST,
-                axum::Json(RespPostTasksNew::Failure {
-                    msg: format!("Malformed 'hash': {e}"),
-                }),
-            );
-        }
-    };
-    let execution_time_str = execution_time.to_rfc3339();
+    // Make sure the latency bound, if any, is within bounds.
+    if max_latency_ms.is_some_and(|l| !(1..=MAX_MAX_LATENCY_MS).contains(&l)) {
+        return Err(format!(
+            "Malformed 'webhook': field 'max_latency_ms' must lie between 1 and \
+            {MAX_MAX_LATENCY_MS}"
+        ));
+    }
 
-    // Make sure field 'secret' is not empty.
-    if secret.is_empty() {
-        return (
-            axum::http::StatusCode::BAD_REQUEST,
-            axum::Json(RespPostTasksNew::Failure {
-                msg: "Malformed 'hash': field 'secret' must contain a string".to_string(),
-            }),
-        );
+    // Make sure the signing secret, if any, is not empty, as an empty key would
+    // make the signature worthless.
+    if signing_secret.as_deref() == Some("") {
+        return Err("Malformed 'webhook': field 'signing_secret' must not be empty".to_string());
     }
 
     // Generate a new UUIDv7 for this task.
-    let id = uuid::Uuid::now_v7();
-    let id_str = id.to_string();
-
-    // Insert new hash task into database.
-    match sqlx::query!(
-        "INSERT INTO hashes ( id, state, execution_time, secret ) \
-        VALUES ( $1, $2, $3, $4 );",
-        id_str,
-        "todo",
-        execution_time_str,
-        secret,
-    )
-    .execute(&api_ctx.db_pool)
-    .await
+    let id = uuid::Uuid::now_v7().to_string();
+
+    // Make sure a custom idempotency key can be sent as a header value, or derive
+    // the key from the task's ID if requested.
+    let idempotency_key = resolve_idempotency_key(idempotency_key, &id)
+        .map_err(|e| format!("Malformed 'webhook': {e}"))?;
+
+    Ok(ApiWebhook {
+        id,
+        state: "todo".to_string(),
+        execution_time: execution_time.to_rfc3339(),
+        method,
+        url,
+        body,
+        idempotency_key,
+        cron,
+        calendar,
+        priority,
+        shard_key: normalize_shard_key(shard_key)?,
+        max_retries: i64::from(policy.max_retries),
+        initial_backoff_ms: i64::try_from(policy.initial_backoff_ms).unwrap_or(i64::MAX),
+        timeout_ms: Some(i64::try_from(policy.timeout_ms).unwrap_or(i64::MAX)),
+        max_latency_ms: max_latency_ms.and_then(|l| i64::try_from(l).ok()),
+        signing_secret,
+        headers: headers.map(sqlx::types::Json),
+        tags: tags.map(sqlx::types::Json),
+        last_status_code: None,
+        last_response_body: None,
+        last_latency_ms: None,
+        created_at: Some(crate::db::timestamp(chrono::Utc::now())),
+        started_at: None,
+        completed_at: None,
+    })
+}
+
+/// Maximum number of tags of a webhook task.
+const MAX_TAGS: usize = 16;
+
+/// Maximum length in bytes of a tag of a webhook task.
+const MAX_TAG_LEN: usize = 64;
+
+/// Normalizes a single tag by trimming surrounding whitespace and lowercasing
+/// it. Returns the reason if the tag is empty, too long, or contains characters
+/// other than lowercase ASCII letters, digits, `-`, `_`, `.`, `:`, and `/`.
+pub(crate) fn normalize_tag(tag: &str) -> std::result::Result<String, String> {
+    let tag = tag.trim().to_lowercase();
+    if tag.is_empty() {
+        return Err("tags must not be empty".to_string());
+    }
+    if tag.len() > MAX_TAG_LEN {
+        return Err(format!("tag '{tag}' must not exceed {MAX_TAG_LEN} bytes"));
+    }
+    if let Some(c) = tag
+        .chars()
+        .find(|c| !(c.is_ascii_lowercase() || c.is_ascii_digit() || "-_.:/".contains(*c)))
     {
-        Ok(_) => {}
-        Err(e) => match e {
-            sqlx::Error::Database(err_db) if err_db.is_unique_violation() => {
+        return Err(format!("tag '{tag}' must not contain character '{c}'"));
+    }
+    Ok(tag)
+}
+
+/// Normalizes the tags of a new webhook task via `normalize_tag` and removes
+/// duplicates, keeping the order in which tags first occur, as the first tag
+/// mapped to a policy determines the task's policy. Reports every invalid tag.
+fn normalize_tags(tags: &[String]) -> std::result::Result<Vec<String>, String> {
+    let mut normalized = Vec::with_capacity(tags.len());
+    let mut invalid = Vec::new();
+    for tag in tags {
+        match normalize_tag(tag) {
+            Ok(tag) if !normalized.contains(&tag) => normalized.push(tag),
+            Ok(_) => {}
+            Err(e) => invalid.push(e),
+        }
+    }
+
+    if !invalid.is_empty() {
+        return Err(format!(
+            "Malformed 'webhook': field 'tags' is invalid: {}",
+            invalid.join(", and ")
+        ));
+    }
+    if normalized.len() > MAX_TAGS {
+        return Err(format!(
+            "Malformed 'webhook': field 'tags' must not contain more than {MAX_TAGS} distinct tags"
+        ));
+    }
+
+    Ok(normalized)
+}
+
+/// Makes sure the `url` and `body` of a new webhook task don't exceed the
+/// configured maximum lengths, reporting every exceeded limit.
+fn check_webhook_lengths(
+    config: &ApiConfig,
+    url: &str,
+    body: &str,
+) -> std::result::Result<(), String> {
+    let mut exceeded = Vec::new();
+    if url.len() > config.max_webhook_url_len {
+        exceeded.push(format!(
+            "field 'url' must not exceed {} bytes",
+            config.max_webhook_url_len
+        ));
+    }
+    if body.len() > config.max_webhook_body_len {
+        exceeded.push(format!(
+            "field 'body' must not exceed {} bytes",
+            config.max_webhook_body_len
+        ));
+    }
+
+    if exceeded.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Malformed 'webhook': {}", exceeded.join(", and ")))
+    }
+}
+
+/// Turns the user-supplied idempotency key of a new webhook task with ID `id`
+/// into the key to store, making sure a custom key can be sent as a header
+/// value.
+fn resolve_idempotency_key(
+    idempotency_key: Option<ReqIdempotencyKey>,
+    id: &str,
+) -> std::result::Result<Option<String>, String> {
+    match idempotency_key {
+        None | Some(ReqIdempotencyKey::Derived(false)) => Ok(None),
+        Some(ReqIdempotencyKey::Derived(true)) => Ok(Some(id.to_string())),
+        Some(ReqIdempotencyKey::Custom(key)) => {
+            if key.is_empty()
+                || key.len() > MAX_IDEMPOTENCY_KEY_LEN
+                || !key.chars().all(|c| c.is_ascii_graphic())
+            {
+                return Err(format!(
+                    "field 'idempotency_key' must either be a boolean or contain between 1 and \
+                    {MAX_IDEMPOTENCY_KEY_LEN} visible ASCII characters"
+                ));
+            }
+            Ok(Some(key))
+        }
+    }
+}
+
+/// Validates a new hash task and prepares it for insertion into the database.
+/// Returns the message to report to the user if the task is invalid.
+fn prepare_hash(
+    config: &ApiConfig,
+    execution_time: &ReqExecutionTime,
+    secret: String,
+    algorithm: crate::hashing::Algorithm,
+    params: Option<serde_json::Value>,
+) -> std::result::Result<ApiHash, String> {
+    // Parse field 'execution_time' from RFC 3339 format and validate it, pick one
+    // from the supplied range, or fall back to the default delay for hash tasks.
+    let execution_time = resolve_execution_time(
+        execution_time,
+        config.hash_delay,
+        config.clock_skew_tolerance,
+    )
+    .map_err(|e| format!("Malformed 'hash': {e}"))?;
+
+    // Make sure field 'secret' is not empty.
+    if secret.is_empty() {
+        return Err("Malformed 'hash': field 'secret' must contain a string".to_string());
+    }
+    if algorithm == crate::hashing::Algorithm::Bcrypt
+        && secret.len() > crate::hashing::BCRYPT_MAX_SECRET_LEN
+    {
+        return Err(format!(
+            "Malformed 'hash': field 'secret' must not exceed {} bytes for algorithm 'bcrypt'",
+            crate::hashing::BCRYPT_MAX_SECRET_LEN
+        ));
+    }
+
+    // Validate field 'params' against the parameters of field 'algorithm' and the
+    // configured limits, and fill in defaults for any missing parameter.
+    let params = crate::hashing::normalize_params(algorithm, params, &config.hash_limits)
+        .map_err(|e| format!("Malformed 'hash': {e}"))?;
+
+    // Generate a new UUIDv7 for this task.
+    Ok(ApiHash {
+        id: uuid::Uuid::now_v7().to_string(),
+        state: "todo".to_string(),
+        execution_time: execution_time.to_rfc3339(),
+        secret,
+        algorithm: algorithm.as_str().to_string(),
+        params: sqlx::types::Json(params),
+        result: None,
+        created_at: Some(crate::db::timestamp(chrono::Utc::now())),
+        started_at: None,
+        completed_at: None,
+    })
+}
+
+/// Validates a new task (either webhook or hash) and prepares it for insertion
+/// into the respective database table.
+fn prepare_task(config: &ApiConfig, req: ReqPostTasksNew) -> std::result::Result<Task, String> {
+    match req {
+        ReqPostTasksNew::Webhook(req) => prepare_webhook(config, req).map(Task::Webhook),
+        ReqPostTasksNew::Hash {
+            execution_time,
+            secret,
+            algorithm,
+            params,
+        } => prepare_hash(config, &execution_time, secret, algorithm, params).map(Task::Hash),
+    }
+}
+
+/// Inserts a prepared task into the respective database table as part of the
+/// transaction `tx`.
+#[allow(clippy::too_many_lines)]
+pub(crate) async fn insert_task(
+    tx: &mut crate::db::DbTx,
+    task: &Task,
+) -> std::result::Result<(), sqlx::Error> {
+    match (tx, task) {
+        (crate::db::DbTx::Sqlite(tx), Task::Webhook(wh)) => {
+            sqlx::query!(
+                "INSERT INTO webhooks ( \
+                    id, state, execution_time, method, url, body, idempotency_key, cron, calendar, \
+                    priority, shard_key, max_retries, initial_backoff_ms, timeout_ms, \
+                    max_latency_ms, signing_secret, headers, tags, created_at \
+                ) \
+                VALUES ( \
+                    $1, $2, $3, $4, $5, $6, $7, $8, $9, \
+                    $10, $11, $12, $13, $14, $15, $16, $17, $18, $19 \
+                );",
+                wh.id,
+                wh.state,
+                wh.execution_time,
+                wh.method,
+                wh.url,
+                wh.body,
+                wh.idempotency_key,
+                wh.cron,
+                wh.calendar,
+                wh.priority,
+                wh.shard_key,
+                wh.max_retries,
+                wh.initial_backoff_ms,
+                wh.timeout_ms,
+                wh.max_latency_ms,
+                wh.signing_secret,
+                wh.headers,
+                wh.tags,
+                wh.created_at,
+            )
+            .execute(&mut **tx)
+            .await?;
+        }
+        (crate::db::DbTx::Sqlite(tx), Task::Hash(h)) => {
+            sqlx::query!(
+                "INSERT INTO hashes ( \
+                    id, state, execution_time, secret, algorithm, params, created_at \
+                ) \
+                VALUES ( $1, $2, $3, $4, $5, $6, $7 );",
+                h.id,
+                h.state,
+                h.execution_time,
+                h.secret,
+                h.algorithm,
+                h.params,
+                h.created_at,
+            )
+            .execute(&mut **tx)
+            .await?;
+        }
+        (crate::db::DbTx::Postgres(tx), Task::Webhook(wh)) => {
+            sqlx::query(
+                "INSERT INTO webhooks ( \
+                    id, state, execution_time, method, url, body, idempotency_key, cron, calendar, \
+                    priority, shard_key, max_retries, initial_backoff_ms, timeout_ms, \
+                    max_latency_ms, signing_secret, headers, tags, created_at \
+                ) \
+                VALUES ( \
+                    $1, $2, $3, $4, $5, $6, $7, $8, $9, \
+                    $10, $11, $12, $13, $14, $15, $16, $17, $18, $19 \
+                );",
+            )
+            .bind(&wh.id)
+            .bind(&wh.state)
+            .bind(&wh.execution_time)
+            .bind(&wh.method)
+            .bind(&wh.url)
+            .bind(&wh.body)
+            .bind(&wh.idempotency_key)
+            .bind(&wh.cron)
+            .bind(&wh.calendar)
+            .bind(wh.priority)
+            .bind(&wh.shard_key)
+            .bind(wh.max_retries)
+            .bind(wh.initial_backoff_ms)
+            .bind(wh.timeout_ms)
+            .bind(wh.max_latency_ms)
+            .bind(&wh.signing_secret)
+            .bind(&wh.headers)
+            .bind(&wh.tags)
+            .bind(&wh.created_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+        (crate::db::DbTx::Postgres(tx), Task::Hash(h)) => {
+            sqlx::query(
+                "INSERT INTO hashes ( \
+                    id, state, execution_time, secret, algorithm, params, created_at \
+                ) \
+                VALUES ( $1, $2, $3, $4, $5, $6, $7 );",
+            )
+            .bind(&h.id)
+            .bind(&h.state)
+            .bind(&h.execution_time)
+            .bind(&h.secret)
+            .bind(&h.algorithm)
+            .bind(&h.params)
+            .bind(&h.created_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Makes room for `task` in its database table if the table holds at least
+/// `max_rows` tasks, by evicting the oldest tasks that finished (`done`,
+/// `failed`, or `cancelled`). Pending tasks are never evicted. Returns whether
+/// the table has room for `task` afterwards.
+///
+/// Evicting first means the transaction starts out writing, so that it holds
+/// `SQLite`'s write lock while counting the remaining tasks. On `PostgreSQL`,
+/// the table is locked against concurrent writes explicitly instead.
+async fn make_room(
+    tx: &mut crate::db::DbTx,
+    task: &Task,
+    max_rows: i64,
+) -> std::result::Result<bool, sqlx::Error> {
+    let (evicted, rows) = match (tx, task) {
+        (crate::db::DbTx::Sqlite(tx), Task::Webhook(_)) => {
+            let evicted = sqlx::query!(
+                "DELETE FROM webhooks \
+                WHERE id IN ( \
+                    SELECT id FROM webhooks \
+                    WHERE state IN ( 'done', 'failed', 'cancelled' ) \
+                    ORDER BY created_at ASC, id ASC \
+                    LIMIT MAX(0, ( SELECT COUNT(*) FROM webhooks ) - $1 + 1) \
+                );",
+                max_rows,
+            )
+            .execute(&mut **tx)
+            .await?;
+            let rows = sqlx::query_scalar!("SELECT COUNT(*) FROM webhooks;")
+                .fetch_one(&mut **tx)
+                .await?;
+            (evicted.rows_affected(), rows)
+        }
+        (crate::db::DbTx::Sqlite(tx), Task::Hash(_)) => {
+            let evicted = sqlx::query!(
+                "DELETE FROM hashes \
+                WHERE id IN ( \
+                    SELECT id FROM hashes \
+                    WHERE state IN ( 'done', 'failed', 'cancelled' ) \
+                    ORDER BY created_at ASC, id ASC \
+                    LIMIT MAX(0, ( SELECT COUNT(*) FROM hashes ) - $1 + 1) \
+                );",
+                max_rows,
+            )
+            .execute(&mut **tx)
+            .await?;
+            let rows = sqlx::query_scalar!("SELECT COUNT(*) FROM hashes;")
+                .fetch_one(&mut **tx)
+                .await?;
+            (evicted.rows_affected(), rows)
+        }
+        (crate::db::DbTx::Postgres(tx), task) => {
+            let table = match task {
+                Task::Webhook(_) => "webhooks",
+                Task::Hash(_) => "hashes",
+            };
+            sqlx::query(&format!("LOCK TABLE {table} IN SHARE ROW EXCLUSIVE MODE;"))
+                .execute(&mut **tx)
+                .await?;
+            let evicted = sqlx::query(&format!(
+                "DELETE FROM {table} \
+                WHERE id IN ( \
+                    SELECT id FROM {table} \
+                    WHERE state IN ( 'done', 'failed', 'cancelled' ) \
+                    ORDER BY created_at ASC, id ASC \
+                    LIMIT GREATEST(0, ( SELECT COUNT(*) FROM {table} ) - $1 + 1) \
+                );"
+            ))
+            .bind(max_rows)
+            .execute(&mut **tx)
+            .await?;
+            let rows: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table};"))
+                .fetch_one(&mut **tx)
+                .await?;
+            (evicted.rows_affected(), rows)
+        }
+    };
+
+    if evicted > 0 {
+        event!(
+            Level::INFO,
+            "Evicted {evicted} finished {} tasks to stay within maximum of {max_rows} tasks",
+            task.type_name(),
+        );
+    }
+
+    Ok(rows < max_rows)
+}
+
+/// Inserts a prepared task as part of the transaction `tx`, after making room
+/// for it if a maximum number of tasks per type is configured. Returns the
+/// status and message to report to the user if the task can't be stored.
+async fn store_task(
+    tx: &mut crate::db::DbTx,
+    config: &ApiConfig,
+    task: &Task,
+) -> std::result::Result<(), (axum::http::StatusCode, String)> {
+    if let Some(max_rows) = config.max_rows_per_table {
+        let max_rows = i64::try_from(max_rows).unwrap_or(i64::MAX);
+        match make_room(tx, task, max_rows).await {
+            Ok(true) => {}
+            Ok(false) => {
                 event!(
                     Level::WARN,
-                    "Uniqueness criterion for UUIDv7 violated: {} already in database",
-                    id.to_string(),
-                );
-                return (
-                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                    axum::Json(RespPostTasksNew::Failure {
-                        msg: "Task with generated ID already exists in database".to_string(),
-                    }),
+                    "Rejecting new {} task, as the maximum of {max_rows} tasks is reached and \
+                    none finished",
+                    task.type_name(),
                 );
+                return Err((
+                    axum::http::StatusCode::INSUFFICIENT_STORAGE,
+                    format!(
+                        "Maximum number of {} tasks reached, no new ones are accepted until \
+                        some finish",
+                        task.type_name()
+                    ),
+                ));
             }
-            _ => {
+            Err(e) => return Err(insert_failure(task, &e)),
+        }
+    }
+
+    insert_task(tx, task)
+        .await
+        .map_err(|e| insert_failure(task, &e))
+}
+
+/// Logs why inserting `task` into the database failed and returns the status
+/// and message to report to the user.
+fn insert_failure(task: &Task, e: &sqlx::Error) -> (axum::http::StatusCode, String) {
+    match e {
+        sqlx::Error::Database(err_db) if err_db.is_unique_violation() => {
+            event!(
+                Level::WARN,
+                "Uniqueness criterion for UUIDv7 violated: {} already in database",
+                task.id(),
+            );
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Task with generated ID already exists in database".to_string(),
+            )
+        }
+        _ => {
+            event!(
+                Level::WARN,
+                "Inserting new {} task into database failed: {e}",
+                task.type_name(),
+            );
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!(
+                    "Inserting new {} task into database failed",
+                    task.type_name()
+                ),
+            )
+        }
+    }
+}
+
+/// Rejects the creation of a task of type `type_name` with `403 Forbidden` if
+/// that task type is disabled.
+fn check_task_type(
+    config: &ApiConfig,
+    type_name: &str,
+) -> std::result::Result<(), (axum::http::StatusCode, String)> {
+    if config.task_types.is_enabled(type_name) {
+        Ok(())
+    } else {
+        Err((
+            axum::http::StatusCode::FORBIDDEN,
+            format!("Tasks of type '{type_name}' are disabled"),
+        ))
+    }
+}
+
+/// Rejects the creation of new tasks while they can't be handled or stored
+/// safely: if the worker stopped running, or if disk space for the database is
+/// low. Returns the response to send in these cases.
+fn reject_new_tasks(api_ctx: &ApiCtx) -> Option<axum::response::Response> {
+    if !api_ctx
+        .worker_alive
+        .load(std::sync::atomic::Ordering::Acquire)
+    {
+        event!(
+            Level::WARN,
+            "Rejecting new task, as the worker is not running anymore"
+        );
+        return Some(
+            (
+                axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                [(
+                    axum::http::header::RETRY_AFTER,
+                    WORKER_DOWN_RETRY_AFTER_SECS.to_string(),
+                )],
+                axum::Json(RespPostTasksNew::Failure {
+                    msg: "Worker is not running, no new tasks are accepted until restart"
+                        .to_string(),
+                }),
+            )
+                .into_response(),
+        );
+    }
+
+    if api_ctx.disk_space.as_ref().is_some_and(|d| d.is_low()) {
+        event!(
+            Level::WARN,
+            "Rejecting new task, as disk space for the database is low"
+        );
+        return Some(
+            (
+                axum::http::StatusCode::INSUFFICIENT_STORAGE,
+                axum::Json(RespPostTasksNew::Failure {
+                    msg: "Disk space for the database is low, no new tasks are accepted"
+                        .to_string(),
+                }),
+            )
+                .into_response(),
+        );
+    }
+
+    None
+}
+
+/// Limits the rate at which tasks of a single type are created to `per_minute`
+/// tasks, allowing bursts of up to as many tasks. The budget is refilled
+/// continuously rather than once a minute, so that it recovers gradually.
+#[derive(Debug)]
+pub(crate) struct CreateRateLimiter {
+    per_minute: u32,
+    /// The remaining budget and when it was last refilled.
+    bucket: std::sync::Mutex<(f64, std::time::Instant)>,
+}
+
+impl CreateRateLimiter {
+    pub(crate) fn new(per_minute: u32) -> Self {
+        CreateRateLimiter {
+            per_minute,
+            bucket: std::sync::Mutex::new((f64::from(per_minute), std::time::Instant::now())),
+        }
+    }
+
+    /// Takes one task from the budget, or returns the time until the budget
+    /// allows another task if it is exhausted.
+    fn try_acquire(&self) -> std::result::Result<(), std::time::Duration> {
+        let mut bucket = self
+            .bucket
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let (budget, refilled_at) = &mut *bucket;
+
+        let now = std::time::Instant::now();
+        let per_sec = f64::from(self.per_minute) / 60.0;
+        *budget = (*budget + now.duration_since(*refilled_at).as_secs_f64() * per_sec)
+            .min(f64::from(self.per_minute));
+        *refilled_at = now;
+
+        if *budget >= 1.0 {
+            *budget -= 1.0;
+            Ok(())
+        } else {
+            Err(std::time::Duration::from_secs_f64(
+                (1.0 - *budget) / per_sec,
+            ))
+        }
+    }
+}
+
+/// The creation rate limits per task type, configured via CLI or ENV arguments.
+/// Task types without a limit can be created at any rate.
+#[derive(Debug, Default)]
+pub(crate) struct CreateRateLimits {
+    pub(crate) webhook: Option<CreateRateLimiter>,
+    pub(crate) hash: Option<CreateRateLimiter>,
+}
+
+impl CreateRateLimits {
+    /// Takes `task` from the budget of its type. If the budget is exhausted,
+    /// returns the number of seconds after which the client may retry and the
+    /// message to report to it.
+    fn try_acquire(&self, task: &Task) -> std::result::Result<(), (u64, String)> {
+        let limiter = match task {
+            Task::Webhook(_) => &self.webhook,
+            Task::Hash(_) => &self.hash,
+        };
+        let Some(limiter) = limiter else {
+            return Ok(());
+        };
+
+        limiter.try_acquire().map_err(|retry_after| {
+            let retry_after = retry_after.as_secs() + 1;
+            (
+                retry_after,
+                format!(
+                    "At most {} {} tasks may be created per minute, retry after {retry_after} \
+                    seconds",
+                    limiter.per_minute,
+                    task.type_name(),
+                ),
+            )
+        })
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct QueryTasksNew {
+    link: Option<String>,
+}
+
+/// Makes sure the signed `link` is valid and authorizes the creation of the
+/// prepared `task`. Returns the status and message to report to the user
+/// otherwise.
+fn check_link(
+    config: &ApiConfig,
+    link: &str,
+    task: &Task,
+) -> std::result::Result<(), (axum::http::StatusCode, String)> {
+    let Some(links) = &config.links else {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Signed links are not configured".to_string(),
+        ));
+    };
+
+    links
+        .verify(link)
+        .and_then(|grant| grant.authorizes(task))
+        .map_err(|e| {
+            event!(
+                Level::INFO,
+                "Rejecting new {} task created via signed link: {e}",
+                task.type_name(),
+            );
+            (
+                axum::http::StatusCode::FORBIDDEN,
+                format!("Signed link rejected: {e}"),
+            )
+        })
+}
+
+/// Inserts a new task (either webhook or hash) into the respective database
+/// table after light validation. Also places a task for the worker task onto
+/// the worker queue for handling at the specified execution time.
+///
+/// The database is the source of truth for all tasks: if inserting the task
+/// fails, nothing is queued and an error is returned. Once the insert
+/// succeeded, the task is considered created. Placing it onto the worker queue
+/// is best-effort only, as `reinsert_tasks` queues all `todo` tasks from the
+/// database upon the next start and thus acts as a backstop. The response
+/// carries the created task as stored next to its ID, so that callers see the
+/// normalized fields without fetching the task.
+///
+/// If the caller supplies query parameter `link`, the task is only created if
+/// the signed link is valid and authorizes a task of this shape, see
+/// `post_admin_links`.
+///
+/// If the caller supplies header `Idempotency-Key`, the key is recorded with
+/// the created task. Repeating the request with the same key for the same task
+/// type within `IDEMPOTENCY_KEY_TTL` doesn't create another task, but returns
+/// the ID of the original task with `200 OK` instead. Reusing the key for a
+/// different request results in `409 Conflict`.
+///
+/// The request body may be encoded as msgpack instead of JSON, if the
+/// caller sends header `Content-Type: application/msgpack`. Likewise, the
+/// response body is encoded as msgpack if the caller sends header
+/// `Accept: application/msgpack`.
+pub(crate) async fn post_tasks_new(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::extract::Query(query): axum::extract::Query<QueryTasksNew>,
+    headers: axum::http::HeaderMap,
+    JsonOrMsgPack(payload): JsonOrMsgPack<ReqPostTasksNew>,
+) -> axum::response::Response {
+    let resp = create_task(&api_ctx, &query, &headers, payload).await;
+    if accepts_msgpack(&headers) {
+        json_to_msgpack(resp).await
+    } else {
+        resp
+    }
+}
+
+/// Media type of msgpack-encoded bodies.
+const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+/// Returns whether the media type in header `name` of `headers` is msgpack.
+fn is_msgpack(headers: &axum::http::HeaderMap, name: axum::http::HeaderName) -> bool {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .filter_map(|v| v.split(';').next())
+        .any(|v| v.trim().eq_ignore_ascii_case(MSGPACK_CONTENT_TYPE))
+}
+
+/// Returns whether the caller asks for a msgpack-encoded response.
+fn accepts_msgpack(headers: &axum::http::HeaderMap) -> bool {
+    is_msgpack(headers, axum::http::header::ACCEPT)
+}
+
+/// Extracts a request body encoded as msgpack if the request carries header
+/// `Content-Type: application/msgpack`, and as JSON otherwise, with the same
+/// rejections as `axum::Json`.
+pub(crate) struct JsonOrMsgPack<T>(pub(crate) T);
+
+impl<T, S> axum::extract::FromRequest<S> for JsonOrMsgPack<T>
+where
+    T: serde::de::DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = axum::response::Response;
+
+    async fn from_request(req: axum::extract::Request, state: &S) -> Result<Self, Self::Rejection> {
+        if !is_msgpack(req.headers(), axum::http::header::CONTENT_TYPE) {
+            return <axum::Json<T> as axum::extract::FromRequest<S>>::from_request(req, state)
+                .await
+                .map(|axum::Json(payload)| JsonOrMsgPack(payload))
+                .map_err(IntoResponse::into_response);
+        }
+
+        let body = <axum::body::Bytes as axum::extract::FromRequest<S>>::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        rmp_serde::from_slice(&body)
+            .map(JsonOrMsgPack)
+            .map_err(|e| {
+                (
+                    axum::http::StatusCode::BAD_REQUEST,
+                    format!("Failed to deserialize the MessagePack body: {e}"),
+                )
+                    .into_response()
+            })
+    }
+}
+
+/// Re-encodes the JSON body of `resp` as msgpack. Responses without a JSON
+/// body are returned unchanged.
+async fn json_to_msgpack(resp: axum::response::Response) -> axum::response::Response {
+    let is_json = resp
+        .headers()
+        .get(axum::http::header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+    if !is_json {
+        return resp;
+    }
+
+    let (mut parts, body) = resp.into_parts();
+    let encoded = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(body) => serde_json::from_slice::<serde_json::Value>(&body)
+            .map_err(|e| e.to_string())
+            .and_then(|value| rmp_serde::to_vec_named(&value).map_err(|e| e.to_string())),
+        Err(e) => Err(e.to_string()),
+    };
+    match encoded {
+        Ok(body) => {
+            parts.headers.insert(
+                axum::http::header::CONTENT_TYPE,
+                axum::http::HeaderValue::from_static(MSGPACK_CONTENT_TYPE),
+            );
+            parts.headers.remove(axum::http::header::CONTENT_LENGTH);
+            axum::response::Response::from_parts(parts, axum::body::Body::from(body))
+        }
+        Err(e) => {
+            event!(Level::WARN, "Encoding response as MessagePack failed: {e}");
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Time for which the `Idempotency-Key` of a request that created a task is
+/// remembered. Expired keys are purged by the maintenance task.
+const IDEMPOTENCY_KEY_TTL: std::time::Duration = std::time::Duration::from_hours(24);
+
+/// Returns the time before which recorded idempotency keys have expired.
+pub(crate) fn idempotency_key_cutoff() -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::TimeDelta::from_std(IDEMPOTENCY_KEY_TTL)
+        .ok()
+        .and_then(|ttl| chrono::Utc::now().checked_sub_signed(ttl))
+}
+
+/// Extracts the optional `Idempotency-Key` header of a request creating a task.
+/// Returns the message to report to the user if the header is malformed.
+fn request_idempotency_key(
+    headers: &axum::http::HeaderMap,
+) -> std::result::Result<Option<String>, String> {
+    let Some(value) = headers.get("idempotency-key") else {
+        return Ok(None);
+    };
+
+    match value.to_str() {
+        Ok(key)
+            if !key.is_empty()
+                && key.len() <= MAX_IDEMPOTENCY_KEY_LEN
+                && key.chars().all(|c| c.is_ascii_graphic()) =>
+        {
+            Ok(Some(key.to_string()))
+        }
+        _ => Err(format!(
+            "Header 'Idempotency-Key' must contain between 1 and {MAX_IDEMPOTENCY_KEY_LEN} \
+            visible ASCII characters"
+        )),
+    }
+}
+
+/// Returns the fingerprint of the request creating the task described by
+/// `payload`, as the hex-encoded SHA-256 hash of its JSON encoding. The encoding
+/// is canonical, as fields are encoded in declaration order and maps sorted by
+/// key, so repeating a request yields the same fingerprint.
+fn request_fingerprint(payload: &ReqPostTasksNew) -> String {
+    let encoded = serde_json::to_vec(payload).unwrap_or_default();
+    <sha2::Sha256 as sha2::Digest>::digest(&encoded)
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = std::fmt::Write::write_fmt(&mut hex, format_args!("{byte:02x}"));
+            hex
+        })
+}
+
+/// The task created by an earlier request carrying an idempotency key.
+#[derive(sqlx::FromRow)]
+struct IdempotentTask {
+    task_id: String,
+    request_hash: Option<String>,
+}
+
+impl IdempotentTask {
+    /// Returns whether the earlier request matches the request with the given
+    /// `fingerprint`. Keys recorded without a fingerprint match any request.
+    fn matches(&self, fingerprint: &str) -> bool {
+        self.request_hash
+            .as_deref()
+            .is_none_or(|hash| hash == fingerprint)
+    }
+}
+
+/// Returns the task created by an earlier request carrying the idempotency
+/// `key` for a task of type `task_type`, if the key hasn't expired.
+async fn find_idempotent_task(
+    db_pool: &crate::db::DbPool,
+    task_type: &str,
+    key: &str,
+) -> std::result::Result<Option<IdempotentTask>, sqlx::Error> {
+    let cutoff = idempotency_key_cutoff()
+        .map(crate::db::timestamp)
+        .unwrap_or_default();
+    match db_pool {
+        crate::db::DbPool::Sqlite(db_pool) => {
+            sqlx::query_as!(
+                IdempotentTask,
+                "SELECT task_id, request_hash FROM idempotency_keys \
+                WHERE type = $1 AND key = $2 AND created_at >= $3;",
+                task_type,
+                key,
+                cutoff,
+            )
+            .fetch_optional(db_pool)
+            .await
+        }
+        crate::db::DbPool::Postgres(db_pool) => {
+            sqlx::query_as(
+                "SELECT task_id, request_hash FROM idempotency_keys \
+                WHERE type = $1 AND key = $2 AND created_at >= $3;",
+            )
+            .bind(task_type)
+            .bind(key)
+            .bind(cutoff)
+            .fetch_optional(db_pool)
+            .await
+        }
+    }
+}
+
+/// Records the idempotency `key` for the new `task` along with the
+/// `fingerprint` of the request as part of the transaction `tx`, replacing the
+/// key if it has expired. Returns `false` if the key is still recorded for
+/// another task, i.e., a concurrent request with the same key created a task in
+/// the meantime.
+async fn record_idempotency_key(
+    tx: &mut crate::db::DbTx,
+    task: &Task,
+    key: &str,
+    fingerprint: &str,
+) -> std::result::Result<bool, sqlx::Error> {
+    let task_type = task.type_name();
+    let task_id = task.id();
+    let created_at = crate::db::timestamp(chrono::Utc::now());
+    let cutoff = idempotency_key_cutoff()
+        .map(crate::db::timestamp)
+        .unwrap_or_default();
+    let recorded = match tx {
+        crate::db::DbTx::Sqlite(tx) => sqlx::query!(
+            "INSERT INTO idempotency_keys ( type, key, task_id, created_at, request_hash ) \
+            VALUES ( $1, $2, $3, $4, $5 ) \
+            ON CONFLICT ( type, key ) DO UPDATE \
+            SET task_id = excluded.task_id, created_at = excluded.created_at, \
+            request_hash = excluded.request_hash \
+            WHERE idempotency_keys.created_at < $6;",
+            task_type,
+            key,
+            task_id,
+            created_at,
+            fingerprint,
+            cutoff,
+        )
+        .execute(&mut **tx)
+        .await?
+        .rows_affected(),
+        crate::db::DbTx::Postgres(tx) => sqlx::query(
+            "INSERT INTO idempotency_keys ( type, key, task_id, created_at, request_hash ) \
+            VALUES ( $1, $2, $3, $4, $5 ) \
+            ON CONFLICT ( type, key ) DO UPDATE \
+            SET task_id = excluded.task_id, created_at = excluded.created_at, \
+            request_hash = excluded.request_hash \
+            WHERE idempotency_keys.created_at < $6;",
+        )
+        .bind(task_type)
+        .bind(key)
+        .bind(task_id)
+        .bind(created_at)
+        .bind(fingerprint)
+        .bind(cutoff)
+        .execute(&mut **tx)
+        .await?
+        .rows_affected(),
+    };
+
+    Ok(recorded > 0)
+}
+
+/// Responds to a repeated request creating a task with the ID of the task the
+/// original request created. If the key was used for a different request than
+/// the one with the given `fingerprint`, responds with `409 Conflict` instead.
+fn idempotent_replay(original: IdempotentTask, fingerprint: &str) -> axum::response::Response {
+    if !original.matches(fingerprint) {
+        return (
+            axum::http::StatusCode::CONFLICT,
+            axum::Json(RespPostTasksNew::Failure {
+                msg: "Header 'Idempotency-Key' was already used for a different request"
+                    .to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    (
+        axum::http::StatusCode::OK,
+        axum::Json(RespPostTasksNew::Success {
+            id: original.task_id,
+            task: None,
+        }),
+    )
+        .into_response()
+}
+
+/// Stores the prepared `task` and records its idempotency `key`, if any, along
+/// with the `fingerprint` of the request in a single transaction. Returns the
+/// task created for `key` by another request if that request won the race for
+/// the key, in which case `task` is not stored.
+async fn store_new_task(
+    api_ctx: &ApiCtx,
+    task: &Task,
+    key: Option<&str>,
+    fingerprint: &str,
+) -> std::result::Result<Option<IdempotentTask>, (axum::http::StatusCode, String)> {
+    let mut tx = crate::db::retry_transient(|| api_ctx.db_pool.begin())
+        .await
+        .map_err(|e| insert_failure(task, &e))?;
+    store_task(&mut tx, &api_ctx.config, task).await?;
+
+    if let Some(key) = key
+        && !record_idempotency_key(&mut tx, task, key, fingerprint)
+            .await
+            .map_err(|e| insert_failure(task, &e))?
+    {
+        drop(tx);
+        return match find_idempotent_task(&api_ctx.db_pool, task.type_name(), key).await {
+            Ok(Some(original)) => Ok(Some(original)),
+            Ok(None) | Err(_) => Err((
+                axum::http::StatusCode::CONFLICT,
+                "Another request with the same 'Idempotency-Key' is in progress".to_string(),
+            )),
+        };
+    }
+
+    tx.commit().await.map_err(|e| insert_failure(task, &e))?;
+    Ok(None)
+}
+
+/// Time after which a notification about a created task is aborted.
+const CREATION_NOTIFY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Summary of a created task sent to the URL configured to be notified of
+/// created tasks.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+struct TaskCreated {
+    id: String,
+    #[serde(rename = "type")]
+    task_type: &'static str,
+    execution_time: String,
+}
+
+/// Notifies an external system of every created task by sending a summary of
+/// the task in a POST request to a configured URL.
+#[derive(Debug, Clone)]
+pub(crate) struct CreationNotifier {
+    url: reqwest::Url,
+    client: reqwest::Client,
+}
+
+impl CreationNotifier {
+    pub(crate) fn new(url: reqwest::Url) -> Self {
+        CreationNotifier {
+            url,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Sends the notification about the created `task` in the background, so
+    /// that the creating request doesn't wait for it. Notifications are
+    /// best-effort: failures are logged, but not retried.
+    fn notify(&self, task: &Task) {
+        let created = TaskCreated {
+            id: task.id().to_string(),
+            task_type: task.type_name(),
+            execution_time: task.execution_time().to_string(),
+        };
+        let body = match serde_json::to_vec(&created) {
+            Ok(b) => b,
+            Err(e) => {
                 event!(
                     Level::WARN,
-                    "Inserting new hash task into database failed: {e}"
-                );
-                return (
-                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                    axum::Json(RespPostTasksNew::Failure {
-                        msg: "Inserting new hash task into database failed".to_string(),
-                    }),
+                    "Serializing created task '{}' failed: {e}",
+                    created.id
                 );
+                return;
             }
-        },
+        };
+        let request = self
+            .client
+            .post(self.url.clone())
+            .timeout(CREATION_NOTIFY_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body);
+
+        tokio::task::spawn(async move {
+            match request
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+            {
+                Ok(_) => {}
+                Err(e) => event!(
+                    Level::WARN,
+                    "Notifying of created task '{}' failed: {e}",
+                    created.id
+                ),
+            }
+        });
+    }
+}
+
+/// Notifies the configured URL of the created `task`, if any.
+fn notify_created(api_ctx: &ApiCtx, task: &Task) {
+    if let Some(notifier) = &api_ctx.config.on_task_created {
+        notifier.notify(task);
+    }
+}
+
+/// Creates the task described by `payload` as in `post_tasks_new`, which also
+/// applies to tasks created from templates.
+async fn create_task(
+    api_ctx: &ApiCtx,
+    query: &QueryTasksNew,
+    headers: &axum::http::HeaderMap,
+    payload: ReqPostTasksNew,
+) -> axum::response::Response {
+    if let Err((status, msg)) = check_task_type(&api_ctx.config, payload.type_name()) {
+        return (status, axum::Json(RespPostTasksNew::Failure { msg })).into_response();
     }
 
-    let Ok(dur_from_now_millis) =
-        u64::try_from((execution_time - chrono::Utc::now().fixed_offset()).num_milliseconds())
-    else {
+    let key = match request_idempotency_key(headers) {
+        Ok(k) => k,
+        Err(msg) => {
+            return (
+                axum::http::StatusCode::BAD_REQUEST,
+                axum::Json(RespPostTasksNew::Failure { msg }),
+            )
+                .into_response();
+        }
+    };
+
+    // Repeated requests return the original task, even if it was since handled.
+    // Reusing a key for a different request is a conflict instead.
+    let fingerprint = request_fingerprint(&payload);
+    if let Some(key) = &key {
+        match find_idempotent_task(&api_ctx.db_pool, payload.type_name(), key).await {
+            Ok(Some(original)) => return idempotent_replay(original, &fingerprint),
+            Ok(None) => {}
+            Err(e) => {
+                event!(Level::WARN, "Looking up idempotency key failed: {e}");
+                return (
+                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    axum::Json(RespPostTasksNew::Failure {
+                        msg: "Looking up idempotency key failed".to_string(),
+                    }),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    if let Some(rejection) = reject_new_tasks(api_ctx) {
+        return rejection;
+    }
+
+    let task = match prepare_task(&api_ctx.config, payload) {
+        Ok(t) => t,
+        Err(msg) => {
+            return (
+                axum::http::StatusCode::BAD_REQUEST,
+                axum::Json(RespPostTasksNew::Failure { msg }),
+            )
+                .into_response();
+        }
+    };
+
+    if let Some(link) = &query.link
+        && let Err((status, msg)) = check_link(&api_ctx.config, link, &task)
+    {
+        return (status, axum::Json(RespPostTasksNew::Failure { msg })).into_response();
+    }
+
+    if let Err((retry_after, msg)) = api_ctx.create_limits.try_acquire(&task) {
+        return (
+            axum::http::StatusCode::TOO_MANY_REQUESTS,
+            [(axum::http::header::RETRY_AFTER, retry_after.to_string())],
+            axum::Json(RespPostTasksNew::Failure { msg }),
+        )
+            .into_response();
+    }
+
+    match store_new_task(api_ctx, &task, key.as_deref(), &fingerprint).await {
+        Ok(None) => {}
+        Ok(Some(original)) => return idempotent_replay(original, &fingerprint),
+        Err((status, msg)) => {
+            return (status, axum::Json(RespPostTasksNew::Failure { msg })).into_response();
+        }
+    }
+
+    // The row is persisted at this point, which makes the database the source of
+    // truth for this task. Handing the task to the delay queue is best-effort: if
+    // it fails, the task will still be picked up by `reinsert_tasks` upon the next
+    // start, so we report successful creation either way. An execution time that
+    // passed in the meantime results in immediate handling.
+    let id = task.id().to_string();
+    let created = task
+        .clone()
+        .with_time_precision(api_ctx.config.time_precision);
+    notify_created(api_ctx, &task);
+    queue_task(api_ctx, task).await;
+
+    (
+        axum::http::StatusCode::CREATED,
+        axum::Json(RespPostTasksNew::Success {
+            id,
+            task: Some(created),
+        }),
+    )
+        .into_response()
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespPostTasksValidate {
+    Failure { msg: String },
+    Valid { task: Task },
+}
+
+/// Validates a new task exactly as `post_tasks_new` does and returns it as it
+/// would be stored, with all defaults filled in and all fields normalized,
+/// without inserting it into the database or queueing it for the worker. The
+/// task's `id` is freshly generated for the preview only, so creating the task
+/// afterwards assigns another one. Checks depending on the state of the
+/// scheduler rather than on the task, such as rate limits, idempotency keys,
+/// and free disk space, are not performed.
+///
+/// As for `post_tasks_new`, the request and response bodies may be encoded as
+/// msgpack instead of JSON.
+pub(crate) async fn post_tasks_validate(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    headers: axum::http::HeaderMap,
+    JsonOrMsgPack(payload): JsonOrMsgPack<ReqPostTasksNew>,
+) -> axum::response::Response {
+    let resp = match check_task_type(&api_ctx.config, payload.type_name()).and_then(|()| {
+        prepare_task(&api_ctx.config, payload)
+            .map_err(|msg| (axum::http::StatusCode::BAD_REQUEST, msg))
+    }) {
+        Ok(task) => (
+            axum::http::StatusCode::OK,
+            axum::Json(RespPostTasksValidate::Valid {
+                task: task.with_time_precision(api_ctx.config.time_precision),
+            }),
+        ),
+        Err((status, msg)) => (status, axum::Json(RespPostTasksValidate::Failure { msg })),
+    }
+    .into_response();
+    if accepts_msgpack(&headers) {
+        json_to_msgpack(resp).await
+    } else {
+        resp
+    }
+}
+
+/// Maximum length of the name of a template.
+const MAX_TEMPLATE_NAME_LEN: usize = 64;
+
+/// Fields of a task a template must not specify, as they are supplied anew for
+/// every task created from the template.
+const TEMPLATE_EXCLUDED_FIELDS: [&str; 3] =
+    ["execution_time", "execution_time_min", "execution_time_max"];
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct ReqPostTemplates {
+    name: String,
+    task: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespPostTemplates {
+    Failure { msg: String },
+    Success { name: String },
+}
+
+/// Validates a new template and splits its task into the task's type and its
+/// fields. The task has the same format as for `POST /tasks/new`, minus the
+/// execution time.
+fn prepare_template(
+    req: ReqPostTemplates,
+) -> std::result::Result<(String, String, serde_json::Value), String> {
+    if req.name.is_empty()
+        || req.name.len() > MAX_TEMPLATE_NAME_LEN
+        || !req
+            .name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+    {
+        return Err(format!(
+            "Field 'name' must consist of 1 to {MAX_TEMPLATE_NAME_LEN} ASCII letters, digits, '-', or '_'"
+        ));
+    }
+
+    let mut entries = req.task.into_iter();
+    let (Some((task_type, fields)), None) = (entries.next(), entries.next()) else {
+        return Err("Field 'task' must specify exactly one task".to_string());
+    };
+    let Some(field_map) = fields.as_object() else {
+        return Err(format!("Fields of {task_type} task must be an object"));
+    };
+    if let Some(field) = TEMPLATE_EXCLUDED_FIELDS
+        .iter()
+        .find(|f| field_map.contains_key(**f))
+    {
+        return Err(format!("Template must not specify field '{field}'"));
+    }
+
+    // Make sure the template describes a task of a known type with well-formed
+    // fields. Constraints depending on the execution time are only checked once
+    // a task is created from the template.
+    let task = serde_json::json!({ task_type.clone(): fields.clone() });
+    let task_type = match serde_json::from_value::<ReqPostTasksNew>(task) {
+        Ok(ReqPostTasksNew::Webhook(_)) => "webhook",
+        Ok(ReqPostTasksNew::Hash { .. }) => "hash",
+        Err(e) => return Err(format!("Field 'task' is invalid: {e}")),
+    };
+
+    Ok((req.name, task_type.to_string(), fields))
+}
+
+/// Stores a named template of a task (webhook or hash), from which tasks of the
+/// same shape can be created via `post_tasks_new_from_template`, supplying only
+/// the execution time and the fields to override. Template names are unique.
+pub(crate) async fn post_templates(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::Json(payload): axum::Json<ReqPostTemplates>,
+) -> (axum::http::StatusCode, axum::Json<RespPostTemplates>) {
+    let (name, task_type, fields) = match prepare_template(payload) {
+        Ok(t) => t,
+        Err(msg) => {
+            return (
+                axum::http::StatusCode::BAD_REQUEST,
+                axum::Json(RespPostTemplates::Failure { msg }),
+            );
+        }
+    };
+
+    let fields = fields.to_string();
+    let created_at = crate::db::timestamp(chrono::Utc::now());
+    let inserted = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => sqlx::query!(
+                "INSERT INTO templates ( name, type, fields, created_at ) \
+                VALUES ( $1, $2, $3, $4 );",
+                name,
+                task_type,
+                fields,
+                created_at,
+            )
+            .execute(db_pool)
+            .await
+            .map(|_| ()),
+            crate::db::DbPool::Postgres(db_pool) => sqlx::query(
+                "INSERT INTO templates ( name, type, fields, created_at ) \
+                VALUES ( $1, $2, $3, $4 );",
+            )
+            .bind(&name)
+            .bind(&task_type)
+            .bind(&fields)
+            .bind(&created_at)
+            .execute(db_pool)
+            .await
+            .map(|_| ()),
+        }
+    })
+    .await;
+    match inserted {
+        Ok(()) => (
+            axum::http::StatusCode::CREATED,
+            axum::Json(RespPostTemplates::Success { name }),
+        ),
+        Err(sqlx::Error::Database(err_db)) if err_db.is_unique_violation() => (
+            axum::http::StatusCode::CONFLICT,
+            axum::Json(RespPostTemplates::Failure {
+                msg: format!("Template '{name}' already exists"),
+            }),
+        ),
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Inserting template '{name}' into database failed: {e}"
+            );
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespPostTemplates::Failure {
+                    msg: format!("Inserting template '{name}' into database failed"),
+                }),
+            )
+        }
+    }
+}
+
+/// Creates a new task from the template `name`. The payload is an object of
+/// fields that are set on top of the template's fields, which must include the
+/// execution time and may override any other field. The resulting task is then
+/// validated and created exactly as via `post_tasks_new`.
+pub(crate) async fn post_tasks_new_from_template(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::extract::Path(name): axum::extract::Path<String>,
+    axum::extract::Query(query): axum::extract::Query<QueryTasksNew>,
+    headers: axum::http::HeaderMap,
+    axum::Json(overrides): axum::Json<serde_json::Map<String, serde_json::Value>>,
+) -> axum::response::Response {
+    let template = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query!("SELECT type, fields FROM templates WHERE name = $1;", name,)
+                    .fetch_optional(db_pool)
+                    .await
+                    .map(|t| t.map(|t| (t.r#type, t.fields)))
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_as::<_, (String, String)>(
+                    "SELECT type, fields FROM templates WHERE name = $1;",
+                )
+                .bind(&name)
+                .fetch_optional(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    let (template_type, template_fields) = match template {
+        Ok(Some(t)) => t,
+        Ok(None) => {
+            return (
+                axum::http::StatusCode::NOT_FOUND,
+                axum::Json(RespPostTasksNew::Failure {
+                    msg: format!("Template '{name}' does not exist"),
+                }),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Retrieving template '{name}' from database failed: {e}"
+            );
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespPostTasksNew::Failure {
+                    msg: format!("Retrieving template '{name}' failed"),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    // Templates were validated upon storing, so their fields are always an object.
+    let mut fields =
+        serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&template_fields)
+            .unwrap_or_default();
+    fields.extend(overrides);
+
+    let task = serde_json::json!({ template_type: fields });
+    match serde_json::from_value::<ReqPostTasksNew>(task) {
+        Ok(payload) => create_task(&api_ctx, &query, &headers, payload).await,
+        Err(e) => (
+            axum::http::StatusCode::BAD_REQUEST,
+            axum::Json(RespPostTasksNew::Failure {
+                msg: format!("Task from template '{name}' is invalid: {e}"),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Maximum number of items accepted in a single batch request.
+const MAX_BATCH_SIZE: usize = 1000;
+
+/// The outcome for a single item of a batch request, identified by its index
+/// within the batch.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespBatchItem {
+    Failure { index: usize, msg: String },
+    Success { index: usize, id: String },
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespBatch {
+    Failure {
+        msg: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        index: Option<usize>,
+    },
+    Items(Vec<RespBatchItem>),
+}
+
+/// Rejects batches that are empty or exceed `MAX_BATCH_SIZE` items.
+fn check_batch_size(len: usize) -> std::result::Result<(), (axum::http::StatusCode, RespBatch)> {
+    if len == 0 || len > MAX_BATCH_SIZE {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            RespBatch::Failure {
+                msg: format!("Batch must contain between 1 and {MAX_BATCH_SIZE} items"),
+                index: None,
+            },
+        ));
+    }
+    Ok(())
+}
+
+/// Stores `task` as an item of a batch as part of the transaction `tx`, see
+/// `store_task`. Failing to store it only rolls back the item itself, so that
+/// the transaction can go on with the remaining items.
+async fn store_batch_item(
+    tx: &mut crate::db::DbTx,
+    config: &ApiConfig,
+    task: &Task,
+) -> std::result::Result<(), (axum::http::StatusCode, String)> {
+    tx.savepoint().await.map_err(|e| insert_failure(task, &e))?;
+    let stored = store_task(tx, config, task).await;
+    if stored.is_err() {
+        tx.rollback_to_savepoint()
+            .await
+            .map_err(|e| insert_failure(task, &e))?;
+    }
+    stored
+}
+
+/// Inserts a batch of new tasks (webhook or hash, mixed freely) within a single
+/// database transaction and returns the outcome for each item. Each item is
+/// validated as in `post_tasks_new`.
+///
+/// If the API is configured for atomic batches, the first item that fails
+/// validation or insertion rolls back the whole batch, and the failure is
+/// reported along with the index of the causing item. Otherwise, failing items
+/// are reported individually, while all other items are created.
+pub(crate) async fn post_tasks_new_batch(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::Json(payload): axum::Json<Vec<ReqPostTasksNew>>,
+) -> axum::response::Response {
+    if let Some(rejection) = reject_new_tasks(&api_ctx) {
+        return rejection;
+    }
+    if let Err((status, resp)) = check_batch_size(payload.len()) {
+        return (status, axum::Json(resp)).into_response();
+    }
+
+    let mut tx = match crate::db::retry_transient(|| api_ctx.db_pool.begin()).await {
+        Ok(tx) => tx,
+        Err(e) => {
+            event!(Level::WARN, "Starting transaction for batch failed: {e}");
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespBatch::Failure {
+                    msg: "Starting transaction for batch failed".to_string(),
+                    index: None,
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let mut items = Vec::with_capacity(payload.len());
+    let mut created = Vec::with_capacity(payload.len());
+    for (index, req) in payload.into_iter().enumerate() {
+        let prepared = check_task_type(&api_ctx.config, req.type_name()).and_then(|()| {
+            prepare_task(&api_ctx.config, req)
+                .map_err(|msg| (axum::http::StatusCode::BAD_REQUEST, msg))
+        });
+        let outcome = match prepared {
+            Ok(task) => match api_ctx.create_limits.try_acquire(&task) {
+                Ok(()) => store_batch_item(&mut tx, &api_ctx.config, &task)
+                    .await
+                    .map(|()| task),
+                Err((_, msg)) => Err((axum::http::StatusCode::TOO_MANY_REQUESTS, msg)),
+            },
+            Err(e) => Err(e),
+        };
+
+        match outcome {
+            Ok(task) => {
+                items.push(RespBatchItem::Success {
+                    index,
+                    id: task.id().to_string(),
+                });
+                created.push(task);
+            }
+            // Dropping the transaction rolls back all items inserted so far.
+            Err((status, msg)) if api_ctx.config.batch_atomic => {
+                return (
+                    status,
+                    axum::Json(RespBatch::Failure {
+                        msg: format!("Item {index} of batch failed, no task was created: {msg}"),
+                        index: Some(index),
+                    }),
+                )
+                    .into_response();
+            }
+            Err((_, msg)) => items.push(RespBatchItem::Failure { index, msg }),
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        event!(Level::WARN, "Committing transaction for batch failed: {e}");
+        return (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(RespBatch::Failure {
+                msg: "Committing transaction for batch failed, no task was created".to_string(),
+                index: None,
+            }),
+        )
+            .into_response();
+    }
+
+    // As for single tasks, queueing the now persisted tasks is best-effort.
+    for task in created {
+        notify_created(&api_ctx, &task);
+        queue_task(&api_ctx, task).await;
+    }
+
+    (
+        axum::http::StatusCode::OK,
+        axum::Json(RespBatch::Items(items)),
+    )
+        .into_response()
+}
+
+/// Deletes a single task (webhook or hash) as part of a batch, unless it is
+/// currently in progress. Returns the type of the deleted task and the state
+/// it was in, if it was deleted.
+async fn delete_batch_item(
+    tx: &mut crate::db::DbTx,
+    id: &str,
+) -> std::result::Result<Option<(&'static str, String)>, sqlx::Error> {
+    tx.savepoint().await?;
+    let deleted = match tx {
+        crate::db::DbTx::Sqlite(tx) => {
+            let webhook = sqlx::query_scalar!(
+                "DELETE FROM webhooks WHERE id = $1 AND state != 'in_progress' RETURNING state;",
+                id,
+            )
+            .fetch_optional(&mut **tx)
+            .await;
+            match webhook {
+                Ok(Some(state)) => Ok(Some(("webhook", state))),
+                Ok(None) => sqlx::query_scalar!(
+                    "DELETE FROM hashes WHERE id = $1 AND state != 'in_progress' RETURNING state;",
+                    id,
+                )
+                .fetch_optional(&mut **tx)
+                .await
+                .map(|hash| hash.map(|state| ("hash", state))),
+                Err(e) => Err(e),
+            }
+        }
+        crate::db::DbTx::Postgres(tx) => {
+            let webhook = sqlx::query_scalar(
+                "DELETE FROM webhooks WHERE id = $1 AND state != 'in_progress' RETURNING state;",
+            )
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await;
+            match webhook {
+                Ok(Some(state)) => Ok(Some(("webhook", state))),
+                Ok(None) => sqlx::query_scalar(
+                    "DELETE FROM hashes WHERE id = $1 AND state != 'in_progress' RETURNING state;",
+                )
+                .bind(id)
+                .fetch_optional(&mut **tx)
+                .await
+                .map(|hash| hash.map(|state| ("hash", state))),
+                Err(e) => Err(e),
+            }
+        }
+    };
+    if deleted.is_err() {
+        tx.rollback_to_savepoint().await?;
+    }
+    deleted
+}
+
+/// Deletes a batch of tasks (webhook or hash) identified by the supplied IDs
+/// within a single database transaction and returns the outcome for each item.
+/// As for `delete_task`, tasks in state `in_progress` can't be deleted.
+///
+/// If the API is configured for atomic batches, the first task that doesn't
+/// exist or can't be deleted rolls back the whole batch, and the failure is
+/// reported along with the index of the causing item. Otherwise, failing items
+/// are reported individually, while all other tasks are deleted.
+pub(crate) async fn post_tasks_delete_batch(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::Json(ids): axum::Json<Vec<String>>,
+) -> (axum::http::StatusCode, axum::Json<RespBatch>) {
+    if let Err((status, resp)) = check_batch_size(ids.len()) {
+        return (status, axum::Json(resp));
+    }
+
+    let mut tx = match crate::db::retry_transient(|| api_ctx.db_pool.begin()).await {
+        Ok(tx) => tx,
+        Err(e) => {
+            event!(Level::WARN, "Starting transaction for batch failed: {e}");
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespBatch::Failure {
+                    msg: "Starting transaction for batch failed".to_string(),
+                    index: None,
+                }),
+            );
+        }
+    };
+
+    let mut items = Vec::with_capacity(ids.len());
+    let mut deleted = Vec::new();
+    for (index, id) in ids.into_iter().enumerate() {
+        let outcome = match delete_batch_item(&mut tx, &id).await {
+            Ok(Some((task_type, state))) => {
+                deleted.push((task_type, id.clone(), state));
+                Ok(())
+            }
+            Ok(None) => Err((
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Task '{id}' does not exist or is in progress"),
+            )),
+            Err(e) => {
+                event!(
+                    Level::WARN,
+                    "Deleting task '{id}' in batch failed with: {e}"
+                );
+                Err((
+                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Deleting task '{id}' failed"),
+                ))
+            }
+        };
+
+        match outcome {
+            Ok(()) => items.push(RespBatchItem::Success { index, id }),
+            // Dropping the transaction rolls back all deletions so far.
+            Err((status, msg)) if api_ctx.config.batch_atomic => {
+                return (
+                    status,
+                    axum::Json(RespBatch::Failure {
+                        msg: format!("Item {index} of batch failed, no task was deleted: {msg}"),
+                        index: Some(index),
+                    }),
+                );
+            }
+            Err((_, msg)) => items.push(RespBatchItem::Failure { index, msg }),
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        event!(Level::WARN, "Committing transaction for batch failed: {e}");
+        return (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(RespBatch::Failure {
+                msg: "Committing transaction for batch failed, no task was deleted".to_string(),
+                index: None,
+            }),
+        );
+    }
+
+    for (task_type, id, state) in &deleted {
+        audit_api(
+            task_type,
+            id,
+            state,
+            crate::audit::DELETED,
+            "deleted in batch",
+        );
+    }
+
+    (
+        axum::http::StatusCode::OK,
+        axum::Json(RespBatch::Items(items)),
+    )
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum RespGetTask {
+    Failure { msg: String },
+    Webhook(ApiWebhook),
+    Hash(ApiHash),
+}
+
+impl RespGetTask {
+    /// Wraps `task` for a response, with its times formatted with `precision`.
+    fn task(task: Task, precision: TimePrecision) -> Self {
+        match task.with_time_precision(precision) {
+            Task::Webhook(wh) => RespGetTask::Webhook(wh),
+            Task::Hash(h) => RespGetTask::Hash(h),
+        }
+    }
+}
+
+/// Fetches all details about the specified task (webhook or hash) from the
+/// respective table. We rely on the property that collisions when generating
+/// UUIDs are exceedingly unlikely, and can thus be ignored. If we thus find the
+/// task in the `webhooks` table, we do not query the `hashes` table anymore. If
+/// we also do not find the task in the `hashes` table, we report this fact to
+/// the caller.
+async fn fetch_task(
+    api_ctx: &ApiCtx,
+    id: &str,
+) -> std::result::Result<Task, (axum::http::StatusCode, String)> {
+    let fetched = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => sqlx::query_as!(
+                ApiWebhook,
+                "SELECT id, state, execution_time, method, url, body, idempotency_key, cron, calendar, \
+                max_retries, initial_backoff_ms, timeout_ms, max_latency_ms, signing_secret, \
+                priority, shard_key, last_status_code, last_response_body, last_latency_ms, \
+                created_at, started_at, completed_at, \
+                headers AS \"headers: sqlx::types::Json<WebhookHeaders>\", \
+                tags AS \"tags: sqlx::types::Json<Vec<String>>\" \
+                FROM webhooks \
+                WHERE id = $1;",
+                id,
+            )
+            .fetch_optional(db_pool)
+            .await,
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_as(&format!(
+                    "SELECT {} \
+                    FROM webhooks \
+                    WHERE id = $1;",
+                    crate::db::PG_WEBHOOK_COLUMNS
+                ))
+                .bind(id)
+                .fetch_optional(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    match fetched {
+        Ok(Some(webhook)) => return Ok(Task::Webhook(webhook)),
+        Ok(None) => {}
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Fetching task '{id}' from 'webhooks' table failed: {e}"
+            );
+            return Err((
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Fetching task '{id}' from 'webhooks' table failed"),
+            ));
+        }
+    }
+
+    let fetched = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_as!(
+                    ApiHash,
+                    "SELECT id, state, execution_time, secret, algorithm, \
+                    params AS \"params: sqlx::types::Json<serde_json::Value>\", result, \
+                    created_at, started_at, completed_at \
+                    FROM hashes \
+                    WHERE id = $1;",
+                    id,
+                )
+                .fetch_optional(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_as(&format!(
+                    "SELECT {} \
+                    FROM hashes \
+                    WHERE id = $1;",
+                    crate::db::PG_HASH_COLUMNS
+                ))
+                .bind(id)
+                .fetch_optional(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    match fetched {
+        Ok(Some(hash)) => Ok(Task::Hash(hash)),
+        Ok(None) => Err((
+            axum::http::StatusCode::NOT_FOUND,
+            format!("Task '{id}' does not exist"),
+        )),
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Fetching task '{id}' from 'hashes' table failed: {e}"
+            );
+            Err((
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Fetching task '{id}' from 'hashes' table failed"),
+            ))
+        }
+    }
+}
+
+/// Returns all details about the specified task (webhook or hash).
+pub(crate) async fn get_task(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> (axum::http::StatusCode, axum::Json<RespGetTask>) {
+    match fetch_task(&api_ctx, &id).await {
+        Ok(task) => (
+            axum::http::StatusCode::OK,
+            axum::Json(RespGetTask::task(task, api_ctx.config.time_precision)),
+        ),
+        Err((status, msg)) => (status, axum::Json(RespGetTask::Failure { msg })),
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespGetTaskDebug {
+    Failure {
+        msg: String,
+    },
+    Debug {
+        task: Box<Task>,
+        queued: bool,
+        queued_until: Option<String>,
+        handling: bool,
+    },
+}
+
+/// Returns the stored task merged with the worker's runtime state for it, for
+/// diagnosing why a task did or didn't fire. The runtime state consists of the
+/// time at which the delay queue will yield the task, if it is queued, and
+/// whether a handler is currently working on the task. Webhook handlers are
+/// tracked by the worker, while a hash task has an active handler exactly when
+/// it is in state `in_progress`, as such tasks are reset to `todo` upon start.
+///
+/// Requires the configured admin token as bearer token in header
+/// `Authorization`.
+pub(crate) async fn get_task_debug(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    headers: axum::http::HeaderMap,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> (axum::http::StatusCode, axum::Json<RespGetTaskDebug>) {
+    if let Err((status, msg)) = check_admin(&api_ctx.config, &headers) {
+        return (status, axum::Json(RespGetTaskDebug::Failure { msg }));
+    }
+
+    let task = match fetch_task(&api_ctx, &id).await {
+        Ok(t) => t,
+        Err((status, msg)) => return (status, axum::Json(RespGetTaskDebug::Failure { msg })),
+    };
+
+    // Ask the worker for the deadline of the task in its delay queue. If the
+    // worker doesn't reply, it isn't running and thus has nothing queued.
+    let (send_reply, recv_reply) = tokio::sync::oneshot::channel();
+    let deadline = match api_ctx
+        .send_cmd
+        .send(crate::worker::WorkerCmd::Inspect {
+            id: id.clone(),
+            reply: send_reply,
+        })
+        .await
+    {
+        Ok(()) => recv_reply.await.ok().flatten(),
+        Err(_) => None,
+    };
+
+    // Translate the deadline into wall-clock time.
+    let queued_until = deadline.and_then(|deadline| {
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        chrono::TimeDelta::from_std(remaining)
+            .ok()
+            .and_then(|remaining| chrono::Utc::now().checked_add_signed(remaining))
+            .map(|t| api_ctx.config.time_precision.format(&t))
+    });
+
+    let handling = match &task {
+        Task::Webhook(_) => api_ctx.cancel_registry.is_registered(&id),
+        Task::Hash(h) => h.state == "in_progress",
+    };
+
+    (
+        axum::http::StatusCode::OK,
+        axum::Json(RespGetTaskDebug::Debug {
+            task: Box::new(task.with_time_precision(api_ctx.config.time_precision)),
+            queued: deadline.is_some(),
+            queued_until,
+            handling,
+        }),
+    )
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespGetTaskEta {
+    Failure {
+        msg: String,
+    },
+    Eta {
+        execution_time: String,
+        ahead: i64,
+        completions_per_sec: f64,
+        eta: Option<String>,
+    },
+}
+
+/// Estimates when a task in state `todo` will run. Counts the pending (`todo`
+/// or `in_progress`) tasks of any type scheduled before it, ties broken by ID,
+/// and extrapolates how long the worker takes to handle them at its recent
+/// throughput, as in `get_admin_drain_estimate`. A task never runs before its
+/// execution time, thus the later of both is the estimate. If tasks are ahead
+/// of the task but the worker didn't finish any task recently, no estimate can
+/// be given.
+#[allow(clippy::cast_precision_loss)]
+pub(crate) async fn get_task_eta(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> (axum::http::StatusCode, axum::Json<RespGetTaskEta>) {
+    let task = match fetch_task(&api_ctx, &id).await {
+        Ok(t) => t,
+        Err((status, msg)) => return (status, axum::Json(RespGetTaskEta::Failure { msg })),
+    };
+
+    let state = match &task {
+        Task::Webhook(wh) => &wh.state,
+        Task::Hash(h) => &h.state,
+    };
+    if state != "todo" {
+        return (
+            axum::http::StatusCode::CONFLICT,
+            axum::Json(RespGetTaskEta::Failure {
+                msg: format!("Task '{id}' is in state '{state}', not waiting to run"),
+            }),
+        );
+    }
+
+    // Execution times are compared as points in time, as they are stored in the
+    // offset they were supplied in.
+    let execution_time = task.execution_time().to_string();
+    let fetched = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_scalar!(
+                    "SELECT \
+                        (SELECT COUNT(*) FROM webhooks \
+                        WHERE (state = 'todo' OR state = 'in_progress') \
+                        AND (julianday(execution_time), id) < (julianday($1), $2)) \
+                        + \
+                        (SELECT COUNT(*) FROM hashes \
+                        WHERE (state = 'todo' OR state = 'in_progress') \
+                        AND (julianday(execution_time), id) < (julianday($1), $2)) \
+                    AS \"ahead!: i64\";",
+                    execution_time,
+                    id,
+                )
+                .fetch_one(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_scalar(
+                    "SELECT \
+                        (SELECT COUNT(*) FROM webhooks \
+                        WHERE (state = 'todo' OR state = 'in_progress') \
+                        AND (execution_time::TIMESTAMPTZ, id) < ($1::TIMESTAMPTZ, $2)) \
+                        + \
+                        (SELECT COUNT(*) FROM hashes \
+                        WHERE (state = 'todo' OR state = 'in_progress') \
+                        AND (execution_time::TIMESTAMPTZ, id) < ($1::TIMESTAMPTZ, $2)) \
+                    AS ahead;",
+                )
+                .bind(&execution_time)
+                .bind(&id)
+                .fetch_one(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    let ahead = match fetched {
+        Ok(n) => n,
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Failed to count tasks ahead of task '{id}' in database: {e}"
+            );
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespGetTaskEta::Failure {
+                    msg: format!("Failed to count tasks ahead of task '{id}' in database"),
+                }),
+            );
+        }
+    };
+
+    // Extrapolate the time it takes to handle all tasks ahead at the recent rate.
+    let completions_per_sec = api_ctx.throughput.per_sec();
+    let backlog_secs = if ahead == 0 {
+        Some(0.0)
+    } else if completions_per_sec > 0.0 {
+        Some(ahead as f64 / completions_per_sec)
+    } else {
+        None
+    };
+    let scheduled = chrono::DateTime::parse_from_rfc3339(&execution_time).ok();
+    let eta = backlog_secs
+        .and_then(|secs| std::time::Duration::try_from_secs_f64(secs).ok())
+        .and_then(|dur| chrono::TimeDelta::from_std(dur).ok())
+        .and_then(|delta| chrono::Utc::now().fixed_offset().checked_add_signed(delta))
+        .and_then(|drained| scheduled.map(|s| s.max(drained)))
+        .map(|t| api_ctx.config.time_precision.format(&t));
+
+    (
+        axum::http::StatusCode::OK,
+        axum::Json(RespGetTaskEta::Eta {
+            execution_time: scheduled
+                .map_or(execution_time, |t| api_ctx.config.time_precision.format(&t)),
+            ahead,
+            completions_per_sec,
+            eta,
+        }),
+    )
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct QueryTasksList {
+    url_contains: Option<String>,
+    fields: Option<String>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+}
+
+/// Number of tasks returned by list endpoints if no `limit` is supplied.
+const DEFAULT_PAGE_LIMIT: u64 = 100;
+
+/// Maximum number of tasks list endpoints return at once.
+const MAX_PAGE_LIMIT: u64 = 1000;
+
+/// The slice of all matching tasks a list endpoint returns: at most `limit`
+/// tasks, after skipping the first `offset` ones.
+#[derive(Debug, Clone, Copy)]
+struct Page {
+    limit: i64,
+    offset: i64,
+}
+
+impl Page {
+    /// Parses the `limit` and `offset` query parameters of list endpoints, or
+    /// falls back to the first page of default size.
+    fn from_query(query: &QueryTasksList) -> std::result::Result<Self, String> {
+        Page::parse(query.limit, query.offset)
+    }
+
+    fn parse(limit: Option<u64>, offset: Option<u64>) -> std::result::Result<Self, String> {
+        let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+        if limit > MAX_PAGE_LIMIT {
+            return Err(format!(
+                "Query parameter 'limit' must lie between 0 and {MAX_PAGE_LIMIT}"
+            ));
+        }
+        Ok(Page {
+            limit: i64::try_from(limit).unwrap_or(i64::MAX),
+            offset: i64::try_from(offset.unwrap_or(0)).unwrap_or(i64::MAX),
+        })
+    }
+
+    /// Splits this page across two lists returned one after the other, of which
+    /// the first holds `first_total` tasks. Returns the page to take from each
+    /// list.
+    fn split(self, first_total: i64) -> (Page, Page) {
+        let first = Page {
+            limit: self.limit.min((first_total - self.offset).max(0)),
+            offset: self.offset,
+        };
+        let second = Page {
+            limit: self.limit - first.limit,
+            offset: (self.offset - first_total).max(0),
+        };
+        (first, second)
+    }
+}
+
+/// A page of the tasks of a list endpoint, along with the applied `limit` and
+/// `offset` and the `total` number of matching tasks, so that clients can
+/// request the remaining pages.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct TasksPage<T> {
+    tasks: Vec<T>,
+    limit: i64,
+    offset: i64,
+    total: i64,
+}
+
+impl<T> TasksPage<T> {
+    fn new(tasks: Vec<T>, page: Page, total: i64) -> Self {
+        TasksPage {
+            tasks,
+            limit: page.limit,
+            offset: page.offset,
+            total,
+        }
+    }
+
+    /// Applies `f` to each task of the page.
+    fn map_tasks(mut self, f: impl FnMut(T) -> T) -> Self {
+        self.tasks = self.tasks.into_iter().map(f).collect();
+        self
+    }
+}
+
+/// Maximum time in milliseconds a webhook task may allow its request to take
+/// before a response counts as failed.
+const MAX_MAX_LATENCY_MS: u64 = 60 * 60 * 1000;
+
+/// Highest priority of a webhook task. Priorities range from 0 to this value.
+const MAX_PRIORITY: u32 = 9;
+
+/// Priority of webhook tasks that don't specify one.
+pub(crate) const DEFAULT_PRIORITY: i64 = 5;
+
+/// Columns of webhook tasks that may be selected via the `fields` query
+/// parameter of list endpoints.
+const WEBHOOK_FIELDS: &[&str] = &[
+    "id",
+    "state",
+    "execution_time",
+    "method",
+    "url",
+    "body",
+    "idempotency_key",
+    "cron",
+    "calendar",
+    "priority",
+    "shard_key",
+    "max_retries",
+    "initial_backoff_ms",
+    "timeout_ms",
+    "max_latency_ms",
+    "signing_secret",
+    "headers",
+    "tags",
+    "last_status_code",
+    "last_response_body",
+    "last_latency_ms",
+    "created_at",
+    "started_at",
+    "completed_at",
+];
+
+/// Columns of hash tasks that may be selected via the `fields` query parameter
+/// of list endpoints.
+const HASH_FIELDS: &[&str] = &[
+    "id",
+    "state",
+    "execution_time",
+    "secret",
+    "algorithm",
+    "params",
+    "result",
+    "created_at",
+    "started_at",
+    "completed_at",
+];
+
+/// Parses the user-supplied comma-separated list of `fields` to return for
+/// each task of a list endpoint. Every field must be contained in one of the
+/// `allowed` lists, and the returned names are taken from these lists, so that
+/// only allowlisted names ever end up in a query. Duplicates are dropped.
+fn parse_fields(
+    fields: &str,
+    allowed: &[&[&'static str]],
+) -> std::result::Result<Vec<&'static str>, String> {
+    let mut parsed = Vec::new();
+    for field in fields.split(',').map(str::trim) {
+        let Some(field) = allowed.iter().flat_map(|a| a.iter()).find(|a| **a == field) else {
+            let mut all = Vec::new();
+            for a in allowed.iter().flat_map(|a| a.iter()) {
+                if !all.contains(a) {
+                    all.push(*a);
+                }
+            }
+            return Err(format!(
+                "Query parameter 'fields' needs to be a comma-separated list of: '{}'",
+                all.join("', '")
+            ));
+        };
+        if !parsed.contains(field) {
+            parsed.push(*field);
+        }
+    }
+    Ok(parsed)
+}
+
+/// Returns the conditions restricting a list endpoint to tasks in `state` and
+/// to tasks whose URL matches `url_pattern`, if supplied. The respective values
+/// need to be bound in this order.
+fn list_conditions(
+    db_pool: &crate::db::DbPool,
+    state: Option<&str>,
+    url_pattern: Option<&str>,
+) -> String {
+    let mut conditions = vec!["TRUE".to_string()];
+    if state.is_some() {
+        conditions.push(format!("state = ${}", conditions.len()));
+    }
+    if url_pattern.is_some() {
+        conditions.push(format!(
+            "url {} ${} ESCAPE '\\'",
+            db_pool.like_operator(),
+            conditions.len()
+        ));
+    }
+    conditions.join(" AND ")
+}
+
+/// Counts all tasks in `table`, optionally restricted to those in `state` and
+/// those whose URL matches `url_pattern`.
+async fn count_tasks(
+    db_pool: &crate::db::DbPool,
+    table: &str,
+    state: Option<&str>,
+    url_pattern: Option<&str>,
+) -> std::result::Result<i64, sqlx::Error> {
+    let sql = format!(
+        "SELECT COUNT(*) FROM {table} WHERE {};",
+        list_conditions(db_pool, state, url_pattern)
+    );
+
+    match db_pool {
+        crate::db::DbPool::Sqlite(db_pool) => {
+            let mut query = sqlx::query_scalar::<_, i64>(&sql);
+            if let Some(state) = state {
+                query = query.bind(state);
+            }
+            if let Some(url_pattern) = url_pattern {
+                query = query.bind(url_pattern);
+            }
+            query.fetch_one(db_pool).await
+        }
+        crate::db::DbPool::Postgres(db_pool) => {
+            let mut query = sqlx::query_scalar::<_, i64>(&sql);
+            if let Some(state) = state {
+                query = query.bind(state);
+            }
+            if let Some(url_pattern) = url_pattern {
+                query = query.bind(url_pattern);
+            }
+            query.fetch_one(db_pool).await
+        }
+    }
+}
+
+/// Collects the selected `fields` of a task from `row` into a JSON object.
+/// Fields that are not set for the task are skipped.
+fn row_fields<R>(row: &R, fields: &[&str]) -> std::result::Result<serde_json::Value, sqlx::Error>
+where
+    R: sqlx::Row,
+    for<'a> &'a str: sqlx::ColumnIndex<R>,
+    for<'r> i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    for<'r> String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    for<'r> sqlx::types::Json<serde_json::Value>:
+        sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    let mut task = serde_json::Map::new();
+    for field in fields {
+        let value = match *field {
+            // The delivery settings of webhook tasks are stored as integers.
+            "max_retries" | "initial_backoff_ms" | "timeout_ms" | "max_latency_ms"
+            | "last_status_code" | "last_latency_ms" => row
+                .try_get::<Option<i64>, _>(*field)?
+                .map(serde_json::Value::from),
+            // Hash parameters, webhook headers, and webhook tags are stored as JSON
+            // and returned as such.
+            "params" | "headers" | "tags" => row
+                .try_get::<Option<sqlx::types::Json<serde_json::Value>>, _>(*field)?
+                .map(|v| v.0),
+            _ => row
+                .try_get::<Option<String>, _>(*field)?
+                .map(serde_json::Value::String),
+        };
+        if let Some(value) = value {
+            task.insert((*field).to_string(), value);
+        }
+    }
+    Ok(serde_json::Value::Object(task))
+}
+
+/// Retrieves only the selected `fields` of the `page` of tasks in `table`,
+/// optionally restricted to those in `state` and those whose URL matches
+/// `url_pattern`, ordered by their execution time. Fields not among the
+/// `table`'s `columns` are skipped, as are fields that are not set for a task.
+async fn select_fields(
+    db_pool: &crate::db::DbPool,
+    table: &str,
+    columns: &[&str],
+    fields: &[&str],
+    state: Option<&str>,
+    url_pattern: Option<&str>,
+    page: Page,
+) -> std::result::Result<Vec<serde_json::Value>, sqlx::Error> {
+    let fields = fields
+        .iter()
+        .copied()
+        .filter(|f| columns.contains(f))
+        .collect::<Vec<_>>();
+
+    // The page is selected via the parameters following the ones of the
+    // conditions.
+    let num_conditions = usize::from(state.is_some()) + usize::from(url_pattern.is_some());
+    let sql = format!(
+        "SELECT {} FROM {table} WHERE {} ORDER BY execution_time ASC LIMIT ${} OFFSET ${};",
+        if fields.is_empty() {
+            "NULL".to_string()
+        } else {
+            fields.join(", ")
+        },
+        list_conditions(db_pool, state, url_pattern),
+        num_conditions + 1,
+        num_conditions + 2,
+    );
+
+    match db_pool {
+        crate::db::DbPool::Sqlite(db_pool) => {
+            let mut query = sqlx::query(&sql);
+            if let Some(state) = state {
+                query = query.bind(state);
+            }
+            if let Some(url_pattern) = url_pattern {
+                query = query.bind(url_pattern);
+            }
+            query = query.bind(page.limit).bind(page.offset);
+            query
+                .fetch_all(db_pool)
+                .await?
+                .iter()
+                .map(|row| row_fields(row, &fields))
+                .collect()
+        }
+        crate::db::DbPool::Postgres(db_pool) => {
+            let mut query = sqlx::query(&sql);
+            if let Some(state) = state {
+                query = query.bind(state);
+            }
+            if let Some(url_pattern) = url_pattern {
+                query = query.bind(url_pattern);
+            }
+            query = query.bind(page.limit).bind(page.offset);
+            query
+                .fetch_all(db_pool)
+                .await?
+                .iter()
+                .map(|row| row_fields(row, &fields))
+                .collect()
+        }
+    }
+}
+
+/// Turns the optional user-supplied `url_contains` substring into a pattern
+/// for a `LIKE` comparison with `ESCAPE '\'`. Characters with a special
+/// meaning in `LIKE` patterns are escaped, so that the substring is matched
+/// literally. Without a substring, the pattern matches every URL. Note that a
+/// `LIKE` comparison with a leading wildcard cannot make use of an index and
+/// thus always scans the whole `webhooks` table.
+fn url_like_pattern(url_contains: Option<&str>) -> String {
+    let mut pattern = String::from("%");
+    for c in url_contains.unwrap_or_default().chars() {
+        if c == '\\' || c == '%' || c == '_' {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespGetTasksByState {
+    Failure { msg: String },
+    Tasks(TasksPage<Task>),
+    Fields(TasksPage<serde_json::Value>),
+}
+
+impl RespGetTasksByState {
+    /// Reformats the times of all listed tasks with `precision`.
+    fn with_time_precision(self, precision: TimePrecision) -> Self {
+        match self {
+            RespGetTasksByState::Tasks(page) => RespGetTasksByState::Tasks(
+                page.map_tasks(|task| task.with_time_precision(precision)),
+            ),
+            RespGetTasksByState::Fields(page) => {
+                RespGetTasksByState::Fields(page.map_tasks(|mut task| {
+                    precision.reformat_value(&mut task);
+                    task
+                }))
+            }
+            failure @ RespGetTasksByState::Failure { .. } => failure,
+        }
+    }
+}
+
+/// Retrieves the `page` of only the selected `fields` of all tasks in `state`,
+/// optionally only of webhook tasks whose URL contains `url_contains`. Each
+/// task is wrapped in an object naming its type, just as for full tasks.
+async fn select_fields_by_state(
+    db_pool: &crate::db::DbPool,
+    state: &str,
+    url_contains: Option<&str>,
+    fields: &[&str],
+    page: Page,
+) -> std::result::Result<TasksPage<serde_json::Value>, sqlx::Error> {
+    let url_pattern = url_like_pattern(url_contains);
+    let total_webhooks = count_tasks(db_pool, "webhooks", Some(state), Some(&url_pattern)).await?;
+
+    // Hash tasks do not have a URL and thus never match a URL filter.
+    let total_hashes = if url_contains.is_some() {
+        0
+    } else {
+        count_tasks(db_pool, "hashes", Some(state), None).await?
+    };
+
+    // Webhook tasks are listed before hash tasks.
+    let (webhooks_page, hashes_page) = page.split(total_webhooks);
+    let mut tasks = Vec::new();
+    if webhooks_page.limit > 0 {
+        let webhooks = select_fields(
+            db_pool,
+            "webhooks",
+            WEBHOOK_FIELDS,
+            fields,
+            Some(state),
+            Some(&url_pattern),
+            webhooks_page,
+        )
+        .await?;
+        tasks.extend(
+            webhooks
+                .into_iter()
+                .map(|w| serde_json::json!({ "webhook": w })),
+        );
+    }
+    if hashes_page.limit > 0 && total_hashes > 0 {
+        let hashes = select_fields(
+            db_pool,
+            "hashes",
+            HASH_FIELDS,
+            fields,
+            Some(state),
+            None,
+            hashes_page,
+        )
+        .await?;
+        tasks.extend(hashes.into_iter().map(|h| serde_json::json!({ "hash": h })));
+    }
+
+    Ok(TasksPage::new(tasks, page, total_webhooks + total_hashes))
+}
+
+/// Retrieves the `page` of all tasks in `state`, optionally only of webhook
+/// tasks whose URL contains `url_contains`. Webhook tasks are listed before
+/// hash tasks.
+async fn select_tasks_by_state(
+    db_pool: &crate::db::DbPool,
+    state: &str,
+    url_contains: Option<&str>,
+    page: Page,
+) -> std::result::Result<TasksPage<Task>, sqlx::Error> {
+    let url_pattern = url_like_pattern(url_contains);
+    let total_webhooks = count_tasks(db_pool, "webhooks", Some(state), Some(&url_pattern)).await?;
+
+    // Hash tasks do not have a URL and thus never match a URL filter.
+    let total_hashes = if url_contains.is_some() {
+        0
+    } else {
+        count_tasks(db_pool, "hashes", Some(state), None).await?
+    };
+
+    let (webhooks_page, hashes_page) = page.split(total_webhooks);
+    let webhooks = match db_pool {
+        crate::db::DbPool::Sqlite(db_pool) => sqlx::query_as!(
+            ApiWebhook,
+            "SELECT id, state, execution_time, method, url, body, idempotency_key, cron, calendar, \
+            max_retries, initial_backoff_ms, timeout_ms, max_latency_ms, signing_secret, \
+            priority, shard_key, last_status_code, last_response_body, last_latency_ms, \
+            created_at, started_at, completed_at, \
+            headers AS \"headers: sqlx::types::Json<WebhookHeaders>\", \
+            tags AS \"tags: sqlx::types::Json<Vec<String>>\" \
+            FROM webhooks \
+            WHERE state = $1 AND url LIKE $2 ESCAPE '\\' \
+            ORDER BY execution_time ASC \
+            LIMIT $3 OFFSET $4;",
+            state,
+            url_pattern,
+            webhooks_page.limit,
+            webhooks_page.offset,
+        )
+        .fetch_all(db_pool)
+        .await?,
+        crate::db::DbPool::Postgres(db_pool) => {
+            sqlx::query_as(&format!(
+                "SELECT {} \
+                FROM webhooks \
+                WHERE state = $1 AND url ILIKE $2 ESCAPE '\\' \
+                ORDER BY execution_time ASC \
+                LIMIT $3 OFFSET $4;",
+                crate::db::PG_WEBHOOK_COLUMNS
+            ))
+            .bind(state)
+            .bind(&url_pattern)
+            .bind(webhooks_page.limit)
+            .bind(webhooks_page.offset)
+            .fetch_all(db_pool)
+            .await?
+        }
+    };
+
+    let hashes = if total_hashes > 0 {
+        match db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_as!(
+                    ApiHash,
+                    "SELECT id, state, execution_time, secret, algorithm, \
+                    params AS \"params: sqlx::types::Json<serde_json::Value>\", result, \
+                    created_at, started_at, completed_at \
+                    FROM hashes \
+                    WHERE state = $1 \
+                    ORDER BY execution_time ASC \
+                    LIMIT $2 OFFSET $3;",
+                    state,
+                    hashes_page.limit,
+                    hashes_page.offset,
+                )
+                .fetch_all(db_pool)
+                .await?
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_as(&format!(
+                    "SELECT {} \
+                    FROM hashes \
+                    WHERE state = $1 \
+                    ORDER BY execution_time ASC \
+                    LIMIT $2 OFFSET $3;",
+                    crate::db::PG_HASH_COLUMNS
+                ))
+                .bind(state)
+                .bind(hashes_page.limit)
+                .bind(hashes_page.offset)
+                .fetch_all(db_pool)
+                .await?
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
+    // Create one tasks list by combining the webhooks and the hashes lists.
+    let mut tasks = std::vec::Vec::<Task>::with_capacity(webhooks.len() + hashes.len());
+    for webhook in webhooks {
+        tasks.push(Task::Webhook(webhook));
+    }
+    for hash in hashes {
+        tasks.push(Task::Hash(hash));
+    }
+
+    Ok(TasksPage::new(tasks, page, total_webhooks + total_hashes))
+}
+
+/// Returns a page of the list of tasks (containing potentially both webhook
+/// tasks and hash tasks) in the specified state to the caller. If
+/// `url_contains` is supplied, only webhook tasks whose URL contains the given
+/// substring are returned, as hash tasks do not have a URL. If `fields` is
+/// supplied, only the listed fields of each task are returned. The page is
+/// selected via `limit` (default 100, at most 1000) and `offset` (default 0).
+pub(crate) async fn get_tasks_by_state(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::extract::Path(state): axum::extract::Path<String>,
+    axum::extract::Query(query): axum::extract::Query<QueryTasksList>,
+) -> (axum::http::StatusCode, axum::Json<RespGetTasksByState>) {
+    let state = state.to_lowercase();
+    if !TASK_STATES.contains(&state.as_str()) {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            axum::Json(RespGetTasksByState::Failure {
+                msg: format!(
+                    "Field 'state' needs to be one of: '{}'",
+                    TASK_STATES.join("', '")
+                ),
+            }),
+        );
+    }
+
+    let page = match Page::from_query(&query) {
+        Ok(p) => p,
+        Err(msg) => {
+            return (
+                axum::http::StatusCode::BAD_REQUEST,
+                axum::Json(RespGetTasksByState::Failure { msg }),
+            );
+        }
+    };
+
+    let url_contains = query.url_contains.as_deref();
+    let tasks = match &query.fields {
+        Some(fields) => {
+            let fields = match parse_fields(fields, &[WEBHOOK_FIELDS, HASH_FIELDS]) {
+                Ok(f) => f,
+                Err(msg) => {
+                    return (
+                        axum::http::StatusCode::BAD_REQUEST,
+                        axum::Json(RespGetTasksByState::Failure { msg }),
+                    );
+                }
+            };
+            select_fields_by_state(&api_ctx.db_pool, &state, url_contains, &fields, page)
+                .await
+                .map(RespGetTasksByState::Fields)
+        }
+        None => select_tasks_by_state(&api_ctx.db_pool, &state, url_contains, page)
+            .await
+            .map(RespGetTasksByState::Tasks),
+    };
+
+    match tasks {
+        Ok(tasks) => (
+            axum::http::StatusCode::OK,
+            axum::Json(tasks.with_time_precision(api_ctx.config.time_precision)),
+        ),
+        Err(e) => {
+            event!(Level::WARN, "Failed to retrieve tasks from database: {e}");
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespGetTasksByState::Failure {
+                    msg: "Failed to retrieve tasks from database".to_string(),
+                }),
+            )
+        }
+    }
+}
+
+/// States in which tasks reached their end and may be deleted in bulk.
+const TERMINAL_STATES: [&str; 3] = ["done", "failed", "cancelled"];
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespDeleteTasksByState {
+    Failure { msg: String },
+    Success { deleted: u64 },
+}
+
+/// Deletes all tasks of both types in `state` within a single transaction and
+/// returns the number of deleted tasks.
+async fn delete_by_state(
+    db_pool: &crate::db::DbPool,
+    state: &str,
+) -> std::result::Result<u64, sqlx::Error> {
+    let (webhooks, hashes): (Vec<String>, Vec<String>) = match db_pool {
+        crate::db::DbPool::Sqlite(db_pool) => {
+            let mut tx = db_pool.begin().await?;
+            let webhooks =
+                sqlx::query_scalar!("DELETE FROM webhooks WHERE state = $1 RETURNING id;", state)
+                    .fetch_all(&mut *tx)
+                    .await?;
+            let hashes =
+                sqlx::query_scalar!("DELETE FROM hashes WHERE state = $1 RETURNING id;", state)
+                    .fetch_all(&mut *tx)
+                    .await?;
+            tx.commit().await?;
+            (webhooks, hashes)
+        }
+        crate::db::DbPool::Postgres(db_pool) => {
+            let mut tx = db_pool.begin().await?;
+            let webhooks =
+                sqlx::query_scalar("DELETE FROM webhooks WHERE state = $1 RETURNING id;")
+                    .bind(state)
+                    .fetch_all(&mut *tx)
+                    .await?;
+            let hashes = sqlx::query_scalar("DELETE FROM hashes WHERE state = $1 RETURNING id;")
+                .bind(state)
+                .fetch_all(&mut *tx)
+                .await?;
+            tx.commit().await?;
+            (webhooks, hashes)
+        }
+    };
+
+    let deleted = webhooks
+        .iter()
+        .map(|id| ("webhook", id))
+        .chain(hashes.iter().map(|id| ("hash", id)));
+    for (task_type, id) in deleted {
+        audit_api(
+            task_type,
+            id,
+            state,
+            crate::audit::DELETED,
+            "deleted by state",
+        );
+    }
+
+    Ok(u64::try_from(webhooks.len() + hashes.len()).unwrap_or(u64::MAX))
+}
+
+/// Deletes all tasks of both types in the specified terminal state within a
+/// single transaction and returns the number of deleted tasks. Tasks that may
+/// still be handled can't be deleted in bulk, so that no scheduled task is
+/// lost by accident. Dead letters are kept, as they are managed separately.
+pub(crate) async fn delete_tasks_by_state(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::extract::Path(state): axum::extract::Path<String>,
+) -> (axum::http::StatusCode, axum::Json<RespDeleteTasksByState>) {
+    let state = state.to_lowercase();
+    if !TERMINAL_STATES.contains(&state.as_str()) {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            axum::Json(RespDeleteTasksByState::Failure {
+                msg: format!(
+                    "Field 'state' needs to be one of: '{}'",
+                    TERMINAL_STATES.join("', '")
+                ),
+            }),
+        );
+    }
+
+    match crate::db::retry_transient(|| delete_by_state(&api_ctx.db_pool, &state)).await {
+        Ok(deleted) => {
+            event!(Level::INFO, "Deleted {deleted} tasks in state '{state}'");
+            (
+                axum::http::StatusCode::OK,
+                axum::Json(RespDeleteTasksByState::Success { deleted }),
+            )
+        }
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Failed to delete tasks in state '{state}': {e}"
+            );
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespDeleteTasksByState::Failure {
+                    msg: format!("Failed to delete tasks in state '{state}'"),
+                }),
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct QueryTasks {
+    state: Option<String>,
+    #[serde(rename = "type")]
+    task_type: Option<String>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespGetTasks {
+    Failure { msg: String },
+    Tasks(TasksPage<Task>),
+}
+
+/// Retrieves the `page` of all tasks of both types, optionally only those in
+/// `state`, those of type `task_type`, and those whose execution time lies in
+/// the half-open range `scheduled`, in one order of their execution times with
+/// ties broken by ID.
+#[allow(clippy::too_many_lines)]
+async fn select_tasks(
+    db_pool: &crate::db::DbPool,
+    state: Option<&str>,
+    task_type: Option<&str>,
+    scheduled: Option<std::ops::Range<chrono::DateTime<chrono::FixedOffset>>>,
+    page: Page,
+) -> std::result::Result<TasksPage<Task>, sqlx::Error> {
+    // Compare execution times as instants regardless of their offsets.
+    let (from, to) = scheduled
+        .map(|r| {
+            (
+                crate::db::timestamp(r.start.into()),
+                crate::db::timestamp(r.end.into()),
+            )
+        })
+        .unzip();
+
+    let total = match db_pool {
+        crate::db::DbPool::Sqlite(db_pool) => {
+            sqlx::query_scalar!(
+                "SELECT COUNT(*) FROM ( \
+                    SELECT execution_time FROM webhooks \
+                    WHERE ($1 IS NULL OR state = $1) AND ($2 IS NULL OR $2 = 'webhook') \
+                    UNION ALL \
+                    SELECT execution_time FROM hashes \
+                    WHERE ($1 IS NULL OR state = $1) AND ($2 IS NULL OR $2 = 'hash') \
+                ) \
+                WHERE ($3 IS NULL OR julianday(execution_time) >= julianday($3)) \
+                AND ($4 IS NULL OR julianday(execution_time) < julianday($4));",
+                state,
+                task_type,
+                from,
+                to,
+            )
+            .fetch_one(db_pool)
+            .await?
+        }
+        crate::db::DbPool::Postgres(db_pool) => {
+            sqlx::query_scalar(
+                "SELECT COUNT(*) FROM ( \
+                    SELECT execution_time FROM webhooks \
+                    WHERE ($1::TEXT IS NULL OR state = $1) \
+                    AND ($2::TEXT IS NULL OR $2 = 'webhook') \
+                    UNION ALL \
+                    SELECT execution_time FROM hashes \
+                    WHERE ($1::TEXT IS NULL OR state = $1) \
+                    AND ($2::TEXT IS NULL OR $2 = 'hash') \
+                ) AS tasks \
+                WHERE ($3::TEXT IS NULL OR execution_time::TIMESTAMPTZ >= $3::TIMESTAMPTZ) \
+                AND ($4::TEXT IS NULL OR execution_time::TIMESTAMPTZ < $4::TIMESTAMPTZ);",
+            )
+            .bind(state)
+            .bind(task_type)
+            .bind(&from)
+            .bind(&to)
+            .fetch_one(db_pool)
+            .await?
+        }
+    };
+
+    // Determine the tasks on the page across both tables first.
+    let refs: Vec<(String, String)> = match db_pool {
+        crate::db::DbPool::Sqlite(db_pool) => sqlx::query!(
+            "SELECT type AS \"type!: String\", id AS \"id!: String\" FROM ( \
+                SELECT 'webhook' AS type, id, execution_time FROM webhooks \
+                WHERE ($1 IS NULL OR state = $1) AND ($2 IS NULL OR $2 = 'webhook') \
+                UNION ALL \
+                SELECT 'hash' AS type, id, execution_time FROM hashes \
+                WHERE ($1 IS NULL OR state = $1) AND ($2 IS NULL OR $2 = 'hash') \
+            ) \
+            WHERE ($5 IS NULL OR julianday(execution_time) >= julianday($5)) \
+            AND ($6 IS NULL OR julianday(execution_time) < julianday($6)) \
+            ORDER BY julianday(execution_time) ASC, id ASC \
+            LIMIT $3 OFFSET $4;",
+            state,
+            task_type,
+            page.limit,
+            page.offset,
+            from,
+            to,
+        )
+        .fetch_all(db_pool)
+        .await?
+        .into_iter()
+        .map(|r| (r.r#type, r.id))
+        .collect(),
+        crate::db::DbPool::Postgres(db_pool) => {
+            sqlx::query_as(
+                "SELECT type, id FROM ( \
+                    SELECT 'webhook' AS type, id, execution_time FROM webhooks \
+                    WHERE ($1::TEXT IS NULL OR state = $1) \
+                    AND ($2::TEXT IS NULL OR $2 = 'webhook') \
+                    UNION ALL \
+                    SELECT 'hash' AS type, id, execution_time FROM hashes \
+                    WHERE ($1::TEXT IS NULL OR state = $1) \
+                    AND ($2::TEXT IS NULL OR $2 = 'hash') \
+                ) AS tasks \
+                WHERE ($5::TEXT IS NULL OR execution_time::TIMESTAMPTZ >= $5::TIMESTAMPTZ) \
+                AND ($6::TEXT IS NULL OR execution_time::TIMESTAMPTZ < $6::TIMESTAMPTZ) \
+                ORDER BY execution_time::TIMESTAMPTZ ASC, id ASC \
+                LIMIT $3 OFFSET $4;",
+            )
+            .bind(state)
+            .bind(task_type)
+            .bind(page.limit)
+            .bind(page.offset)
+            .bind(&from)
+            .bind(&to)
+            .fetch_all(db_pool)
+            .await?
+        }
+    };
+
+    let ids_of = |task_type: &str| {
+        serde_json::Value::from(
+            refs.iter()
+                .filter(|(t, _)| t == task_type)
+                .map(|(_, id)| id.clone())
+                .collect::<Vec<_>>(),
+        )
+        .to_string()
+    };
+    let (webhook_ids, hash_ids) = (ids_of("webhook"), ids_of("hash"));
+
+    let webhooks = match db_pool {
+        crate::db::DbPool::Sqlite(db_pool) => sqlx::query_as!(
+            ApiWebhook,
+            "SELECT id, state, execution_time, method, url, body, idempotency_key, cron, calendar, \
+            max_retries, initial_backoff_ms, timeout_ms, max_latency_ms, signing_secret, \
+            priority, shard_key, last_status_code, last_response_body, last_latency_ms, \
+            created_at, started_at, completed_at, \
+            headers AS \"headers: sqlx::types::Json<WebhookHeaders>\", \
+            tags AS \"tags: sqlx::types::Json<Vec<String>>\" \
+            FROM webhooks \
+            WHERE id IN (SELECT value FROM json_each($1));",
+            webhook_ids,
+        )
+        .fetch_all(db_pool)
+        .await?,
+        crate::db::DbPool::Postgres(db_pool) => {
+            sqlx::query_as(&format!(
+                "SELECT {} \
+                FROM webhooks \
+                WHERE id IN (SELECT jsonb_array_elements_text($1::JSONB));",
+                crate::db::PG_WEBHOOK_COLUMNS
+            ))
+            .bind(&webhook_ids)
+            .fetch_all(db_pool)
+            .await?
+        }
+    };
+
+    let hashes = match db_pool {
+        crate::db::DbPool::Sqlite(db_pool) => {
+            sqlx::query_as!(
+                ApiHash,
+                "SELECT id, state, execution_time, secret, algorithm, \
+                params AS \"params: sqlx::types::Json<serde_json::Value>\", result, \
+                created_at, started_at, completed_at \
+                FROM hashes \
+                WHERE id IN (SELECT value FROM json_each($1));",
+                hash_ids,
+            )
+            .fetch_all(db_pool)
+            .await?
+        }
+        crate::db::DbPool::Postgres(db_pool) => {
+            sqlx::query_as(&format!(
+                "SELECT {} \
+                FROM hashes \
+                WHERE id IN (SELECT jsonb_array_elements_text($1::JSONB));",
+                crate::db::PG_HASH_COLUMNS
+            ))
+            .bind(&hash_ids)
+            .fetch_all(db_pool)
+            .await?
+        }
+    };
+
+    // Arrange the tasks in the order of the page. Tasks deleted in the meantime
+    // are skipped.
+    let mut by_id = webhooks
+        .into_iter()
+        .map(|wh| (wh.id.clone(), Task::Webhook(wh)))
+        .chain(hashes.into_iter().map(|h| (h.id.clone(), Task::Hash(h))))
+        .collect::<std::collections::HashMap<_, _>>();
+    let tasks = refs.iter().filter_map(|(_, id)| by_id.remove(id)).collect();
+
+    Ok(TasksPage::new(tasks, page, total))
+}
+
+/// Returns a page of the list of all tasks (containing potentially both
+/// webhook tasks and hash tasks), ordered by their execution time across both
+/// types. The list can be restricted to tasks in a `state` and to tasks of a
+/// `type` via the respective query parameters. The page is selected via
+/// `limit` (default 100, at most 1000) and `offset` (default 0).
+pub(crate) async fn get_tasks(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::extract::Query(query): axum::extract::Query<QueryTasks>,
+) -> (axum::http::StatusCode, axum::Json<RespGetTasks>) {
+    let state = query.state.map(|s| s.to_lowercase());
+    if let Some(state) = &state
+        && !TASK_STATES.contains(&state.as_str())
+    {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            axum::Json(RespGetTasks::Failure {
+                msg: format!(
+                    "Query parameter 'state' needs to be one of: '{}'",
+                    TASK_STATES.join("', '")
+                ),
+            }),
+        );
+    }
+
+    let task_type = query.task_type.map(|t| t.to_lowercase());
+    if let Some(task_type) = &task_type
+        && task_type != "webhook"
+        && task_type != "hash"
+    {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            axum::Json(RespGetTasks::Failure {
+                msg: "Query parameter 'type' needs to be one of: 'webhook', 'hash'".to_string(),
+            }),
+        );
+    }
+
+    let page = match Page::parse(query.limit, query.offset) {
+        Ok(p) => p,
+        Err(msg) => {
+            return (
+                axum::http::StatusCode::BAD_REQUEST,
+                axum::Json(RespGetTasks::Failure { msg }),
+            );
+        }
+    };
+
+    match select_tasks(
+        &api_ctx.db_pool,
+        state.as_deref(),
+        task_type.as_deref(),
+        None,
+        page,
+    )
+    .await
+    {
+        Ok(tasks) => (
+            axum::http::StatusCode::OK,
+            axum::Json(RespGetTasks::Tasks(tasks.map_tasks(|task| {
+                task.with_time_precision(api_ctx.config.time_precision)
+            }))),
+        ),
+        Err(e) => {
+            event!(Level::WARN, "Failed to retrieve tasks from database: {e}");
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespGetTasks::Failure {
+                    msg: "Failed to retrieve tasks from database".to_string(),
+                }),
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct QueryTasksScheduled {
+    from: String,
+    to: String,
+    limit: Option<u64>,
+    offset: Option<u64>,
+}
+
+/// Returns a page of the list of all tasks (containing potentially both
+/// webhook tasks and hash tasks) whose execution time lies at or after `from`
+/// and before `to`, ordered by their execution time across both types as for
+/// `get_tasks`. Both `from` and `to` are RFC 3339 datetimes, which may lie in
+/// the past. The page is selected via `limit` (default 100, at most 1000) and
+/// `offset` (default 0).
+pub(crate) async fn get_tasks_scheduled(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::extract::Query(query): axum::extract::Query<QueryTasksScheduled>,
+) -> (axum::http::StatusCode, axum::Json<RespGetTasks>) {
+    let range = parse_rfc3339("Query parameter 'from'", &query.from).and_then(|from| {
+        let to = parse_rfc3339("Query parameter 'to'", &query.to)?;
+        if from >= to {
+            return Err(ApiTimeError::InvalidRange(
+                "Query parameter 'from' must lie before query parameter 'to'".to_string(),
+            ));
+        }
+        Ok(from..to)
+    });
+    let range = match range {
+        Ok(r) => r,
+        Err(e) => {
+            return (
+                axum::http::StatusCode::BAD_REQUEST,
+                axum::Json(RespGetTasks::Failure { msg: e.to_string() }),
+            );
+        }
+    };
+
+    let page = match Page::parse(query.limit, query.offset) {
+        Ok(p) => p,
+        Err(msg) => {
+            return (
+                axum::http::StatusCode::BAD_REQUEST,
+                axum::Json(RespGetTasks::Failure { msg }),
+            );
+        }
+    };
+
+    match select_tasks(&api_ctx.db_pool, None, None, Some(range), page).await {
+        Ok(tasks) => (
+            axum::http::StatusCode::OK,
+            axum::Json(RespGetTasks::Tasks(tasks.map_tasks(|task| {
+                task.with_time_precision(api_ctx.config.time_precision)
+            }))),
+        ),
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Failed to retrieve scheduled tasks from database: {e}"
+            );
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespGetTasks::Failure {
+                    msg: "Failed to retrieve tasks from database".to_string(),
+                }),
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct QueryTasksCount {
+    #[serde(rename = "type")]
+    task_type: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespGetTasksCount {
+    Failure { msg: String },
+    Counts(std::collections::BTreeMap<String, i64>),
+}
+
+/// Returns the number of tasks in each state, summed across both task types or
+/// restricted to the type supplied via query parameter `type`. The counts are
+/// computed by the database with a single grouped query per table, without
+/// fetching any task. States without tasks are included with a count of zero.
+pub(crate) async fn get_tasks_count(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::extract::Query(query): axum::extract::Query<QueryTasksCount>,
+) -> (axum::http::StatusCode, axum::Json<RespGetTasksCount>) {
+    let task_type = query.task_type.map(|t| t.to_lowercase());
+    if let Some(task_type) = &task_type
+        && task_type != "webhook"
+        && task_type != "hash"
+    {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            axum::Json(RespGetTasksCount::Failure {
+                msg: "Query parameter 'type' needs to be one of: 'webhook', 'hash'".to_string(),
+            }),
+        );
+    }
+
+    match crate::db::count_task_states(&api_ctx.db_pool).await {
+        Ok(counts) => {
+            let mut by_state = TASK_STATES
+                .iter()
+                .map(|state| ((*state).to_string(), 0))
+                .collect::<std::collections::BTreeMap<_, _>>();
+            for (t, state, count) in counts {
+                if task_type.as_ref().is_none_or(|task_type| *task_type == t) {
+                    *by_state.entry(state).or_default() += count;
+                }
+            }
+            (
+                axum::http::StatusCode::OK,
+                axum::Json(RespGetTasksCount::Counts(by_state)),
+            )
+        }
+        Err(e) => {
+            event!(Level::WARN, "Failed to count tasks in database: {e}");
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespGetTasksCount::Failure {
+                    msg: "Failed to count tasks in database".to_string(),
+                }),
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespGetTasksByType {
+    Failure { msg: String },
+    Webhooks(TasksPage<ApiWebhook>),
+    Hashes(TasksPage<ApiHash>),
+    Fields(TasksPage<serde_json::Value>),
+}
+
+impl RespGetTasksByType {
+    /// Reformats the times of all listed tasks with `precision`.
+    fn with_time_precision(self, precision: TimePrecision) -> Self {
+        match self {
+            RespGetTasksByType::Webhooks(page) => {
+                RespGetTasksByType::Webhooks(page.map_tasks(|mut wh| {
+                    precision.reformat_task(
+                        &mut wh.execution_time,
+                        [&mut wh.created_at, &mut wh.started_at, &mut wh.completed_at],
+                    );
+                    wh
+                }))
+            }
+            RespGetTasksByType::Hashes(page) => {
+                RespGetTasksByType::Hashes(page.map_tasks(|mut h| {
+                    precision.reformat_task(
+                        &mut h.execution_time,
+                        [&mut h.created_at, &mut h.started_at, &mut h.completed_at],
+                    );
+                    h
+                }))
+            }
+            RespGetTasksByType::Fields(page) => {
+                RespGetTasksByType::Fields(page.map_tasks(|mut task| {
+                    precision.reformat_value(&mut task);
+                    task
+                }))
+            }
+            failure @ RespGetTasksByType::Failure { .. } => failure,
+        }
+    }
+}
+
+/// Retrieves the `page` of only the selected `fields` of all tasks of type
+/// `task_type`, optionally only of those whose URL contains `url_contains`.
+async fn select_fields_by_type(
+    db_pool: &crate::db::DbPool,
+    task_type: &str,
+    url_contains: Option<&str>,
+    fields: &[&str],
+    page: Page,
+) -> std::result::Result<TasksPage<serde_json::Value>, sqlx::Error> {
+    let (table, columns) = if task_type == "webhook" {
+        ("webhooks", WEBHOOK_FIELDS)
+    } else {
+        ("hashes", HASH_FIELDS)
+    };
+
+    // Hash tasks do not have a URL and thus never match a URL filter.
+    if task_type == "hash" && url_contains.is_some() {
+        return Ok(TasksPage::new(Vec::new(), page, 0));
+    }
+
+    let url_pattern = url_contains.map(|u| url_like_pattern(Some(u)));
+    let total = count_tasks(db_pool, table, None, url_pattern.as_deref()).await?;
+    let tasks = select_fields(
+        db_pool,
+        table,
+        columns,
+        fields,
+        None,
+        url_pattern.as_deref(),
+        page,
+    )
+    .await?;
+
+    Ok(TasksPage::new(tasks, page, total))
+}
+
+/// Retrieves the `page` of all webhook tasks, optionally only of those whose
+/// URL contains the substring `url_contains`.
+async fn select_webhooks(
+    db_pool: &crate::db::DbPool,
+    url_contains: Option<&str>,
+    page: Page,
+) -> std::result::Result<TasksPage<ApiWebhook>, sqlx::Error> {
+    let url_pattern = url_like_pattern(url_contains);
+    let total = count_tasks(db_pool, "webhooks", None, Some(&url_pattern)).await?;
+    let webhooks = match db_pool {
+        crate::db::DbPool::Sqlite(db_pool) => sqlx::query_as!(
+            ApiWebhook,
+            "SELECT id, state, execution_time, method, url, body, idempotency_key, cron, calendar, \
+            max_retries, initial_backoff_ms, timeout_ms, max_latency_ms, signing_secret, \
+            priority, shard_key, last_status_code, last_response_body, last_latency_ms, \
+            created_at, started_at, completed_at, \
+            headers AS \"headers: sqlx::types::Json<WebhookHeaders>\", \
+            tags AS \"tags: sqlx::types::Json<Vec<String>>\" \
+            FROM webhooks \
+            WHERE url LIKE $1 ESCAPE '\\' \
+            ORDER BY execution_time ASC \
+            LIMIT $2 OFFSET $3;",
+            url_pattern,
+            page.limit,
+            page.offset,
+        )
+        .fetch_all(db_pool)
+        .await?,
+        crate::db::DbPool::Postgres(db_pool) => {
+            sqlx::query_as(&format!(
+                "SELECT {} \
+                FROM webhooks \
+                WHERE url ILIKE $1 ESCAPE '\\' \
+                ORDER BY execution_time ASC \
+                LIMIT $2 OFFSET $3;",
+                crate::db::PG_WEBHOOK_COLUMNS
+            ))
+            .bind(&url_pattern)
+            .bind(page.limit)
+            .bind(page.offset)
+            .fetch_all(db_pool)
+            .await?
+        }
+    };
+
+    Ok(TasksPage::new(webhooks, page, total))
+}
+
+/// Retrieves the `page` of all hash tasks.
+async fn select_hashes(
+    db_pool: &crate::db::DbPool,
+    page: Page,
+) -> std::result::Result<TasksPage<ApiHash>, sqlx::Error> {
+    let total = count_tasks(db_pool, "hashes", None, None).await?;
+    let hashes = match db_pool {
+        crate::db::DbPool::Sqlite(db_pool) => {
+            sqlx::query_as!(
+                ApiHash,
+                "SELECT id, state, execution_time, secret, algorithm, \
+                params AS \"params: sqlx::types::Json<serde_json::Value>\", result, \
+                created_at, started_at, completed_at \
+                FROM hashes \
+                ORDER BY execution_time ASC \
+                LIMIT $1 OFFSET $2;",
+                page.limit,
+                page.offset,
+            )
+            .fetch_all(db_pool)
+            .await?
+        }
+        crate::db::DbPool::Postgres(db_pool) => {
+            sqlx::query_as(&format!(
+                "SELECT {} \
+                FROM hashes \
+                ORDER BY execution_time ASC \
+                LIMIT $1 OFFSET $2;",
+                crate::db::PG_HASH_COLUMNS
+            ))
+            .bind(page.limit)
+            .bind(page.offset)
+            .fetch_all(db_pool)
+            .await?
+        }
+    };
+
+    Ok(TasksPage::new(hashes, page, total))
+}
+
+/// Returns a page of all tasks to the user that are of the specified type
+/// (webhook or hash), ordered by their execution time. The optional
+/// `url_contains` filter only applies to webhook tasks, hash tasks never match
+/// it. If `fields` is supplied, only the listed fields of each task are
+/// returned. The page is selected via `limit` (default 100, at most 1000) and
+/// `offset` (default 0).
+pub(crate) async fn get_tasks_by_type(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::extract::Path(task_type): axum::extract::Path<String>,
+    axum::extract::Query(query): axum::extract::Query<QueryTasksList>,
+) -> (axum::http::StatusCode, axum::Json<RespGetTasksByType>) {
+    let task_type = task_type.to_lowercase();
+    if task_type != "webhook" && task_type != "hash" {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            axum::Json(RespGetTasksByType::Failure {
+                msg: "Unsupported task type, use either 'webhook' or 'hash'".to_string(),
+            }),
+        );
+    }
+
+    let page = match Page::from_query(&query) {
+        Ok(p) => p,
+        Err(msg) => {
+            return (
+                axum::http::StatusCode::BAD_REQUEST,
+                axum::Json(RespGetTasksByType::Failure { msg }),
+            );
+        }
+    };
+
+    let url_contains = query.url_contains.as_deref();
+    let tasks = if let Some(fields) = &query.fields {
+        let columns = if task_type == "webhook" {
+            WEBHOOK_FIELDS
+        } else {
+            HASH_FIELDS
+        };
+        let fields = match parse_fields(fields, &[columns]) {
+            Ok(f) => f,
+            Err(msg) => {
+                return (
+                    axum::http::StatusCode::BAD_REQUEST,
+                    axum::Json(RespGetTasksByType::Failure { msg }),
+                );
+            }
+        };
+        select_fields_by_type(&api_ctx.db_pool, &task_type, url_contains, &fields, page)
+            .await
+            .map(RespGetTasksByType::Fields)
+    } else if task_type == "webhook" {
+        select_webhooks(&api_ctx.db_pool, url_contains, page)
+            .await
+            .map(RespGetTasksByType::Webhooks)
+    } else if url_contains.is_some() {
+        // Hash tasks do not have a URL and thus never match a URL filter.
+        Ok(RespGetTasksByType::Hashes(TasksPage::new(
+            Vec::new(),
+            page,
+            0,
+        )))
+    } else {
+        select_hashes(&api_ctx.db_pool, page)
+            .await
+            .map(RespGetTasksByType::Hashes)
+    };
+
+    match tasks {
+        Ok(tasks) => (
+            axum::http::StatusCode::OK,
+            axum::Json(tasks.with_time_precision(api_ctx.config.time_precision)),
+        ),
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Failed to retrieve {task_type} tasks from database: {e}"
+            );
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespGetTasksByType::Failure {
+                    msg: format!("Failed to retrieve {task_type} tasks from database"),
+                }),
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespDeleteTask {
+    Failure { msg: String },
+    Success {},
+    Deleted(Box<Task>),
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct QueryDeleteTask {
+    #[serde(default)]
+    return_task: bool,
+}
+
+/// Builds the response for a successfully deleted task. If the caller asked
+/// for it via `return_task`, the task as it was right before its deletion is
+/// returned, otherwise no content is.
+fn deleted_response(
+    return_task: bool,
+    task: Task,
+) -> (axum::http::StatusCode, axum::Json<RespDeleteTask>) {
+    if return_task {
+        (
+            axum::http::StatusCode::OK,
+            axum::Json(RespDeleteTask::Deleted(Box::new(task))),
+        )
+    } else {
+        (
+            axum::http::StatusCode::NO_CONTENT,
+            axum::Json(RespDeleteTask::Success {}),
+        )
+    }
+}
+
+/// Deletes a task (webhook or hash) from the respective table. We rely on the
+/// property that collisions when generating UUIDs are exceedingly unlikely, and
+/// can thus be ignored. We thus attempt to delete the task from the webhooks
+/// table first and if no row was affected, then from the hashes table. If
+/// neither of the two queries succeeded, the task didn't exist and report that
+/// back to the caller.
+///
+/// If the caller supplies an `If-Match` header carrying the state in which the
+/// caller expects the task to be (e.g. `If-Match: "todo"`), the task is only
+/// deleted if it still is in that state. Checking the state is part of the
+/// same `DELETE` statement, so a task changing its state in the meantime is
+/// never deleted. Instead, `412 Precondition Failed` is returned.
+///
+/// If the caller supplies query parameter `return_task=true`, the deleted task
+/// including its final state is returned, so that cleanup tooling can confirm
+/// what it deleted without an additional request.
+#[allow(clippy::too_many_lines)]
+pub(crate) async fn delete_task(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    axum::extract::Query(query): axum::extract::Query<QueryDeleteTask>,
+    headers: axum::http::HeaderMap,
+) -> (axum::http::StatusCode, axum::Json<RespDeleteTask>) {
+    // Extract the expected state from the optional 'If-Match' header. A value of
+    // '*' matches any state, just as omitting the header.
+    let expected_state = match headers.get(axum::http::header::IF_MATCH) {
+        Some(value) => match value.to_str() {
+            Ok(v) if v.trim() == "*" => None,
+            Ok(v) => Some(v.trim().trim_matches('"').to_lowercase()),
+            Err(_) => {
+                return (
+                    axum::http::StatusCode::BAD_REQUEST,
+                    axum::Json(RespDeleteTask::Failure {
+                        msg: "Header 'If-Match' must contain the expected state of the task"
+                            .to_string(),
+                    }),
+                );
+            }
+        },
+        None => None,
+    };
+
+    let deleted = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_as!(
+                    ApiWebhook,
+                    "DELETE FROM webhooks \
+                    WHERE id = $1 AND state != $2 AND ($3 IS NULL OR state = $3) \
+                    RETURNING id, state, execution_time, method, url, body, idempotency_key, cron, calendar, \
+                    max_retries, initial_backoff_ms, timeout_ms, max_latency_ms, signing_secret, \
+                    priority, shard_key, last_status_code, last_response_body, last_latency_ms, \
+                    created_at, started_at, completed_at, \
+                    headers AS \"headers: sqlx::types::Json<WebhookHeaders>\", \
+                    tags AS \"tags: sqlx::types::Json<Vec<String>>\";",
+                    id,
+                    "in_progress",
+                    expected_state,
+                )
+                .fetch_optional(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_as(&format!(
+                    "DELETE FROM webhooks \
+                    WHERE id = $1 AND state != $2 AND ($3 IS NULL OR state = $3) \
+                    RETURNING {};",
+                    crate::db::PG_WEBHOOK_COLUMNS
+                ))
+                .bind(&id)
+                .bind("in_progress")
+                .bind(&expected_state)
+                .fetch_optional(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    let del_webhook = match deleted {
+        Ok(wh) => wh,
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Deleting task '{id}' from webhooks table failed with: {e}"
+            );
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespDeleteTask::Failure {
+                    msg: format!("Deleting task '{id}' from webhooks table failed"),
+                }),
+            );
+        }
+    };
+
+    if let Some(webhook) = del_webhook {
+        audit_api(
+            "webhook",
+            &id,
+            &webhook.state,
+            crate::audit::DELETED,
+            "deleted via API",
+        );
+        return deleted_response(query.return_task, Task::Webhook(webhook));
+    }
+
+    let deleted = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_as!(
+                    ApiHash,
+                    "DELETE FROM hashes \
+                    WHERE id = $1 AND state != $2 AND ($3 IS NULL OR state = $3) \
+                    RETURNING id, state, execution_time, secret, algorithm, \
+                    params AS \"params: sqlx::types::Json<serde_json::Value>\", result, \
+                    created_at, started_at, completed_at;",
+                    id,
+                    "in_progress",
+                    expected_state,
+                )
+                .fetch_optional(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_as(&format!(
+                    "DELETE FROM hashes \
+                    WHERE id = $1 AND state != $2 AND ($3 IS NULL OR state = $3) \
+                    RETURNING {};",
+                    crate::db::PG_HASH_COLUMNS
+                ))
+                .bind(&id)
+                .bind("in_progress")
+                .bind(&expected_state)
+                .fetch_optional(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    let del_hash = match deleted {
+        Ok(h) => h,
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Deleting task '{id}' from hashes table failed with: {e}"
+            );
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespDeleteTask::Failure {
+                    msg: format!("Deleting task '{id}' from hashes table failed"),
+                }),
+            );
+        }
+    };
+
+    if let Some(hash) = del_hash {
+        audit_api(
+            "hash",
+            &id,
+            &hash.state,
+            crate::audit::DELETED,
+            "deleted via API",
+        );
+        return deleted_response(query.return_task, Task::Hash(hash));
+    }
+
+    // If the caller expected a state, find out whether the task wasn't deleted
+    // because it is in a different state, and report this.
+    if let Some(expected_state) = expected_state {
+        let fetched = crate::db::retry_transient(|| async {
+            match &api_ctx.db_pool {
+                crate::db::DbPool::Sqlite(db_pool) => {
+                    sqlx::query_scalar!(
+                        "SELECT state FROM webhooks WHERE id = $1 \
+                        UNION ALL \
+                        SELECT state FROM hashes WHERE id = $1;",
+                        id,
+                    )
+                    .fetch_optional(db_pool)
+                    .await
+                }
+                crate::db::DbPool::Postgres(db_pool) => {
+                    sqlx::query_scalar(
+                        "SELECT state FROM webhooks WHERE id = $1 \
+                        UNION ALL \
+                        SELECT state FROM hashes WHERE id = $1;",
+                    )
+                    .bind(&id)
+                    .fetch_optional(db_pool)
+                    .await
+                }
+            }
+        })
+        .await;
+        match fetched {
+            Ok(Some(state)) if state != expected_state => {
+                return (
+                    axum::http::StatusCode::PRECONDITION_FAILED,
+                    axum::Json(RespDeleteTask::Failure {
+                        msg: format!(
+                            "Task '{id}' is in state '{state}' instead of expected state \
+                            '{expected_state}'"
+                        ),
+                    }),
+                );
+            }
+            Ok(_) => {}
+            Err(e) => {
+                event!(Level::WARN, "Fetching state of task '{id}' failed: {e}");
+                return (
+                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    axum::Json(RespDeleteTask::Failure {
+                        msg: format!("Fetching state of task '{id}' failed"),
+                    }),
+                );
+            }
+        }
+    }
+
+    // At this point, it is clear that the task ID doesn't exist. Report this.
+    (
+        axum::http::StatusCode::BAD_REQUEST,
+        axum::Json(RespDeleteTask::Failure {
+            msg: format!("Task '{id}' does not exist"),
+        }),
+    )
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct ReqPatchTask {
+    execution_time: String,
+}
+
+/// Reschedules a task (webhook or hash) to a new execution time, keeping its
+/// ID. The new execution time is validated as for new tasks. Only tasks in
+/// state `todo` or `held` can be rescheduled. A task in state `todo` is queued
+/// again, which replaces its entry in the worker's delay queue, so that it is
+/// handled at the new execution time only. A task in state `held` keeps being
+/// held and is queued for the new execution time once released. Returns the
+/// rescheduled task.
+#[allow(clippy::too_many_lines)]
+pub(crate) async fn patch_task(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    axum::Json(payload): axum::Json<ReqPatchTask>,
+) -> (axum::http::StatusCode, axum::Json<RespGetTask>) {
+    let execution_time = match validate_execution_time(
+        "execution_time",
+        &payload.execution_time,
+        api_ctx.config.clock_skew_tolerance,
+    ) {
+        Ok(t) => t.to_rfc3339(),
+        Err(e) => {
+            return (
+                axum::http::StatusCode::BAD_REQUEST,
+                axum::Json(RespGetTask::Failure {
+                    msg: format!("Malformed update: {e}"),
+                }),
+            );
+        }
+    };
+
+    let updated = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_as!(
+                    ApiWebhook,
+                    "UPDATE webhooks \
+                    SET execution_time = $2 \
+                    WHERE id = $1 AND (state = 'todo' OR state = 'held') \
+                    RETURNING id, state, execution_time, method, url, body, idempotency_key, cron, calendar, \
+                    max_retries, initial_backoff_ms, timeout_ms, max_latency_ms, signing_secret, \
+                    priority, shard_key, last_status_code, last_response_body, last_latency_ms, \
+                    created_at, started_at, completed_at, \
+                    headers AS \"headers: sqlx::types::Json<WebhookHeaders>\", \
+                    tags AS \"tags: sqlx::types::Json<Vec<String>>\";",
+                    id,
+                    execution_time,
+                )
+                .fetch_optional(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_as(&format!(
+                    "UPDATE webhooks \
+                    SET execution_time = $2 \
+                    WHERE id = $1 AND (state = 'todo' OR state = 'held') \
+                    RETURNING {};",
+                    crate::db::PG_WEBHOOK_COLUMNS
+                ))
+                .bind(&id)
+                .bind(&execution_time)
+                .fetch_optional(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    let webhook = match updated {
+        Ok(wh) => wh,
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Rescheduling task '{id}' in webhooks table failed with: {e}"
+            );
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespGetTask::Failure {
+                    msg: format!("Rescheduling task '{id}' in webhooks table failed"),
+                }),
+            );
+        }
+    };
+
+    if let Some(webhook) = webhook {
+        if webhook.state == "todo" {
+            queue_task(&api_ctx, Task::Webhook(webhook.clone())).await;
+        }
+        return (
+            axum::http::StatusCode::OK,
+            axum::Json(RespGetTask::task(
+                Task::Webhook(webhook),
+                api_ctx.config.time_precision,
+            )),
+        );
+    }
+
+    let updated = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_as!(
+                    ApiHash,
+                    "UPDATE hashes \
+                    SET execution_time = $2 \
+                    WHERE id = $1 AND (state = 'todo' OR state = 'held') \
+                    RETURNING id, state, execution_time, secret, algorithm, \
+                    params AS \"params: sqlx::types::Json<serde_json::Value>\", result, \
+                    created_at, started_at, completed_at;",
+                    id,
+                    execution_time,
+                )
+                .fetch_optional(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_as(&format!(
+                    "UPDATE hashes \
+                    SET execution_time = $2 \
+                    WHERE id = $1 AND (state = 'todo' OR state = 'held') \
+                    RETURNING {};",
+                    crate::db::PG_HASH_COLUMNS
+                ))
+                .bind(&id)
+                .bind(&execution_time)
+                .fetch_optional(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    let hash = match updated {
+        Ok(h) => h,
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Rescheduling task '{id}' in hashes table failed with: {e}"
+            );
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespGetTask::Failure {
+                    msg: format!("Rescheduling task '{id}' in hashes table failed"),
+                }),
+            );
+        }
+    };
+
+    if let Some(hash) = hash {
+        if hash.state == "todo" {
+            queue_task(&api_ctx, Task::Hash(hash.clone())).await;
+        }
+        return (
+            axum::http::StatusCode::OK,
+            axum::Json(RespGetTask::task(
+                Task::Hash(hash),
+                api_ctx.config.time_precision,
+            )),
+        );
+    }
+
+    let (status, msg) = transition_failure(&api_ctx, &id, "rescheduled").await;
+    (status, axum::Json(RespGetTask::Failure { msg }))
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespCancelTask {
+    Failure { msg: String },
+    Success {},
+}
+
+/// Cancels a task (webhook or hash) without deleting it. A task in state `todo`
+/// or `held` is moved to state `cancelled` right away, which prevents the
+/// worker from claiming it. A webhook task that is currently being handled by
+/// the worker has its in-flight request aborted and is moved to state
+/// `cancelled` by the worker shortly after, which is signalled by
+/// `202 Accepted`. Tasks in any other state cannot be cancelled anymore.
+#[allow(clippy::too_many_lines)]
+pub(crate) async fn cancel_task(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> (axum::http::StatusCode, axum::Json<RespCancelTask>) {
+    // Try each state a task can be cancelled from in turn, so that the state it
+    // was cancelled from is known for the audit log.
+    for from in ["todo", "held"] {
+        let cancelled = crate::db::retry_transient(|| async {
+            match &api_ctx.db_pool {
+                crate::db::DbPool::Sqlite(db_pool) => sqlx::query!(
+                    "UPDATE webhooks \
+                    SET state = 'cancelled' \
+                    WHERE id = $1 AND state = $2;",
+                    id,
+                    from,
+                )
+                .execute(db_pool)
+                .await
+                .map(|r| r.rows_affected()),
+                crate::db::DbPool::Postgres(db_pool) => sqlx::query(
+                    "UPDATE webhooks \
+                    SET state = 'cancelled' \
+                    WHERE id = $1 AND state = $2;",
+                )
+                .bind(&id)
+                .bind(from)
+                .execute(db_pool)
+                .await
+                .map(|r| r.rows_affected()),
+            }
+        })
+        .await;
+        let num_cancelled_webhooks = match cancelled {
+            Ok(rows) => rows,
+            Err(e) => {
+                event!(
+                    Level::WARN,
+                    "Cancelling task '{id}' in webhooks table failed with: {e}"
+                );
+                return (
+                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    axum::Json(RespCancelTask::Failure {
+                        msg: format!("Cancelling task '{id}' in webhooks table failed"),
+                    }),
+                );
+            }
+        };
+
+        if num_cancelled_webhooks >= 1 {
+            audit_api("webhook", &id, from, "cancelled", "cancelled via API");
+            return (
+                axum::http::StatusCode::OK,
+                axum::Json(RespCancelTask::Success {}),
+            );
+        }
+
+        let cancelled = crate::db::retry_transient(|| async {
+            match &api_ctx.db_pool {
+                crate::db::DbPool::Sqlite(db_pool) => sqlx::query!(
+                    "UPDATE hashes \
+                    SET state = 'cancelled' \
+                    WHERE id = $1 AND state = $2;",
+                    id,
+                    from,
+                )
+                .execute(db_pool)
+                .await
+                .map(|r| r.rows_affected()),
+                crate::db::DbPool::Postgres(db_pool) => sqlx::query(
+                    "UPDATE hashes \
+                    SET state = 'cancelled' \
+                    WHERE id = $1 AND state = $2;",
+                )
+                .bind(&id)
+                .bind(from)
+                .execute(db_pool)
+                .await
+                .map(|r| r.rows_affected()),
+            }
+        })
+        .await;
+        let num_cancelled_hashes = match cancelled {
+            Ok(rows) => rows,
+            Err(e) => {
+                event!(
+                    Level::WARN,
+                    "Cancelling task '{id}' in hashes table failed with: {e}"
+                );
+                return (
+                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    axum::Json(RespCancelTask::Failure {
+                        msg: format!("Cancelling task '{id}' in hashes table failed"),
+                    }),
+                );
+            }
+        };
+
+        if num_cancelled_hashes >= 1 {
+            audit_api("hash", &id, from, "cancelled", "cancelled via API");
+            return (
+                axum::http::StatusCode::OK,
+                axum::Json(RespCancelTask::Success {}),
+            );
+        }
+    }
+
+    // The task is not in state `todo` or `held`. If it is a webhook task
+    // currently being handled, abort its in-flight request.
+    if api_ctx.cancel_registry.cancel(&id) {
+        return (
+            axum::http::StatusCode::ACCEPTED,
+            axum::Json(RespCancelTask::Success {}),
+        );
+    }
+
+    let (status, msg) = transition_failure(&api_ctx, &id, "cancelled").await;
+    (status, axum::Json(RespCancelTask::Failure { msg }))
+}
+
+/// Hands `task` to the worker for handling at its execution time, or right
+/// away if that lies in the past. As the database is the source of truth, this
+/// is best-effort and failures are only logged, see `post_tasks_new`.
+async fn queue_task(api_ctx: &ApiCtx, task: Task) {
+    let dur_from_now = chrono::DateTime::parse_from_rfc3339(task.execution_time())
+        .ok()
+        .and_then(|t| (t - chrono::Utc::now().fixed_offset()).to_std().ok())
+        .unwrap_or_default();
+
+    let id = task.id().to_string();
+    if api_ctx.send_task.send((dur_from_now, task)).await.is_err() {
+        event!(
+            Level::WARN,
+            "Sending task '{id}' to delay queue failed, task will be reinserted upon restart"
+        );
+    }
+}
+
+/// Records in the audit log that the task of type `task_type` with ID `id` was
+/// moved from state `from` to state `to` by a request to the API.
+fn audit_api(task_type: &str, id: &str, from: &str, to: &str, cause: &str) {
+    crate::audit::record(crate::audit::Actor::Api, task_type, id, from, to, cause);
+}
+
+/// Reports why a task-specific state transition didn't take place: either the
+/// task doesn't exist, or it is in a state that doesn't allow the transition.
+async fn transition_failure(
+    api_ctx: &ApiCtx,
+    id: &str,
+    transition: &str,
+) -> (axum::http::StatusCode, String) {
+    let fetched = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_scalar!(
+                    "SELECT state FROM webhooks WHERE id = $1 \
+                    UNION ALL \
+                    SELECT state FROM hashes WHERE id = $1;",
+                    id,
+                )
+                .fetch_optional(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_scalar(
+                    "SELECT state FROM webhooks WHERE id = $1 \
+                    UNION ALL \
+                    SELECT state FROM hashes WHERE id = $1;",
+                )
+                .bind(id)
+                .fetch_optional(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    match fetched {
+        Ok(Some(state)) => (
+            axum::http::StatusCode::CONFLICT,
+            format!("Task '{id}' is in state '{state}' and can't be {transition}"),
+        ),
+        Ok(None) => (
+            axum::http::StatusCode::NOT_FOUND,
+            format!("Task '{id}' does not exist"),
+        ),
+        Err(e) => {
+            event!(Level::WARN, "Fetching state of task '{id}' failed: {e}");
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Fetching state of task '{id}' failed"),
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespHoldTask {
+    Failure { msg: String },
+    Success {},
+}
+
+/// Holds a task (webhook or hash) in state `todo` indefinitely by moving it to
+/// state `held` and removing it from the worker's delay queue, until it gets
+/// released again via `release_task`.
+#[allow(clippy::too_many_lines)]
+pub(crate) async fn hold_task(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> (axum::http::StatusCode, axum::Json<RespHoldTask>) {
+    let updated = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => sqlx::query!(
+                "UPDATE webhooks \
+                SET state = 'held' \
+                WHERE id = $1 AND state = 'todo';",
+                id,
+            )
+            .execute(db_pool)
+            .await
+            .map(|r| r.rows_affected()),
+            crate::db::DbPool::Postgres(db_pool) => sqlx::query(
+                "UPDATE webhooks \
+                SET state = 'held' \
+                WHERE id = $1 AND state = 'todo';",
+            )
+            .bind(&id)
+            .execute(db_pool)
+            .await
+            .map(|r| r.rows_affected()),
+        }
+    })
+    .await;
+    let num_held_webhooks = match updated {
+        Ok(t) => t,
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Holding task '{id}' in webhooks table failed with: {e}"
+            );
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespHoldTask::Failure {
+                    msg: format!("Holding task '{id}' in webhooks table failed"),
+                }),
+            );
+        }
+    };
+
+    let num_held_hashes = if num_held_webhooks >= 1 {
+        0
+    } else {
+        let updated = crate::db::retry_transient(|| async {
+            match &api_ctx.db_pool {
+                crate::db::DbPool::Sqlite(db_pool) => sqlx::query!(
+                    "UPDATE hashes \
+                    SET state = 'held' \
+                    WHERE id = $1 AND state = 'todo';",
+                    id,
+                )
+                .execute(db_pool)
+                .await
+                .map(|r| r.rows_affected()),
+                crate::db::DbPool::Postgres(db_pool) => sqlx::query(
+                    "UPDATE hashes \
+                    SET state = 'held' \
+                    WHERE id = $1 AND state = 'todo';",
+                )
+                .bind(&id)
+                .execute(db_pool)
+                .await
+                .map(|r| r.rows_affected()),
+            }
+        })
+        .await;
+        match updated {
+            Ok(t) => t,
+            Err(e) => {
+                event!(
+                    Level::WARN,
+                    "Holding task '{id}' in hashes table failed with: {e}"
+                );
+                return (
+                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    axum::Json(RespHoldTask::Failure {
+                        msg: format!("Holding task '{id}' in hashes table failed"),
+                    }),
+                );
+            }
+        }
+    };
+
+    if num_held_webhooks + num_held_hashes == 0 {
+        let (status, msg) = transition_failure(&api_ctx, &id, "held").await;
+        return (status, axum::Json(RespHoldTask::Failure { msg }));
+    }
+    let task_type = if num_held_webhooks >= 1 {
+        "webhook"
+    } else {
+        "hash"
+    };
+    audit_api(task_type, &id, "todo", "held", "held via API");
+
+    // Remove the task from the delay queue. Should this fail, the worker will
+    // not handle the task anyway when its queue entry expires, as it is not in
+    // state `todo` anymore.
+    if api_ctx
+        .send_cmd
+        .send(crate::worker::WorkerCmd::Remove { id: id.clone() })
+        .await
+        .is_err()
+    {
+        event!(
+            Level::WARN,
+            "Sending removal of held task '{id}' from delay queue to worker failed"
+        );
+    }
+
+    (
+        axum::http::StatusCode::OK,
+        axum::Json(RespHoldTask::Success {}),
+    )
+}
+
+/// Releases a task (webhook or hash) in state `held` by moving it back to state
+/// `todo` and rescheduling it for its execution time. If the execution time
+/// passed while the task was held, the task is handled right away. Returns the
+/// released task.
+#[allow(clippy::too_many_lines)]
+pub(crate) async fn release_task(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> (axum::http::StatusCode, axum::Json<RespGetTask>) {
+    let updated = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_as!(
+                    ApiWebhook,
+                    "UPDATE webhooks \
+                    SET state = 'todo' \
+                    WHERE id = $1 AND state = 'held' \
+                    RETURNING id, state, execution_time, method, url, body, idempotency_key, cron, calendar, \
+                    max_retries, initial_backoff_ms, timeout_ms, max_latency_ms, signing_secret, \
+                    priority, shard_key, last_status_code, last_response_body, last_latency_ms, \
+                    created_at, started_at, completed_at, \
+                    headers AS \"headers: sqlx::types::Json<WebhookHeaders>\", \
+                    tags AS \"tags: sqlx::types::Json<Vec<String>>\";",
+                    id,
+                )
+                .fetch_optional(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_as(&format!(
+                    "UPDATE webhooks \
+                    SET state = 'todo' \
+                    WHERE id = $1 AND state = 'held' \
+                    RETURNING {};",
+                    crate::db::PG_WEBHOOK_COLUMNS
+                ))
+                .bind(&id)
+                .fetch_optional(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    let webhook = match updated {
+        Ok(wh) => wh,
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Releasing task '{id}' in webhooks table failed with: {e}"
+            );
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespGetTask::Failure {
+                    msg: format!("Releasing task '{id}' in webhooks table failed"),
+                }),
+            );
+        }
+    };
+
+    if let Some(webhook) = webhook {
+        audit_api("webhook", &id, "held", "todo", "released via API");
+        queue_task(&api_ctx, Task::Webhook(webhook.clone())).await;
+        return (
+            axum::http::StatusCode::OK,
+            axum::Json(RespGetTask::task(
+                Task::Webhook(webhook),
+                api_ctx.config.time_precision,
+            )),
+        );
+    }
+
+    let updated = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_as!(
+                    ApiHash,
+                    "UPDATE hashes \
+                    SET state = 'todo' \
+                    WHERE id = $1 AND state = 'held' \
+                    RETURNING id, state, execution_time, secret, algorithm, \
+                    params AS \"params: sqlx::types::Json<serde_json::Value>\", result, \
+                    created_at, started_at, completed_at;",
+                    id,
+                )
+                .fetch_optional(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_as(&format!(
+                    "UPDATE hashes \
+                    SET state = 'todo' \
+                    WHERE id = $1 AND state = 'held' \
+                    RETURNING {};",
+                    crate::db::PG_HASH_COLUMNS
+                ))
+                .bind(&id)
+                .fetch_optional(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    let hash = match updated {
+        Ok(h) => h,
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Releasing task '{id}' in hashes table failed with: {e}"
+            );
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespGetTask::Failure {
+                    msg: format!("Releasing task '{id}' in hashes table failed"),
+                }),
+            );
+        }
+    };
+
+    if let Some(hash) = hash {
+        audit_api("hash", &id, "held", "todo", "released via API");
+        queue_task(&api_ctx, Task::Hash(hash.clone())).await;
+        return (
+            axum::http::StatusCode::OK,
+            axum::Json(RespGetTask::task(
+                Task::Hash(hash),
+                api_ctx.config.time_precision,
+            )),
+        );
+    }
+
+    let (status, msg) = transition_failure(&api_ctx, &id, "released").await;
+    (status, axum::Json(RespGetTask::Failure { msg }))
+}
+
+/// Triggers a task (webhook or hash) in state `todo` to be handled right away
+/// instead of at its execution time, by moving its execution time to now and
+/// queueing it again. The entry of the task in the delay queue for its previous
+/// execution time is replaced, and even if it lingers, the worker only claims
+/// a task for the execution time it was queued for, so the stale entry never
+/// handles the task a second time. Returns the triggered task.
+#[allow(clippy::too_many_lines)]
+pub(crate) async fn trigger_task(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> (axum::http::StatusCode, axum::Json<RespGetTask>) {
+    let execution_time = crate::db::timestamp(chrono::Utc::now());
+
+    let triggered = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_as!(
+                    ApiWebhook,
+                    "UPDATE webhooks \
+                    SET execution_time = $2 \
+                    WHERE id = $1 AND state = 'todo' \
+                    RETURNING id, state, execution_time, method, url, body, idempotency_key, cron, calendar, \
+                    max_retries, initial_backoff_ms, timeout_ms, max_latency_ms, signing_secret, \
+                    priority, shard_key, last_status_code, last_response_body, last_latency_ms, \
+                    created_at, started_at, completed_at, \
+                    headers AS \"headers: sqlx::types::Json<WebhookHeaders>\", \
+                    tags AS \"tags: sqlx::types::Json<Vec<String>>\";",
+                    id,
+                    execution_time,
+                )
+                .fetch_optional(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_as(&format!(
+                    "UPDATE webhooks \
+                    SET execution_time = $2 \
+                    WHERE id = $1 AND state = 'todo' \
+                    RETURNING {};",
+                    crate::db::PG_WEBHOOK_COLUMNS
+                ))
+                .bind(&id)
+                .bind(&execution_time)
+                .fetch_optional(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    let webhook = match triggered {
+        Ok(wh) => wh,
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Triggering task '{id}' in webhooks table failed with: {e}"
+            );
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespGetTask::Failure {
+                    msg: format!("Triggering task '{id}' in webhooks table failed"),
+                }),
+            );
+        }
+    };
+
+    if let Some(webhook) = webhook {
+        queue_task(&api_ctx, Task::Webhook(webhook.clone())).await;
+        return (
+            axum::http::StatusCode::OK,
+            axum::Json(RespGetTask::task(
+                Task::Webhook(webhook),
+                api_ctx.config.time_precision,
+            )),
+        );
+    }
+
+    let triggered = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_as!(
+                    ApiHash,
+                    "UPDATE hashes \
+                    SET execution_time = $2 \
+                    WHERE id = $1 AND state = 'todo' \
+                    RETURNING id, state, execution_time, secret, algorithm, \
+                    params AS \"params: sqlx::types::Json<serde_json::Value>\", result, \
+                    created_at, started_at, completed_at;",
+                    id,
+                    execution_time,
+                )
+                .fetch_optional(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_as(&format!(
+                    "UPDATE hashes \
+                    SET execution_time = $2 \
+                    WHERE id = $1 AND state = 'todo' \
+                    RETURNING {};",
+                    crate::db::PG_HASH_COLUMNS
+                ))
+                .bind(&id)
+                .bind(&execution_time)
+                .fetch_optional(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    let hash = match triggered {
+        Ok(h) => h,
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Triggering task '{id}' in hashes table failed with: {e}"
+            );
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespGetTask::Failure {
+                    msg: format!("Triggering task '{id}' in hashes table failed"),
+                }),
+            );
+        }
+    };
+
+    if let Some(hash) = hash {
+        queue_task(&api_ctx, Task::Hash(hash.clone())).await;
+        return (
+            axum::http::StatusCode::OK,
+            axum::Json(RespGetTask::task(
+                Task::Hash(hash),
+                api_ctx.config.time_precision,
+            )),
+        );
+    }
+
+    let (status, msg) = transition_failure(&api_ctx, &id, "triggered").await;
+    (status, axum::Json(RespGetTask::Failure { msg }))
+}
+
+#[derive(Debug, Clone, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct ApiDeadLetter {
+    id: String,
+    task: sqlx::types::Json<serde_json::Value>,
+    reason: String,
+    attempts: sqlx::types::Json<serde_json::Value>,
+    failed_at: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespGetDeadLetters {
+    Failure { msg: String },
+    DeadLetters(Vec<ApiDeadLetter>),
+}
+
+/// Returns all dead letters, i.e., tasks (webhook or hash) that failed
+/// permanently, together with the reason for their failure and the history of
+/// their failed attempts. Dead letters are ordered by the time they failed.
+pub(crate) async fn get_dead_letters(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+) -> (axum::http::StatusCode, axum::Json<RespGetDeadLetters>) {
+    let fetched = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_as!(
+                    ApiDeadLetter,
+                    "SELECT id, \
+                    task AS \"task: sqlx::types::Json<serde_json::Value>\", \
+                    reason, \
+                    attempts AS \"attempts: sqlx::types::Json<serde_json::Value>\", \
+                    failed_at \
+                    FROM dead_letters \
+                    ORDER BY failed_at ASC;",
+                )
+                .fetch_all(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_as(
+                    "SELECT id, \
+                    task, \
+                    reason, \
+                    attempts, \
+                    failed_at \
+                    FROM dead_letters \
+                    ORDER BY failed_at ASC;",
+                )
+                .fetch_all(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    let dead_letters = match fetched {
+        Ok(mut d) => {
+            for dead_letter in &mut d {
+                api_ctx
+                    .config
+                    .time_precision
+                    .reformat(&mut dead_letter.failed_at);
+                api_ctx
+                    .config
+                    .time_precision
+                    .reformat_value(&mut dead_letter.task.0);
+                for attempt in dead_letter.attempts.0.as_array_mut().into_iter().flatten() {
+                    if let Some(serde_json::Value::String(at)) = attempt.get_mut("at") {
+                        api_ctx.config.time_precision.reformat(at);
+                    }
+                }
+            }
+            d
+        }
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Failed to retrieve dead letters from database: {e}"
+            );
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespGetDeadLetters::Failure {
+                    msg: "Failed to retrieve dead letters from database".to_string(),
+                }),
+            );
+        }
+    };
+
+    (
+        axum::http::StatusCode::OK,
+        axum::Json(RespGetDeadLetters::DeadLetters(dead_letters)),
+    )
+}
+
+/// Moves dead letter `id` back into the table of its task type in state `todo`,
+/// with an execution time of now, and removes it from the dead letters. Both
+/// happen in one transaction, so a dead letter is requeued at most once.
+#[allow(clippy::too_many_lines)]
+async fn move_dead_letter_back(
+    db_pool: &crate::db::DbPool,
+    id: &str,
+) -> std::result::Result<bool, sqlx::Error> {
+    let execution_time = crate::db::timestamp(chrono::Utc::now());
+
+    let mut tx = db_pool.begin().await?;
+    let webhooks = match &mut tx {
+        crate::db::DbTx::Sqlite(tx) => sqlx::query!(
+            "INSERT INTO webhooks ( \
+                id, state, execution_time, method, url, body, idempotency_key, cron, calendar, \
+                priority, shard_key, max_retries, initial_backoff_ms, timeout_ms, max_latency_ms, \
+                signing_secret, headers, tags, sequence, created_at \
+            ) \
+            SELECT id, 'todo', $2, \
+            COALESCE(json_extract(task, '$.webhook.method'), 'POST'), \
+            json_extract(task, '$.webhook.url'), \
+            json_extract(task, '$.webhook.body'), \
+            json_extract(task, '$.webhook.idempotency_key'), \
+            json_extract(task, '$.webhook.cron'), \
+            json_extract(task, '$.webhook.calendar'), \
+            COALESCE(json_extract(task, '$.webhook.priority'), 5), \
+            json_extract(task, '$.webhook.shard_key'), \
+            COALESCE(json_extract(task, '$.webhook.max_retries'), 5), \
+            COALESCE(json_extract(task, '$.webhook.initial_backoff_ms'), 100), \
+            json_extract(task, '$.webhook.timeout_ms'), \
+            json_extract(task, '$.webhook.max_latency_ms'), \
+            json_extract(task, '$.webhook.signing_secret'), \
+            json_extract(task, '$.webhook.headers'), \
+            json_extract(task, '$.webhook.tags'), \
+            json_extract(task, '$.webhook.sequence'), \
+            $2 \
+            FROM dead_letters \
+            WHERE id = $1 AND type = 'webhook';",
+            id,
+            execution_time,
+        )
+        .execute(&mut **tx)
+        .await?
+        .rows_affected(),
+        crate::db::DbTx::Postgres(tx) => sqlx::query(
+            "INSERT INTO webhooks ( \
+                id, state, execution_time, method, url, body, idempotency_key, cron, calendar, \
+                priority, shard_key, max_retries, initial_backoff_ms, timeout_ms, max_latency_ms, \
+                signing_secret, headers, tags, sequence, created_at \
+            ) \
+            SELECT id, 'todo', $2, \
+            COALESCE(task->'webhook'->>'method', 'POST'), \
+            task->'webhook'->>'url', \
+            task->'webhook'->>'body', \
+            task->'webhook'->>'idempotency_key', \
+            task->'webhook'->>'cron', \
+            task->'webhook'->>'calendar', \
+            COALESCE((task->'webhook'->>'priority')::BIGINT, 5), \
+            task->'webhook'->>'shard_key', \
+            COALESCE((task->'webhook'->>'max_retries')::BIGINT, 5), \
+            COALESCE((task->'webhook'->>'initial_backoff_ms')::BIGINT, 100), \
+            (task->'webhook'->>'timeout_ms')::BIGINT, \
+            (task->'webhook'->>'max_latency_ms')::BIGINT, \
+            task->'webhook'->>'signing_secret', \
+            NULLIF(task->'webhook'->'headers', 'null'::JSONB), \
+            NULLIF(task->'webhook'->'tags', 'null'::JSONB), \
+            COALESCE((task->'webhook'->>'sequence')::BIGINT, 0), \
+            $2 \
+            FROM dead_letters \
+            WHERE id = $1 AND type = 'webhook';",
+        )
+        .bind(id)
+        .bind(&execution_time)
+        .execute(&mut **tx)
+        .await?
+        .rows_affected(),
+    };
+    let hashes = match &mut tx {
+        crate::db::DbTx::Sqlite(tx) => sqlx::query!(
+            "INSERT INTO hashes ( \
+                id, state, execution_time, secret, algorithm, params, created_at \
+            ) \
+            SELECT id, 'todo', $2, \
+            json_extract(task, '$.hash.secret'), \
+            json_extract(task, '$.hash.algorithm'), \
+            json_extract(task, '$.hash.params'), \
+            $2 \
+            FROM dead_letters \
+            WHERE id = $1 AND type = 'hash';",
+            id,
+            execution_time,
+        )
+        .execute(&mut **tx)
+        .await?
+        .rows_affected(),
+        crate::db::DbTx::Postgres(tx) => sqlx::query(
+            "INSERT INTO hashes ( \
+                id, state, execution_time, secret, algorithm, params, created_at \
+            ) \
+            SELECT id, 'todo', $2, \
+            task->'hash'->>'secret', \
+            task->'hash'->>'algorithm', \
+            task->'hash'->'params', \
+            $2 \
+            FROM dead_letters \
+            WHERE id = $1 AND type = 'hash';",
+        )
+        .bind(id)
+        .bind(&execution_time)
+        .execute(&mut **tx)
+        .await?
+        .rows_affected(),
+    };
+    match &mut tx {
+        crate::db::DbTx::Sqlite(tx) => sqlx::query!("DELETE FROM dead_letters WHERE id = $1;", id)
+            .execute(&mut **tx)
+            .await?
+            .rows_affected(),
+        crate::db::DbTx::Postgres(tx) => sqlx::query("DELETE FROM dead_letters WHERE id = $1;")
+            .bind(id)
+            .execute(&mut **tx)
+            .await?
+            .rows_affected(),
+    };
+    tx.commit().await?;
+
+    let task_type = if webhooks > 0 {
+        "webhook"
+    } else if hashes > 0 {
+        "hash"
+    } else {
+        return Ok(false);
+    };
+    audit_api(
+        task_type,
+        id,
+        "failed",
+        "todo",
+        "dead letter requeued via API",
+    );
+    Ok(true)
+}
+
+/// Requeues a dead letter: the failed task (webhook or hash) is moved back from
+/// the dead letters into state `todo` and handled right away, with a fresh set
+/// of retries. Returns the requeued task.
+pub(crate) async fn requeue_dead_letter(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> (axum::http::StatusCode, axum::Json<RespGetTask>) {
+    match crate::db::retry_transient(|| move_dead_letter_back(&api_ctx.db_pool, &id)).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return (
+                axum::http::StatusCode::NOT_FOUND,
+                axum::Json(RespGetTask::Failure {
+                    msg: format!("Dead letter '{id}' does not exist"),
+                }),
+            );
+        }
+        Err(sqlx::Error::Database(err_db)) if err_db.is_unique_violation() => {
+            return (
+                axum::http::StatusCode::CONFLICT,
+                axum::Json(RespGetTask::Failure {
+                    msg: format!("Task '{id}' of dead letter already exists"),
+                }),
+            );
+        }
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Requeueing dead letter '{id}' failed with: {e}"
+            );
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespGetTask::Failure {
+                    msg: format!("Requeueing dead letter '{id}' failed"),
+                }),
+            );
+        }
+    }
+
+    let task = match fetch_task(&api_ctx, &id).await {
+        Ok(t) => t,
+        Err((status, msg)) => return (status, axum::Json(RespGetTask::Failure { msg })),
+    };
+    queue_task(&api_ctx, task.clone()).await;
+
+    (
+        axum::http::StatusCode::OK,
+        axum::Json(RespGetTask::task(task, api_ctx.config.time_precision)),
+    )
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespGetAdminStatus {
+    Failure {
+        msg: String,
+    },
+    Status {
+        tasks: crate::worker::TaskLimitStatus,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        outbound: Option<crate::worker::OutboundStatus>,
+        hosts: std::collections::BTreeMap<String, crate::worker::HostStatus>,
+        scheduling_error: crate::worker::SchedulingErrorStatus,
+    },
+}
+
+/// Reports the runtime status of the worker to operators. This includes the
+/// limit on concurrently handled tasks, the number of tasks currently being
+/// handled, the number of free slots for further tasks, and the depth of the
+/// outbound queue, if configured. For each webhook target host with pending
+/// requests, this includes the host's concurrency limit, the number of requests
+/// currently in flight, and the number of requests queued waiting for a free
+/// slot. Additionally, this includes the scheduling error observed for the
+/// `DelayQueue`, i.e., by how much tasks were yielded later than their
+/// deadline.
+///
+/// Requires the configured admin token as bearer token in header
+/// `Authorization`.
+pub(crate) async fn get_admin_status(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    headers: axum::http::HeaderMap,
+) -> (axum::http::StatusCode, axum::Json<RespGetAdminStatus>) {
+    if let Err((status, msg)) = check_admin(&api_ctx.config, &headers) {
+        return (status, axum::Json(RespGetAdminStatus::Failure { msg }));
+    }
+
+    (
+        axum::http::StatusCode::OK,
+        axum::Json(RespGetAdminStatus::Status {
+            tasks: api_ctx.task_limiter.status(),
+            outbound: api_ctx.outbound.as_ref().map(|o| o.status()),
+            hosts: api_ctx.host_limiter.status(),
+            scheduling_error: api_ctx.scheduling_error.status(),
+        }),
+    )
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub(crate) struct ReqPostAdminConcurrency {
+    limit: usize,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespPostAdminConcurrency {
+    Failure { msg: String },
+    Status(crate::worker::TaskLimitStatus),
+}
+
+/// Changes the limit on tasks the worker handles concurrently at runtime,
+/// without a restart, and reports the resulting status as `GET /admin/status`
+/// does under `tasks`. Raising the limit lets tasks waiting for a free slot
+/// start right away. Lowering it never interrupts tasks in flight: the surplus
+/// slots are retired as these tasks finish. The change lasts until `tasker`
+/// restarts, which starts out with `--max-concurrent-tasks` again.
+///
+/// Requires the configured admin token as bearer token in header
+/// `Authorization`.
+pub(crate) async fn post_admin_concurrency(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    headers: axum::http::HeaderMap,
+    axum::Json(payload): axum::Json<ReqPostAdminConcurrency>,
+) -> (axum::http::StatusCode, axum::Json<RespPostAdminConcurrency>) {
+    if let Err((status, msg)) = check_admin(&api_ctx.config, &headers) {
+        return (
+            status,
+            axum::Json(RespPostAdminConcurrency::Failure { msg }),
+        );
+    }
+
+    if !(1..=tokio::sync::Semaphore::MAX_PERMITS).contains(&payload.limit) {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            axum::Json(RespPostAdminConcurrency::Failure {
+                msg: format!(
+                    "Field 'limit' must lie between 1 and {}",
+                    tokio::sync::Semaphore::MAX_PERMITS
+                ),
+            }),
+        );
+    }
+
+    api_ctx.task_limiter.set_limit(payload.limit);
+    event!(
+        Level::INFO,
+        "Changed limit on concurrently handled tasks to {}",
+        payload.limit
+    );
+
+    (
+        axum::http::StatusCode::OK,
+        axum::Json(RespPostAdminConcurrency::Status(
+            api_ctx.task_limiter.status(),
+        )),
+    )
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespPostAdminReload {
+    Failure { msg: String },
+    Success { cleared: usize, reloaded: usize },
+}
+
+/// Resynchronizes the worker's delay queue with the database without a
+/// restart, e.g. after the database was edited manually: the delay queue is
+/// cleared and then rebuilt from all tasks in state `todo`, just as upon start.
+/// Overdue tasks are handled right away. Tasks created while the queue is
+/// rebuilt are queued exactly once, as queueing a task replaces its previous
+/// entry. Entries that handlers picked up before the queue was cleared can't
+/// fire a task twice either: a task is only ever claimed while in state `todo`
+/// and for the execution time it was queued for. Reports the number of tasks
+/// removed from and reloaded into the queue.
+///
+/// Requires the configured admin token as bearer token in header
+/// `Authorization`.
+pub(crate) async fn post_admin_reload(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    headers: axum::http::HeaderMap,
+) -> (axum::http::StatusCode, axum::Json<RespPostAdminReload>) {
+    if let Err((status, msg)) = check_admin(&api_ctx.config, &headers) {
+        return (status, axum::Json(RespPostAdminReload::Failure { msg }));
+    }
+
+    // Clear the queue before reading the tasks to reload, so that tasks created
+    // in the meantime are either read or queued after the queue was cleared.
+    let (send_reply, recv_reply) = tokio::sync::oneshot::channel();
+    let cleared = match api_ctx
+        .send_cmd
+        .send(crate::worker::WorkerCmd::Clear { reply: send_reply })
+        .await
+    {
+        Ok(()) => recv_reply.await.ok(),
+        Err(_) => None,
+    };
+    let Some(cleared) = cleared else {
         return (
-            axum::http::StatusCode::BAD_REQUEST,
-            axum::Json(RespPostTasksNew::Failure {
-                msg: "Malformed 'hash': field 'execution_time' must contain a \
-                datetime that lies in the future"
-                    .to_string(),
+            axum::http::StatusCode::SERVICE_UNAVAILABLE,
+            axum::Json(RespPostAdminReload::Failure {
+                msg: "Worker is not running".to_string(),
             }),
         );
     };
 
-    if api_ctx
-        .send_task
-        .send((
-            tokio::time::Duration::from_millis(dur_from_now_millis),
-            Task::Hash(ApiHash {
-                id: id_str,
-                state: "todo".to_string(),
-                execution_time: execution_time_str,
-                secret,
-            }),
-        ))
-        .await
-        .is_err()
+    match crate::db::reinsert_tasks(
+        &api_ctx.db_pool,
+        api_ctx.send_task.clone(),
+        None,
+        api_ctx.config.task_types,
+    )
+    .await
     {
-        event!(Level::WARN, "Sending new hash task to delay queue failed");
+        Ok(reloaded) => {
+            event!(
+                Level::INFO,
+                "Reloaded delay queue: removed {cleared} tasks, queued {reloaded} tasks",
+            );
+            (
+                axum::http::StatusCode::OK,
+                axum::Json(RespPostAdminReload::Success { cleared, reloaded }),
+            )
+        }
+        Err(e) => {
+            event!(
+                Level::ERROR,
+                "Reloading tasks into cleared delay queue failed: {e}"
+            );
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespPostAdminReload::Failure {
+                    msg: "Reloading tasks failed after the delay queue was cleared, retry \
+                        or restart to queue all tasks again"
+                        .to_string(),
+                }),
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespGetAdminDrainEstimate {
+    Failure {
+        msg: String,
+    },
+    Estimate {
+        pending: i64,
+        window_secs: u64,
+        completions_per_sec: f64,
+        drain_secs: Option<f64>,
+        drain_time: Option<String>,
+    },
+}
+
+/// Estimates when all pending (`todo` or `in_progress`) tasks will have been
+/// handled, based on the number of tasks the worker finished per second within
+/// the recent throughput window. This purely extrapolates the recent rate and
+/// doesn't account for pending tasks whose execution time lies further in the
+/// future than the estimate. If the worker didn't finish any task within the
+/// window, no estimate can be given.
+///
+/// Requires the configured admin token as bearer token in header
+/// `Authorization`.
+#[allow(clippy::cast_precision_loss, clippy::too_many_lines)]
+pub(crate) async fn get_admin_drain_estimate(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    headers: axum::http::HeaderMap,
+) -> (
+    axum::http::StatusCode,
+    axum::Json<RespGetAdminDrainEstimate>,
+) {
+    if let Err((status, msg)) = check_admin(&api_ctx.config, &headers) {
         return (
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            axum::Json(RespPostTasksNew::Failure {
-                msg: "Sending new hash task to delay queue failed".to_string(),
-            }),
+            status,
+            axum::Json(RespGetAdminDrainEstimate::Failure { msg }),
         );
     }
 
+    let fetched = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_scalar!(
+                    "SELECT COUNT(*) \
+                    FROM webhooks \
+                    WHERE state = 'todo' OR state = 'in_progress';",
+                )
+                .fetch_one(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_scalar(
+                    "SELECT COUNT(*) \
+                    FROM webhooks \
+                    WHERE state = 'todo' OR state = 'in_progress';",
+                )
+                .fetch_one(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    let pending_webhooks = match fetched {
+        Ok(n) => n,
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Failed to count pending webhook tasks in database: {e}"
+            );
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespGetAdminDrainEstimate::Failure {
+                    msg: "Failed to count pending webhook tasks in database".to_string(),
+                }),
+            );
+        }
+    };
+
+    let fetched = crate::db::retry_transient(|| async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_scalar!(
+                    "SELECT COUNT(*) \
+                    FROM hashes \
+                    WHERE state = 'todo' OR state = 'in_progress';",
+                )
+                .fetch_one(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_scalar(
+                    "SELECT COUNT(*) \
+                    FROM hashes \
+                    WHERE state = 'todo' OR state = 'in_progress';",
+                )
+                .fetch_one(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    let pending_hashes = match fetched {
+        Ok(n) => n,
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Failed to count pending hash tasks in database: {e}"
+            );
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespGetAdminDrainEstimate::Failure {
+                    msg: "Failed to count pending hash tasks in database".to_string(),
+                }),
+            );
+        }
+    };
+
+    let pending = pending_webhooks + pending_hashes;
+    let completions_per_sec = api_ctx.throughput.per_sec();
+
+    // Extrapolate the time it takes to handle all pending tasks at the recent rate.
+    let drain_secs = if pending == 0 {
+        Some(0.0)
+    } else if completions_per_sec > 0.0 {
+        Some(pending as f64 / completions_per_sec)
+    } else {
+        None
+    };
+    let drain_time = drain_secs
+        .and_then(|secs| std::time::Duration::try_from_secs_f64(secs).ok())
+        .and_then(|dur| chrono::TimeDelta::from_std(dur).ok())
+        .and_then(|delta| chrono::Utc::now().fixed_offset().checked_add_signed(delta))
+        .map(|t| api_ctx.config.time_precision.format(&t));
+
     (
-        axum::http::StatusCode::CREATED,
-        axum::Json(RespPostTasksNew::Success { id: id.to_string() }),
+        axum::http::StatusCode::OK,
+        axum::Json(RespGetAdminDrainEstimate::Estimate {
+            pending,
+            window_secs: api_ctx.throughput.window().as_secs(),
+            completions_per_sec,
+            drain_secs,
+            drain_time,
+        }),
     )
 }
 
-/// Inserts a new task (either webhook or hash) into the respective database
-/// table after light validation. Also places a task for the worker task onto
-/// the worker queue for handling at the specified execution time.
-pub(crate) async fn post_tasks_new(
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct QueryAdminHostStats {
+    window_secs: Option<u64>,
+}
+
+/// Window over which `GET /admin/host-stats` aggregates finished webhook tasks
+/// if no `window_secs` is supplied.
+const DEFAULT_HOST_STATS_WINDOW_SECS: u64 = 24 * 60 * 60;
+
+/// Longest window over which `GET /admin/host-stats` aggregates finished
+/// webhook tasks.
+const MAX_HOST_STATS_WINDOW_SECS: u64 = 90 * 24 * 60 * 60;
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct HostStats {
+    done: i64,
+    failed: i64,
+    failure_rate: f64,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespGetAdminHostStats {
+    Failure {
+        msg: String,
+    },
+    Stats {
+        window_secs: u64,
+        hosts: std::collections::BTreeMap<String, HostStats>,
+    },
+}
+
+/// Reports for each webhook target host how many webhook tasks to it finished
+/// as `done` and as `failed` within the last `window_secs` seconds, along with
+/// the share of failed ones, to surface flaky integrations. Failed tasks
+/// include those moved to the dead letters. Hosts are parsed from the tasks'
+/// URLs, and tasks of a recurring webhook only count once, with the outcome of
+/// their last fire.
+///
+/// Requires the configured admin token as bearer token in header
+/// `Authorization`.
+#[allow(clippy::cast_precision_loss)]
+pub(crate) async fn get_admin_host_stats(
     axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
-    axum::Json(payload): axum::Json<ReqPostTasksNew>,
-) -> (axum::http::StatusCode, axum::Json<RespPostTasksNew>) {
-    match payload {
-        ReqPostTasksNew::Webhook {
-            execution_time,
-            url,
-            body,
-        } => post_tasks_new_webhook(api_ctx, execution_time, url, body).await,
-        ReqPostTasksNew::Hash {
-            execution_time,
-            secret,
-        } => post_tasks_new_hash(api_ctx, execution_time, secret).await,
+    headers: axum::http::HeaderMap,
+    axum::extract::Query(query): axum::extract::Query<QueryAdminHostStats>,
+) -> (axum::http::StatusCode, axum::Json<RespGetAdminHostStats>) {
+    if let Err((status, msg)) = check_admin(&api_ctx.config, &headers) {
+        return (status, axum::Json(RespGetAdminHostStats::Failure { msg }));
+    }
+
+    let window_secs = query.window_secs.unwrap_or(DEFAULT_HOST_STATS_WINDOW_SECS);
+    if !(1..=MAX_HOST_STATS_WINDOW_SECS).contains(&window_secs) {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            axum::Json(RespGetAdminHostStats::Failure {
+                msg: format!(
+                    "Query parameter 'window_secs' must lie between 1 and {MAX_HOST_STATS_WINDOW_SECS}"
+                ),
+            }),
+        );
+    }
+    let cutoff = crate::db::timestamp(
+        chrono::Utc::now()
+            - chrono::TimeDelta::seconds(i64::try_from(window_secs).unwrap_or(i64::MAX)),
+    );
+
+    // Count per distinct URL in the database, and group the URLs by host below.
+    let fetched: std::result::Result<Vec<(String, String, i64)>, sqlx::Error> =
+        crate::db::retry_transient(|| async {
+            match &api_ctx.db_pool {
+                crate::db::DbPool::Sqlite(db_pool) => sqlx::query!(
+                    "SELECT url, state, COUNT(*) AS \"count!: i64\" \
+                    FROM webhooks \
+                    WHERE state IN ('done', 'failed') AND completed_at >= $1 \
+                    GROUP BY url, state \
+                    UNION ALL \
+                    SELECT json_extract(task, '$.webhook.url') AS \"url!: String\", 'failed', COUNT(*) \
+                    FROM dead_letters \
+                    WHERE type = 'webhook' AND failed_at >= $1 \
+                    GROUP BY 1;",
+                    cutoff,
+                )
+                .fetch_all(db_pool)
+                .await
+                .map(|rows| {
+                    rows.into_iter()
+                        .map(|row| (row.url, row.state, row.count))
+                        .collect()
+                }),
+                crate::db::DbPool::Postgres(db_pool) => {
+                    sqlx::query_as(
+                        "SELECT url, state, COUNT(*) \
+                        FROM webhooks \
+                        WHERE state IN ('done', 'failed') AND completed_at >= $1 \
+                        GROUP BY url, state \
+                        UNION ALL \
+                        SELECT task->'webhook'->>'url', 'failed', COUNT(*) \
+                        FROM dead_letters \
+                        WHERE type = 'webhook' AND failed_at >= $1 \
+                        GROUP BY 1;",
+                    )
+                    .bind(&cutoff)
+                    .fetch_all(db_pool)
+                    .await
+                }
+            }
+        })
+        .await;
+    let counts = match fetched {
+        Ok(c) => c,
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Failed to count finished webhook tasks per URL in database: {e}"
+            );
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(RespGetAdminHostStats::Failure {
+                    msg: "Failed to count finished webhook tasks in database".to_string(),
+                }),
+            );
+        }
+    };
+
+    let mut hosts = std::collections::BTreeMap::<String, HostStats>::new();
+    for (url, state, count) in counts {
+        let stats = hosts.entry(crate::worker::webhook_host(&url)).or_default();
+        if state == "done" {
+            stats.done += count;
+        } else {
+            stats.failed += count;
+        }
     }
+    for stats in hosts.values_mut() {
+        stats.failure_rate = stats.failed as f64 / (stats.done + stats.failed) as f64;
+    }
+
+    (
+        axum::http::StatusCode::OK,
+        axum::Json(RespGetAdminHostStats::Stats { window_secs, hosts }),
+    )
 }
 
+/// Tables whose columns are reported via `get_admin_schema`.
+const SCHEMA_TABLES: [&str; 2] = ["webhooks", "hashes"];
+
 #[derive(Debug, Clone, serde::Serialize)]
 #[serde(rename_all = "snake_case")]
-pub(crate) enum RespGetTask {
-    Failure { msg: String },
-    Webhook(ApiWebhook),
-    Hash(ApiHash),
+pub(crate) struct SchemaColumn {
+    name: String,
+    #[serde(rename = "type")]
+    column_type: String,
+    not_null: bool,
+    primary_key: bool,
 }
 
-/// Returns all details about the specified task (webhook or hash) from the
-/// respective table. We rely on the property that collisions when generating
-/// UUIDs are exceedingly unlikely, and can thus be ignored. If we thus find the
-/// task in the `webhooks` table, we do not query the `hashes` table anymore. If
-/// we also do not find the task in the `hashes` table, we report this fact to
-/// the caller.
-pub(crate) async fn get_task(
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespGetAdminSchema {
+    Failure {
+        msg: String,
+    },
+    Schema {
+        tables: std::collections::BTreeMap<&'static str, Vec<SchemaColumn>>,
+    },
+}
+
+/// Reports the columns of the task tables as they exist in the database, with
+/// their types and constraints, in the order they were added. This helps to
+/// diagnose mismatches between the build of `tasker` and the schema of a
+/// database it was pointed at.
+///
+/// Requires the configured admin token as bearer token in header
+/// `Authorization`.
+pub(crate) async fn get_admin_schema(
     axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
-    axum::extract::Path(id): axum::extract::Path<String>,
-) -> (axum::http::StatusCode, axum::Json<RespGetTask>) {
-    match sqlx::query_as!(
-        ApiWebhook,
-        "SELECT id, state, execution_time, url, body \
-        FROM webhooks \
-        WHERE id = $1;",
-        id,
-    )
-    .fetch_one(&api_ctx.db_pool)
-    .await
-    {
-        Ok(webhook) => (
-            axum::http::StatusCode::OK,
-            axum::Json(RespGetTask::Webhook(webhook)),
-        ),
-        Err(e) => {
-            if let sqlx::Error::RowNotFound = e {
-                match sqlx::query_as!(
-                    ApiHash,
-                    "SELECT id, state, execution_time, secret \
-                    FROM hashes \
-                    WHERE id = $1;",
-                    id,
-                )
-                .fetch_one(&api_ctx.db_pool)
-                .await
-                {
-                    Ok(hash) => (
-                        axum::http::StatusCode::OK,
-                        axum::Json(RespGetTask::Hash(hash)),
-                    ),
-                    Err(e) => {
-                        if let sqlx::Error::RowNotFound = e {
-                            (
-                                axum::http::StatusCode::NOT_FOUND,
-                                axum::Json(RespGetTask::Failure {
-                                    msg: format!("Task '{id}' does not exist"),
-                                }),
-                            )
-                        } else {
-                            event!(
-                                Level::WARN,
-                                "Fetching task '{id}' from 'hashes' table failed: {e}"
-                            );
-                            (
-                                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                                axum::Json(RespGetTask::Failure {
-                                    msg: format!("Fetching task '{id}' from 'hashes' table failed"),
-                                }),
-                            )
-                        }
-                    }
+    headers: axum::http::HeaderMap,
+) -> (axum::http::StatusCode, axum::Json<RespGetAdminSchema>) {
+    if let Err((status, msg)) = check_admin(&api_ctx.config, &headers) {
+        return (status, axum::Json(RespGetAdminSchema::Failure { msg }));
+    }
+
+    let mut tables = std::collections::BTreeMap::new();
+    for table in SCHEMA_TABLES {
+        // The query macros can't describe the columns of `pragma_table_info`.
+        let described = crate::db::retry_transient(|| async {
+            match &api_ctx.db_pool {
+                crate::db::DbPool::Sqlite(db_pool) => {
+                    sqlx::query_as::<_, (String, String, bool, bool)>(
+                        "SELECT name, type, \"notnull\", pk > 0 FROM pragma_table_info($1) ORDER BY cid;",
+                    )
+                    .bind(table)
+                    .fetch_all(db_pool)
+                    .await
                 }
-            } else {
+                crate::db::DbPool::Postgres(db_pool) => {
+                    sqlx::query_as::<_, (String, String, bool, bool)>(
+                        "SELECT c.column_name::TEXT, UPPER(c.data_type)::TEXT, c.is_nullable = 'NO', \
+                        EXISTS ( \
+                            SELECT 1 \
+                            FROM information_schema.table_constraints AS t \
+                            JOIN information_schema.key_column_usage AS k \
+                            ON k.constraint_schema = t.constraint_schema \
+                            AND k.constraint_name = t.constraint_name \
+                            WHERE t.constraint_type = 'PRIMARY KEY' \
+                            AND t.table_schema = c.table_schema \
+                            AND t.table_name = c.table_name \
+                            AND k.column_name = c.column_name \
+                        ) \
+                        FROM information_schema.columns AS c \
+                        WHERE c.table_schema = current_schema() AND c.table_name = $1 \
+                        ORDER BY c.ordinal_position;",
+                    )
+                    .bind(table)
+                    .fetch_all(db_pool)
+                    .await
+                }
+            }
+        })
+        .await;
+        let columns = match described {
+            Ok(c) => c
+                .into_iter()
+                .map(|(name, column_type, not_null, primary_key)| SchemaColumn {
+                    name,
+                    column_type,
+                    not_null,
+                    primary_key,
+                })
+                .collect::<Vec<_>>(),
+            Err(e) => {
                 event!(
                     Level::WARN,
-                    "Fetching task '{id}' from 'webhooks' table failed: {e}"
+                    "Failed to retrieve columns of table '{table}' from database: {e}"
                 );
-                (
+                return (
                     axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                    axum::Json(RespGetTask::Failure {
-                        msg: format!("Fetching task '{id}' from 'webhooks' table failed"),
+                    axum::Json(RespGetAdminSchema::Failure {
+                        msg: format!("Failed to retrieve columns of table '{table}'"),
                     }),
+                );
+            }
+        };
+        tables.insert(table, columns);
+    }
+
+    (
+        axum::http::StatusCode::OK,
+        axum::Json(RespGetAdminSchema::Schema { tables }),
+    )
+}
+
+/// Values of the graded signals of the health score at which their components
+/// drop to zero points.
+#[derive(Debug, Clone)]
+pub(crate) struct HealthThresholds {
+    pub(crate) overdue_tasks: u64,
+    pub(crate) failure_rate: f64,
+    pub(crate) queue_depth: u64,
+}
+
+/// Points each signal contributes to the health score if it is fully healthy,
+/// adding up to 100.
+const HEALTH_WEIGHT_WORKER_ALIVE: u32 = 30;
+const HEALTH_WEIGHT_DB_REACHABLE: u32 = 30;
+const HEALTH_WEIGHT_OVERDUE_TASKS: u32 = 15;
+const HEALTH_WEIGHT_FAILURE_RATE: u32 = 15;
+const HEALTH_WEIGHT_QUEUE_DEPTH: u32 = 10;
+
+/// Time by which the execution time of a task in state `todo` must have passed
+/// for the task to count as overdue in the health score. Tasks are regularly
+/// handled slightly after their execution time, e.g., while waiting for a free
+/// slot.
+const HEALTH_OVERDUE_GRACE: chrono::TimeDelta = chrono::TimeDelta::seconds(60);
+
+/// Window over which the failure rate of the health score is computed.
+const HEALTH_FAILURE_WINDOW: chrono::TimeDelta = chrono::TimeDelta::hours(1);
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct HealthComponent {
+    /// Observed value of the signal, `null` if it couldn't be determined.
+    value: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    threshold: Option<serde_json::Value>,
+    weight: u32,
+    points: f64,
+}
+
+impl HealthComponent {
+    /// Awards the full `weight` if `healthy`, and no points otherwise.
+    fn binary(healthy: bool, weight: u32) -> Self {
+        HealthComponent {
+            value: healthy.into(),
+            threshold: None,
+            weight,
+            points: if healthy { f64::from(weight) } else { 0.0 },
+        }
+    }
+
+    /// Awards points decreasing linearly from the full `weight` at a `value` of
+    /// zero down to none at `threshold`. An unknown `value` earns no points.
+    fn graded(value: Option<serde_json::Value>, threshold: serde_json::Value, weight: u32) -> Self {
+        let points = value
+            .as_ref()
+            .and_then(serde_json::Value::as_f64)
+            .zip(threshold.as_f64())
+            .map_or(0.0, |(v, t)| {
+                f64::from(weight) * (1.0 - (v / t).clamp(0.0, 1.0))
+            });
+        HealthComponent {
+            value: value.unwrap_or_default(),
+            threshold: Some(threshold),
+            weight,
+            points,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespGetAdminHealthScore {
+    Failure {
+        msg: String,
+    },
+    Score {
+        score: u32,
+        components: std::collections::BTreeMap<&'static str, HealthComponent>,
+    },
+}
+
+/// The signals collected from the database for the health score.
+#[derive(Debug, sqlx::FromRow)]
+struct HealthSignals {
+    overdue: i64,
+    pending: i64,
+    done: i64,
+    failed: i64,
+}
+
+/// Computes a single health score between 0 and 100 from the signals already
+/// tracked, along with the breakdown into the points each signal contributed:
+/// whether the worker is running (30 points), whether the database answers in
+/// time (30), the number of overdue tasks (15), the share of failed tasks among
+/// those finished within the last hour (15), and the number of pending tasks
+/// (10). The graded signals earn points decreasing linearly from their full
+/// weight down to none at their configured thresholds. Signals that depend on
+/// an unreachable database earn no points.
+///
+/// Requires the configured admin token as bearer token in header
+/// `Authorization`.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::too_many_lines
+)]
+pub(crate) async fn get_admin_health_score(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    headers: axum::http::HeaderMap,
+) -> (axum::http::StatusCode, axum::Json<RespGetAdminHealthScore>) {
+    if let Err((status, msg)) = check_admin(&api_ctx.config, &headers) {
+        return (status, axum::Json(RespGetAdminHealthScore::Failure { msg }));
+    }
+
+    let now = chrono::Utc::now();
+    let overdue_before = crate::db::timestamp(now - HEALTH_OVERDUE_GRACE);
+    let finished_after = crate::db::timestamp(now - HEALTH_FAILURE_WINDOW);
+    let signals = async {
+        match &api_ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_as!(
+                    HealthSignals,
+                    "SELECT \
+                    ( SELECT COUNT(*) FROM webhooks \
+                        WHERE state = 'todo' AND julianday(execution_time) < julianday($1) ) + \
+                    ( SELECT COUNT(*) FROM hashes \
+                        WHERE state = 'todo' AND julianday(execution_time) < julianday($1) ) \
+                        AS \"overdue!: i64\", \
+                    ( SELECT COUNT(*) FROM webhooks WHERE state = 'todo' OR state = 'in_progress' ) + \
+                    ( SELECT COUNT(*) FROM hashes WHERE state = 'todo' OR state = 'in_progress' ) \
+                        AS \"pending!: i64\", \
+                    ( SELECT COUNT(*) FROM webhooks WHERE state = 'done' AND completed_at >= $2 ) + \
+                    ( SELECT COUNT(*) FROM hashes WHERE state = 'done' AND completed_at >= $2 ) \
+                        AS \"done!: i64\", \
+                    ( SELECT COUNT(*) FROM webhooks WHERE state = 'failed' AND completed_at >= $2 ) + \
+                    ( SELECT COUNT(*) FROM hashes WHERE state = 'failed' AND completed_at >= $2 ) + \
+                    ( SELECT COUNT(*) FROM dead_letters WHERE failed_at >= $2 ) \
+                        AS \"failed!: i64\";",
+                    overdue_before,
+                    finished_after,
+                )
+                .fetch_one(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_as(
+                    "SELECT \
+                    ( SELECT COUNT(*) FROM webhooks \
+                        WHERE state = 'todo' \
+                        AND execution_time::TIMESTAMPTZ < $1::TIMESTAMPTZ ) + \
+                    ( SELECT COUNT(*) FROM hashes \
+                        WHERE state = 'todo' \
+                        AND execution_time::TIMESTAMPTZ < $1::TIMESTAMPTZ ) \
+                        AS overdue, \
+                    ( SELECT COUNT(*) FROM webhooks WHERE state = 'todo' OR state = 'in_progress' ) + \
+                    ( SELECT COUNT(*) FROM hashes WHERE state = 'todo' OR state = 'in_progress' ) \
+                        AS pending, \
+                    ( SELECT COUNT(*) FROM webhooks WHERE state = 'done' AND completed_at >= $2 ) + \
+                    ( SELECT COUNT(*) FROM hashes WHERE state = 'done' AND completed_at >= $2 ) \
+                        AS done, \
+                    ( SELECT COUNT(*) FROM webhooks WHERE state = 'failed' AND completed_at >= $2 ) + \
+                    ( SELECT COUNT(*) FROM hashes WHERE state = 'failed' AND completed_at >= $2 ) + \
+                    ( SELECT COUNT(*) FROM dead_letters WHERE failed_at >= $2 ) \
+                        AS failed;",
                 )
+                .bind(&overdue_before)
+                .bind(&finished_after)
+                .fetch_one(db_pool)
+                .await
             }
         }
+    };
+    let signals = match tokio::time::timeout(READY_TIMEOUT, signals).await {
+        Ok(Ok(s)) => Some(s),
+        Ok(Err(e)) => {
+            event!(
+                Level::WARN,
+                "Collecting health signals from database failed: {e}"
+            );
+            None
+        }
+        Err(_) => {
+            event!(
+                Level::WARN,
+                "Collecting health signals from database timed out"
+            );
+            None
+        }
+    };
+
+    let thresholds = &api_ctx.config.health_thresholds;
+    let worker_alive = api_ctx
+        .worker_alive
+        .load(std::sync::atomic::Ordering::Acquire);
+    let failure_rate = signals.as_ref().map(|s| {
+        let finished = s.done + s.failed;
+        if finished == 0 {
+            0.0
+        } else {
+            s.failed as f64 / finished as f64
+        }
+    });
+
+    let components = std::collections::BTreeMap::from([
+        (
+            "worker_alive",
+            HealthComponent::binary(worker_alive, HEALTH_WEIGHT_WORKER_ALIVE),
+        ),
+        (
+            "db_reachable",
+            HealthComponent::binary(signals.is_some(), HEALTH_WEIGHT_DB_REACHABLE),
+        ),
+        (
+            "overdue_tasks",
+            HealthComponent::graded(
+                signals.as_ref().map(|s| s.overdue.into()),
+                thresholds.overdue_tasks.into(),
+                HEALTH_WEIGHT_OVERDUE_TASKS,
+            ),
+        ),
+        (
+            "failure_rate",
+            HealthComponent::graded(
+                failure_rate.map(serde_json::Value::from),
+                thresholds.failure_rate.into(),
+                HEALTH_WEIGHT_FAILURE_RATE,
+            ),
+        ),
+        (
+            "queue_depth",
+            HealthComponent::graded(
+                signals.as_ref().map(|s| s.pending.into()),
+                thresholds.queue_depth.into(),
+                HEALTH_WEIGHT_QUEUE_DEPTH,
+            ),
+        ),
+    ]);
+    // The points of all components add up to a value between 0 and 100.
+    let score = components.values().map(|c| c.points).sum::<f64>().round();
+
+    (
+        axum::http::StatusCode::OK,
+        axum::Json(RespGetAdminHealthScore::Score {
+            score: score as u32,
+            components,
+        }),
+    )
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub(crate) struct ReqPostAdminLinks {
+    #[serde(rename = "type")]
+    task_type: String,
+    #[serde(default)]
+    fields: serde_json::Map<String, serde_json::Value>,
+    expires_in_secs: u64,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged, rename_all = "snake_case")]
+pub(crate) enum RespPostAdminLinks {
+    Failure { msg: String },
+    Link { link: String, expires_at: String },
+}
+
+/// Validates the requested shape and expiry of a signed link and turns them
+/// into the grant to sign.
+fn prepare_link_grant(
+    req: ReqPostAdminLinks,
+) -> std::result::Result<crate::links::LinkGrant, String> {
+    let allowed = match req.task_type.as_str() {
+        "webhook" => WEBHOOK_FIELDS,
+        "hash" => HASH_FIELDS,
+        _ => return Err("Field 'type' needs to be one of: 'webhook', 'hash'".to_string()),
+    };
+
+    // The ID and state of new tasks are never supplied by the user.
+    for field in req.fields.keys() {
+        if !allowed.contains(&field.as_str()) || field == "id" || field == "state" {
+            return Err(format!(
+                "Field 'fields' can't restrict field '{field}' of {} tasks",
+                req.task_type
+            ));
+        }
     }
+
+    if !(1..=crate::links::MAX_LINK_TTL_SECS).contains(&req.expires_in_secs) {
+        return Err(format!(
+            "Field 'expires_in_secs' must lie between 1 and {}",
+            crate::links::MAX_LINK_TTL_SECS
+        ));
+    }
+    let expires_at =
+        chrono::Utc::now().timestamp() + i64::try_from(req.expires_in_secs).unwrap_or(i64::MAX);
+
+    Ok(crate::links::LinkGrant {
+        task_type: req.task_type,
+        fields: req.fields,
+        expires_at,
+    })
+}
+
+/// Returns the bearer token in header `Authorization` of a request, if any.
+fn bearer_token(headers: &axum::http::HeaderMap) -> Option<&str> {
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
-#[serde(untagged, rename_all = "snake_case")]
-pub(crate) enum RespGetTasksByState {
-    Failure { msg: String },
-    Tasks(Vec<Task>),
+#[serde(rename_all = "snake_case")]
+pub(crate) struct RespUnauthorized {
+    msg: String,
 }
 
-/// Returns the list of tasks (containing potentially both webhook tasks and
-/// hash tasks) in the specified state to the caller.
-pub(crate) async fn get_tasks_by_state(
+/// Makes sure a request carries the configured API key as bearer token in
+/// header `Authorization`, before passing it on to `next`. Rejects requests
+/// without a matching key with `401 Unauthorized`. Passes on all requests if
+/// no API key is configured.
+pub(crate) async fn require_api_key(
     axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
-    axum::extract::Path(state): axum::extract::Path<String>,
-) -> (axum::http::StatusCode, axum::Json<RespGetTasksByState>) {
-    let state = state.to_lowercase();
-    if (state != "todo") && (state != "in_progress") && (state != "failed") && (state != "done") {
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    if let Some(api_key) = &api_ctx.config.api_key
+        && !bearer_token(request.headers()).is_some_and(|t| api_key.matches(t))
+    {
         return (
-            axum::http::StatusCode::BAD_REQUEST,
-            axum::Json(RespGetTasksByState::Failure {
-                msg: "Field 'state' needs to be one of: 'todo', 'in_progress', 'failed', 'done'"
-                    .to_string(),
+            axum::http::StatusCode::UNAUTHORIZED,
+            [(axum::http::header::WWW_AUTHENTICATE, "Bearer")],
+            axum::Json(RespUnauthorized {
+                msg: "Header 'Authorization' must carry the API key".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    next.run(request).await
+}
+
+/// Makes sure a request to an admin endpoint carries the configured admin token
+/// as bearer token in header `Authorization`. Returns the status and message to
+/// report to the user otherwise, or if no admin token is configured.
+fn check_admin(
+    config: &ApiConfig,
+    headers: &axum::http::HeaderMap,
+) -> std::result::Result<(), (axum::http::StatusCode, String)> {
+    let Some(admin_token) = &config.admin_token else {
+        return Err((
+            axum::http::StatusCode::NOT_FOUND,
+            "Admin endpoints are not configured".to_string(),
+        ));
+    };
+
+    if !bearer_token(headers).is_some_and(|t| admin_token.matches(t)) {
+        return Err((
+            axum::http::StatusCode::UNAUTHORIZED,
+            "Header 'Authorization' must carry the admin token".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Issues a signed link authorizing the creation of a single shape of task
+/// until the link expires: tasks of the requested type whose fields listed in
+/// `fields` carry exactly the supplied values, as they would be stored. The
+/// returned link is passed to `POST /tasks/new` via query parameter `link`.
+///
+/// Requires the configured admin token as bearer token in header
+/// `Authorization`.
+pub(crate) async fn post_admin_links(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+    headers: axum::http::HeaderMap,
+    axum::Json(payload): axum::Json<ReqPostAdminLinks>,
+) -> (axum::http::StatusCode, axum::Json<RespPostAdminLinks>) {
+    let Some(links) = &api_ctx.config.links else {
+        return (
+            axum::http::StatusCode::NOT_FOUND,
+            axum::Json(RespPostAdminLinks::Failure {
+                msg: "Signed links are not configured".to_string(),
             }),
         );
+    };
+
+    if let Err((status, msg)) = check_admin(&api_ctx.config, &headers) {
+        return (status, axum::Json(RespPostAdminLinks::Failure { msg }));
     }
 
-    let webhooks = match sqlx::query_as!(
-        ApiWebhook,
-        "SELECT id, state, execution_time, url, body \
-        FROM webhooks \
-        WHERE state = $1 \
-        ORDER BY execution_time ASC;",
-        state,
-    )
-    .fetch_all(&api_ctx.db_pool)
-    .await
-    {
-        Ok(t) => t,
-        Err(e) => {
-            event!(
-                Level::WARN,
-                "Failed to retrieve webhook tasks from database: {e}"
-            );
+    let grant = match prepare_link_grant(payload) {
+        Ok(g) => g,
+        Err(msg) => {
             return (
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                axum::Json(RespGetTasksByState::Failure {
-                    msg: "Failed to retrieve webhook tasks from database".to_string(),
-                }),
+                axum::http::StatusCode::BAD_REQUEST,
+                axum::Json(RespPostAdminLinks::Failure { msg }),
             );
         }
     };
 
-    let hashes = match sqlx::query_as!(
-        ApiHash,
-        "SELECT id, state, execution_time, secret \
-        FROM hashes \
-        WHERE state = $1 \
-        ORDER BY execution_time ASC;",
-        state,
-    )
-    .fetch_all(&api_ctx.db_pool)
-    .await
-    {
-        Ok(h) => h,
+    let link = match links.sign(&grant) {
+        Ok(l) => l,
         Err(e) => {
-            event!(
-                Level::WARN,
-                "Failed to retrieve hash tasks from database: {e}"
-            );
+            event!(Level::WARN, "Signing link failed with: {e}");
             return (
                 axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                axum::Json(RespGetTasksByState::Failure {
-                    msg: "Failed to retrieve hash tasks from database".to_string(),
+                axum::Json(RespPostAdminLinks::Failure {
+                    msg: "Signing link failed".to_string(),
                 }),
             );
         }
     };
-
-    // Create one tasks list by combining the webhooks and the hashes lists.
-    let mut tasks = std::vec::Vec::<Task>::with_capacity(webhooks.len() + hashes.len());
-    for webhook in webhooks {
-        tasks.push(Task::Webhook(webhook));
-    }
-    for hash in hashes {
-        tasks.push(Task::Hash(hash));
-    }
+    let expires_at = chrono::DateTime::from_timestamp(grant.expires_at, 0)
+        .map(|t| api_ctx.config.time_precision.format(&t))
+        .unwrap_or_default();
 
     (
         axum::http::StatusCode::OK,
-        axum::Json(RespGetTasksByState::Tasks(tasks)),
+        axum::Json(RespPostAdminLinks::Link { link, expires_at }),
     )
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
-#[serde(untagged, rename_all = "snake_case")]
-pub(crate) enum RespGetTasksByType {
-    Failure { msg: String },
-    Webhooks(Vec<ApiWebhook>),
-    Hashes(Vec<ApiHash>),
+/// Time after which a readiness check of the database counts as failed, well
+/// below the timeouts usually configured for readiness probes.
+const READY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Reports that the HTTP API is up, for liveness probes. Answering at all is
+/// the only criterion.
+pub(crate) async fn get_healthz() -> (axum::http::StatusCode, &'static str) {
+    (axum::http::StatusCode::OK, "ok")
 }
 
-/// Handles the case that the user requested all webhook tasks.
-pub(crate) async fn get_webhooks(
-    api_ctx: ApiCtx,
-) -> (axum::http::StatusCode, axum::Json<RespGetTasksByType>) {
-    let webhooks = match sqlx::query_as!(
-        ApiWebhook,
-        "SELECT id, state, execution_time, url, body \
-        FROM webhooks \
-        ORDER BY execution_time ASC;",
-    )
-    .fetch_all(&api_ctx.db_pool)
-    .await
-    {
-        Ok(t) => t,
-        Err(e) => {
-            event!(
-                Level::WARN,
-                "Failed to retrieve webhook tasks from database: {e}"
-            );
-            return (
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                axum::Json(RespGetTasksByType::Failure {
-                    msg: "Failed to retrieve webhook tasks from database".to_string(),
-                }),
-            );
+/// Reports whether this scheduler is ready to serve requests, for readiness
+/// probes, which is the case if the database answers a trivial query in time.
+pub(crate) async fn get_readyz(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+) -> (axum::http::StatusCode, &'static str) {
+    match tokio::time::timeout(READY_TIMEOUT, crate::db::ping(&api_ctx.db_pool)).await {
+        Ok(Ok(())) => (axum::http::StatusCode::OK, "ok"),
+        Ok(Err(e)) => {
+            event!(Level::WARN, "Readiness check of database failed: {e}");
+            (
+                axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                "database unreachable",
+            )
         }
-    };
+        Err(_) => {
+            event!(Level::WARN, "Readiness check of database timed out");
+            (
+                axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                "database unreachable",
+            )
+        }
+    }
+}
 
+/// Exports all metrics recorded by this scheduler in the Prometheus text
+/// exposition format, for scraping by Prometheus.
+pub(crate) async fn get_metrics(
+    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
+) -> (
+    axum::http::StatusCode,
+    [(axum::http::HeaderName, &'static str); 1],
+    String,
+) {
     (
         axum::http::StatusCode::OK,
-        axum::Json(RespGetTasksByType::Webhooks(webhooks)),
+        [(
+            axum::http::header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        api_ctx.metrics.render(),
     )
 }
 
-/// Handles the case that the user requested all hash tasks.
-pub(crate) async fn get_hashes(
-    api_ctx: ApiCtx,
-) -> (axum::http::StatusCode, axum::Json<RespGetTasksByType>) {
-    let hashes = match sqlx::query_as!(
-        ApiHash,
-        "SELECT id, state, execution_time, secret \
-        FROM hashes \
-        ORDER BY execution_time ASC;",
-    )
-    .fetch_all(&api_ctx.db_pool)
-    .await
-    {
-        Ok(t) => t,
-        Err(e) => {
-            event!(
-                Level::WARN,
-                "Failed to retrieve hash tasks from database: {e}"
-            );
-            return (
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                axum::Json(RespGetTasksByType::Failure {
-                    msg: "Failed to retrieve hash tasks from database".to_string(),
-                }),
-            );
-        }
-    };
+#[cfg(test)]
+mod tests {
+    use crate::testing::TestApp;
 
-    (
-        axum::http::StatusCode::OK,
-        axum::Json(RespGetTasksByType::Hashes(hashes)),
-    )
-}
+    /// Returns the request creating a webhook task for `url` that executes in
+    /// an hour.
+    fn webhook_in_an_hour(url: &str) -> serde_json::Value {
+        let execution_time = chrono::Utc::now() + chrono::TimeDelta::hours(1);
+        serde_json::json!({
+            "webhook": {
+                "url": url,
+                "body": "{}",
+                "execution_time": execution_time.to_rfc3339(),
+            }
+        })
+    }
 
-/// Returns all tasks to the user that are of the specified type (webhook or
-/// hash). Tasks are ordered by their ID in ascending order, which should mean
-/// chronological insertion order.
-pub(crate) async fn get_tasks_by_type(
-    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
-    axum::extract::Path(task_type): axum::extract::Path<String>,
-) -> (axum::http::StatusCode, axum::Json<RespGetTasksByType>) {
-    let task_type = task_type.to_lowercase();
-    if task_type == "webhook" {
-        get_webhooks(api_ctx).await
-    } else if task_type == "hash" {
-        get_hashes(api_ctx).await
-    } else {
-        (
-            axum::http::StatusCode::BAD_REQUEST,
-            axum::Json(RespGetTasksByType::Failure {
-                msg: "Unsupported task type, use either 'webhook' or 'hash'".to_string(),
-            }),
+    #[tokio::test]
+    async fn failed_insert_queues_no_task() {
+        let mut app = TestApp::without_worker(&[]).await;
+        // Without its table, inserting the task fails on every backend.
+        app.execute("ALTER TABLE webhooks RENAME TO rejected_webhooks;")
+            .await;
+
+        let (status, body) = app
+            .post("/tasks/new", &webhook_in_an_hour("http://127.0.0.1:9/"))
+            .await;
+        assert_eq!(status, reqwest::StatusCode::INTERNAL_SERVER_ERROR, "{body}");
+        assert!(app.recv_task.as_mut().unwrap().try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn failed_queueing_still_creates_task() {
+        let mut app = TestApp::without_worker(&[]).await;
+        drop(app.recv_task.take());
+
+        let (status, body) = app
+            .post("/tasks/new", &webhook_in_an_hour("http://127.0.0.1:9/"))
+            .await;
+        assert_eq!(status, reqwest::StatusCode::CREATED, "{body}");
+
+        // The stored task is queued again upon the next start.
+        let (send_task, mut recv_task) = tokio::sync::mpsc::channel(8);
+        crate::db::reinsert_tasks(
+            &app.db_pool,
+            send_task,
+            None,
+            crate::api::TaskTypes {
+                webhook: true,
+                hash: true,
+            },
         )
+        .await
+        .unwrap();
+        let (_, task) = recv_task.recv().await.unwrap();
+        let crate::api::Task::Webhook(webhook) = task else {
+            panic!("reinserted task is not a webhook: {task:?}");
+        };
+        assert_eq!(webhook.id, body["id"]);
     }
-}
 
-#[derive(Debug, Clone, serde::Serialize)]
-#[serde(untagged, rename_all = "snake_case")]
-pub(crate) enum RespDeleteTask {
-    Failure { msg: String },
-    Success {},
-}
+    #[tokio::test]
+    async fn readiness_reflects_database_availability() {
+        let app = TestApp::without_worker(&[]).await;
+        let (status, _) = app.get("/healthz").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        let (status, _) = app.get("/readyz").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
 
-/// Deletes a task (webhook or hash) from the respective table. We rely on the
-/// property that collisions when generating UUIDs are exceedingly unlikely, and
-/// can thus be ignored. We thus attempt to delete the task from the webhooks
-/// table first and if no row was affected, then from the hashes table. If
-/// neither of the two queries succeeded, the task didn't exist and report that
-/// back to the caller.
-pub(crate) async fn delete_task(
-    axum::extract::State(api_ctx): axum::extract::State<ApiCtx>,
-    axum::extract::Path(id): axum::extract::Path<String>,
-) -> (axum::http::StatusCode, axum::Json<RespDeleteTask>) {
-    let num_del_webhooks = match sqlx::query!(
-        "DELETE FROM webhooks \
-        WHERE id = $1 AND state != $2;",
-        id,
-        "in_progress",
-    )
-    .execute(&api_ctx.db_pool)
-    .await
-    {
-        Ok(t) => t.rows_affected(),
-        Err(e) => {
-            event!(
-                Level::WARN,
-                "Deleting task '{id}' from webhooks table failed with: {e}"
-            );
-            return (
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                axum::Json(RespDeleteTask::Failure {
-                    msg: format!("Deleting task '{id}' from webhooks table failed"),
-                }),
-            );
+        app.db_pool.close().await;
+        let (status, _) = app.get("/readyz").await;
+        assert_eq!(status, reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        let (status, _) = app.get("/healthz").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn repeated_idempotency_key_returns_original_task() {
+        let app = TestApp::without_worker(&[]).await;
+        let create = |key: &str, task: &serde_json::Value| {
+            crate::testing::send(
+                crate::testing::json_request(
+                    app.request(reqwest::Method::POST, "/tasks/new"),
+                    task,
+                )
+                .header("Idempotency-Key", key),
+            )
+        };
+        let task = webhook_in_an_hour("http://127.0.0.1:9/");
+
+        let (status, created) = create("key-1", &task).await;
+        assert_eq!(status, reqwest::StatusCode::CREATED, "{created}");
+        let (status, repeated) = create("key-1", &task).await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{repeated}");
+        assert_eq!(repeated["id"], created["id"]);
+        assert!(repeated.get("task").is_none(), "{repeated}");
+
+        // Another key creates another task.
+        let (status, other) = create("key-2", &task).await;
+        assert_eq!(status, reqwest::StatusCode::CREATED, "{other}");
+        assert_ne!(other["id"], created["id"]);
+
+        // Reusing the key for a different request is a conflict.
+        let other_task = webhook_in_an_hour("http://127.0.0.1:10/");
+        let (status, conflict) = create("key-1", &other_task).await;
+        assert_eq!(status, reqwest::StatusCode::CONFLICT, "{conflict}");
+    }
+
+    #[test]
+    fn normalizes_webhook_urls() {
+        for (url, normalized) in [
+            ("example.com", "http://example.com/"),
+            (
+                "example.com:8080/hook?x=1",
+                "http://example.com:8080/hook?x=1",
+            ),
+            ("  https://Example.COM/a/../b ", "https://example.com/b"),
+            ("https://example.com:443/hook", "https://example.com/hook"),
+            ("192.168.1.10:9000/hook", "http://192.168.1.10:9000/hook"),
+            ("http://127.0.0.1/", "http://127.0.0.1/"),
+            ("[::1]:8080/hook", "http://[::1]:8080/hook"),
+            ("https://[2001:db8::1]/", "https://[2001:db8::1]/"),
+        ] {
+            assert_eq!(super::normalize_url(url).as_deref(), Ok(normalized));
         }
-    };
+    }
 
-    if num_del_webhooks >= 1 {
-        return (
-            axum::http::StatusCode::NO_CONTENT,
-            axum::Json(RespDeleteTask::Success {}),
+    #[test]
+    fn rejects_invalid_webhook_urls() {
+        for url in [
+            "",
+            "   ",
+            "ftp://example.com/file",
+            "file:///etc/passwd",
+            "http://",
+            "https://:8080/hook",
+            "http://exa mple.com/",
+            "http://[::1/",
+        ] {
+            assert!(super::normalize_url(url).is_err(), "accepted '{url}'");
+        }
+    }
+
+    #[tokio::test]
+    async fn stores_normalized_webhook_urls() {
+        let app = TestApp::without_worker(&[]).await;
+        let (status, body) = app
+            .post("/tasks/new", &webhook_in_an_hour("Example.com:8080/hook"))
+            .await;
+        assert_eq!(status, reqwest::StatusCode::CREATED, "{body}");
+        assert_eq!(
+            body["task"]["webhook"]["url"],
+            "http://example.com:8080/hook"
+        );
+
+        let (status, body) = app
+            .post("/tasks/new", &webhook_in_an_hour("ftp://evil"))
+            .await;
+        assert_eq!(status, reqwest::StatusCode::BAD_REQUEST, "{body}");
+        assert!(
+            body["msg"].as_str().is_some_and(|m| m.contains("scheme")),
+            "{body}"
         );
     }
 
-    let num_del_hashes = match sqlx::query!(
-        "DELETE FROM hashes \
-        WHERE id = $1 AND state != $2;",
-        id,
-        "in_progress",
-    )
-    .execute(&api_ctx.db_pool)
-    .await
-    {
-        Ok(t) => t.rows_affected(),
-        Err(e) => {
-            event!(
-                Level::WARN,
-                "Deleting task '{id}' from hashes table failed with: {e}"
-            );
-            return (
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                axum::Json(RespDeleteTask::Failure {
-                    msg: format!("Deleting task '{id}' from hashes table failed"),
+    /// Returns the time `secs` seconds from now.
+    fn in_secs(secs: i64) -> chrono::DateTime<chrono::FixedOffset> {
+        (chrono::Utc::now() + chrono::TimeDelta::seconds(secs)).fixed_offset()
+    }
+
+    #[test]
+    fn picks_execution_time_within_range() {
+        let (min, max) = (in_secs(60), in_secs(61));
+        for _ in 0..100 {
+            let picked = super::pick_execution_time(
+                &min.to_rfc3339(),
+                &max.to_rfc3339(),
+                std::time::Duration::ZERO,
+            )
+            .unwrap();
+            assert!(min <= picked && picked <= max, "{picked} not within range");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_execution_time_ranges() {
+        let tolerance = std::time::Duration::ZERO;
+        let (earlier, later) = (in_secs(60).to_rfc3339(), in_secs(120).to_rfc3339());
+        let past = in_secs(-60).to_rfc3339();
+
+        assert!(matches!(
+            super::pick_execution_time(&later, &earlier, tolerance),
+            Err(super::ApiTimeError::InvalidRange(_))
+        ));
+        assert!(matches!(
+            super::pick_execution_time(&earlier, &earlier, tolerance),
+            Err(super::ApiTimeError::InvalidRange(_))
+        ));
+        assert!(matches!(
+            super::pick_execution_time(&past, &later, tolerance),
+            Err(super::ApiTimeError::InPast(_))
+        ));
+        assert!(matches!(
+            super::pick_execution_time("tomorrow", &later, tolerance),
+            Err(super::ApiTimeError::NotRfc3339(_))
+        ));
+
+        let combined = super::ReqExecutionTime {
+            exact: Some(earlier.clone()),
+            min: Some(earlier.clone()),
+            max: Some(later),
+        };
+        assert!(matches!(
+            super::resolve_execution_time(&combined, None, tolerance),
+            Err(super::ApiTimeError::InvalidRange(_))
+        ));
+        let half_open = super::ReqExecutionTime {
+            exact: None,
+            min: Some(earlier),
+            max: None,
+        };
+        assert!(matches!(
+            super::resolve_execution_time(&half_open, None, tolerance),
+            Err(super::ApiTimeError::InvalidRange(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn stores_execution_time_picked_from_range() {
+        let app = TestApp::without_worker(&[]).await;
+        let (min, max) = (in_secs(60), in_secs(120));
+        let (status, body) = app
+            .post(
+                "/tasks/new",
+                &serde_json::json!({
+                    "hash": {
+                        "secret": "secret",
+                        "execution_time_min": min.to_rfc3339(),
+                        "execution_time_max": max.to_rfc3339(),
+                    }
                 }),
-            );
+            )
+            .await;
+        assert_eq!(status, reqwest::StatusCode::CREATED, "{body}");
+
+        let picked = body["task"]["hash"]["execution_time"].as_str().unwrap();
+        let picked = chrono::DateTime::parse_from_rfc3339(picked).unwrap();
+        assert!(min <= picked && picked <= max, "{picked} not within range");
+    }
+
+    #[tokio::test]
+    async fn lists_times_with_output_precision() {
+        let app = TestApp::without_worker(&["--time-output-precision", "seconds"]).await;
+        let (status, body) = app
+            .post("/tasks/new", &webhook_in_an_hour("http://127.0.0.1:10/"))
+            .await;
+        assert_eq!(status, reqwest::StatusCode::CREATED, "{body}");
+        let id = body["id"].as_str().unwrap();
+        app.execute(&format!(
+            "UPDATE webhooks \
+            SET execution_time = '2030-01-01T00:00:00.123456789+00:00', \
+            created_at = '2029-01-01T00:00:00.123456789+00:00', \
+            started_at = '2029-06-01T00:00:00.123456789+00:00', \
+            completed_at = '2029-07-01T00:00:00.123456789+00:00' \
+            WHERE id = '{id}';"
+        ))
+        .await;
+
+        let expected = [
+            ("execution_time", "2030-01-01T00:00:00+00:00"),
+            ("created_at", "2029-01-01T00:00:00+00:00"),
+            ("started_at", "2029-06-01T00:00:00+00:00"),
+            ("completed_at", "2029-07-01T00:00:00+00:00"),
+        ];
+        let fields = "execution_time,created_at,started_at,completed_at";
+        for path in [
+            "/tasks/type/webhook".to_string(),
+            format!("/tasks/type/webhook?fields={fields}"),
+        ] {
+            let (status, body) = app.get(&path).await;
+            assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+            for (field, time) in expected {
+                assert_eq!(body["tasks"][0][field], time, "{path}: {body}");
+            }
         }
-    };
+        let (status, body) = app.get(&format!("/tasks/state/todo?fields={fields}")).await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        for (field, time) in expected {
+            assert_eq!(body["tasks"][0]["webhook"][field], time, "{body}");
+        }
+    }
 
-    if num_del_hashes >= 1 {
-        return (
-            axum::http::StatusCode::NO_CONTENT,
-            axum::Json(RespDeleteTask::Success {}),
-        );
+    /// Returns the request creating a hash task that executes in an hour.
+    fn hash_in_an_hour() -> serde_json::Value {
+        let execution_time = chrono::Utc::now() + chrono::TimeDelta::hours(1);
+        serde_json::json!({
+            "hash": {
+                "secret": "secret",
+                "execution_time": execution_time.to_rfc3339(),
+            }
+        })
     }
 
-    // At this point, it is clear that the task ID doesn't exist. Report this.
-    (
-        axum::http::StatusCode::BAD_REQUEST,
-        axum::Json(RespDeleteTask::Failure {
-            msg: format!("Task '{id}' does not exist"),
-        }),
-    )
+    /// Creates the task described by `task` and returns its ID.
+    async fn create(app: &TestApp, task: &serde_json::Value) -> String {
+        let (status, body) = app.post("/tasks/new", task).await;
+        assert_eq!(status, reqwest::StatusCode::CREATED, "{body}");
+        body["id"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn lists_tasks_across_types() {
+        let app = TestApp::without_worker(&[]).await;
+        let webhook_id = create(&app, &webhook_in_an_hour("http://Example.com/hook")).await;
+        let hash_id = create(&app, &hash_in_an_hour()).await;
+
+        let (status, body) = app.get("/tasks").await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        assert_eq!(body["total"], 2, "{body}");
+        let (status, body) = app.get("/tasks?type=hash&state=todo").await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        assert_eq!(body["total"], 1, "{body}");
+        assert_eq!(body["tasks"][0]["hash"]["id"], hash_id, "{body}");
+
+        let from = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        let to = (chrono::Utc::now() + chrono::TimeDelta::hours(2))
+            .to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        let (status, body) = app
+            .get(&format!("/tasks/scheduled?from={from}&to={to}"))
+            .await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        assert_eq!(body["tasks"].as_array().map(Vec::len), Some(2), "{body}");
+
+        // URL filters match regardless of case.
+        for path in [
+            "/tasks/state/todo?url_contains=EXAMPLE",
+            "/tasks/type/webhook?url_contains=EXAMPLE",
+        ] {
+            let (status, body) = app.get(path).await;
+            assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+            assert_eq!(body["total"], 1, "{path}: {body}");
+        }
+
+        let (status, body) = app.get("/tasks/count").await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        assert_eq!(body["todo"], 2, "{body}");
+        let (status, body) = app.get(&format!("/tasks/{webhook_id}/eta")).await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        assert_eq!(body["ahead"], 0, "{body}");
+    }
+
+    #[tokio::test]
+    async fn reschedules_holds_releases_triggers_and_cancels_tasks() {
+        let app = TestApp::without_worker(&[]).await;
+        let id = create(&app, &webhook_in_an_hour("http://127.0.0.1:9/")).await;
+        let state = |body: &serde_json::Value| body["webhook"]["state"].clone();
+
+        let execution_time = (chrono::Utc::now() + chrono::TimeDelta::hours(2)).to_rfc3339();
+        let (status, body) = crate::testing::send(crate::testing::json_request(
+            app.request(reqwest::Method::PATCH, &format!("/tasks/{id}")),
+            &serde_json::json!({ "execution_time": execution_time }),
+        ))
+        .await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        assert_eq!(state(&body), "todo", "{body}");
+
+        let (status, body) = app
+            .post(&format!("/tasks/{id}/hold"), &serde_json::json!({}))
+            .await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        let (_, body) = app.get(&format!("/tasks/{id}")).await;
+        assert_eq!(state(&body), "held", "{body}");
+
+        let (status, body) = app
+            .post(&format!("/tasks/{id}/release"), &serde_json::json!({}))
+            .await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        assert_eq!(state(&body), "todo", "{body}");
+
+        let (status, body) = app
+            .post(&format!("/tasks/{id}/trigger"), &serde_json::json!({}))
+            .await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        let triggered = body["webhook"]["execution_time"].as_str().unwrap();
+        let triggered = chrono::DateTime::parse_from_rfc3339(triggered).unwrap();
+        assert!(triggered <= chrono::Utc::now(), "{body}");
+
+        let (status, body) = app
+            .post(&format!("/tasks/{id}/cancel"), &serde_json::json!({}))
+            .await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        let (_, body) = app.get(&format!("/tasks/{id}")).await;
+        assert_eq!(state(&body), "cancelled", "{body}");
+
+        let (status, body) =
+            crate::testing::send(app.request(reqwest::Method::DELETE, "/tasks/state/cancelled"))
+                .await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        assert_eq!(body["deleted"], 1, "{body}");
+    }
+
+    #[tokio::test]
+    async fn requeues_dead_letters() {
+        let app = TestApp::without_worker(&[]).await;
+        app.execute(
+            "INSERT INTO dead_letters ( id, type, task, reason, attempts, failed_at ) \
+            VALUES ( \
+                'dead', 'hash', \
+                '{\"hash\": {\"secret\": \"secret\", \"algorithm\": \"pbkdf2\", \"params\": {}}}', \
+                'failed', '[]', '2030-01-01T00:00:00Z' \
+            );",
+        )
+        .await;
+
+        let (status, body) = app.get("/tasks/dead-letters").await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        assert_eq!(body[0]["id"], "dead", "{body}");
+        assert_eq!(body[0]["task"]["hash"]["algorithm"], "pbkdf2", "{body}");
+
+        let (status, body) = app
+            .post("/tasks/dead-letters/dead/requeue", &serde_json::json!({}))
+            .await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        assert_eq!(body["hash"]["state"], "todo", "{body}");
+        let (_, body) = app.get("/tasks/dead-letters").await;
+        assert_eq!(body, serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn admin_endpoints_report_from_database() {
+        let app = TestApp::without_worker(&["--admin-token", "admin"]).await;
+        create(&app, &webhook_in_an_hour("http://127.0.0.1:9/")).await;
+        let admin_get = |path: &str| {
+            crate::testing::send(app.request(reqwest::Method::GET, path).bearer_auth("admin"))
+        };
+
+        let (status, body) = admin_get("/admin/status").await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        let (status, body) = admin_get("/admin/drain-estimate").await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        assert_eq!(body["pending"], 1, "{body}");
+        let (status, body) = admin_get("/admin/host-stats").await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        assert_eq!(body["hosts"], serde_json::json!({}), "{body}");
+        let (status, body) = admin_get("/admin/health-score").await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        assert_eq!(body["components"]["queue_depth"]["value"], 1, "{body}");
+        let (status, body) = admin_get("/admin/schema").await;
+        assert_eq!(status, reqwest::StatusCode::OK, "{body}");
+        assert!(
+            body["tables"]["hashes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .any(|c| c["name"] == "completed_at"),
+            "{body}"
+        );
+    }
 }
@@ -0,0 +1,101 @@
+use tracing::{Level, event};
+
+/// File to which audit events are appended, if configured via `--audit-log`.
+static AUDIT_FILE: std::sync::OnceLock<std::sync::Mutex<std::fs::File>> =
+    std::sync::OnceLock::new();
+
+/// State into which deleted tasks transition in the audit log.
+pub(crate) const DELETED: &str = "deleted";
+
+/// The party causing a state transition of a task.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Actor {
+    Api,
+    Worker,
+    Maintenance,
+    Startup,
+}
+
+impl Actor {
+    fn name(self) -> &'static str {
+        match self {
+            Actor::Api => "api",
+            Actor::Worker => "worker",
+            Actor::Maintenance => "maintenance",
+            Actor::Startup => "startup",
+        }
+    }
+}
+
+/// A state transition of a task, as appended to the audit log.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+struct AuditEvent<'a> {
+    at: String,
+    actor: Actor,
+    task_type: &'a str,
+    task_id: &'a str,
+    from: &'a str,
+    to: &'a str,
+    cause: &'a str,
+}
+
+/// Opens the audit log at `path` for appending, creating it if it doesn't
+/// exist. Existing audit events are never modified.
+pub(crate) fn init(path: &std::path::Path) -> std::result::Result<(), std::io::Error> {
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    let _ = AUDIT_FILE.set(std::sync::Mutex::new(file));
+    Ok(())
+}
+
+/// Records that `actor` moved the task of type `task_type` with ID `id` from
+/// state `from` to state `to` because of `cause`. The transition is emitted as
+/// a structured event with target `audit`, and appended as one JSON object per
+/// line to the audit log, if one is configured.
+pub(crate) fn record(actor: Actor, task_type: &str, id: &str, from: &str, to: &str, cause: &str) {
+    event!(
+        target: "audit",
+        Level::INFO,
+        actor = actor.name(),
+        task_type,
+        task_id = id,
+        from,
+        to,
+        cause,
+        "Task '{id}' changed state from '{from}' to '{to}'",
+    );
+
+    let Some(file) = AUDIT_FILE.get() else {
+        return;
+    };
+    let audit_event = AuditEvent {
+        at: crate::db::timestamp(chrono::Utc::now()),
+        actor,
+        task_type,
+        task_id: id,
+        from,
+        to,
+        cause,
+    };
+    let mut line = match serde_json::to_vec(&audit_event) {
+        Ok(l) => l,
+        Err(e) => {
+            event!(Level::WARN, "Serializing audit event failed with: {e}");
+            return;
+        }
+    };
+    line.push(b'\n');
+
+    // Each event is appended with a single write, so that events of concurrent
+    // transitions never interleave.
+    let mut file = file
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if let Err(e) = std::io::Write::write_all(&mut *file, &line) {
+        event!(Level::WARN, "Appending to audit log failed with: {e}");
+    }
+}
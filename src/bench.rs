@@ -0,0 +1,274 @@
+use tracing::{Level, event};
+
+/// Time after starting the benchmark at which the first task fires, leaving
+/// time to insert and queue all tasks beforehand.
+const BENCH_LEAD: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Time to wait for outstanding deliveries after the last task's execution
+/// time passed, before tasks that didn't fire are reported as missing.
+const BENCH_GRACE: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Longest time in seconds over which the tasks of a benchmark can be spread.
+const MAX_BENCH_SPREAD_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, clap::Args)]
+pub(crate) struct BenchArgs {
+    #[arg(long, default_value_t = 1000)]
+    /// Number of webhook tasks to schedule.
+    tasks: u32,
+
+    #[arg(long, default_value_t = 10)]
+    /// Time in seconds over which the execution times of the tasks are spread
+    /// evenly, starting shortly after launch.
+    spread: u64,
+}
+
+/// Receives the webhook requests of the benchmark's tasks, whose body carries
+/// the index of the task, and reports the time each request was received.
+async fn mock_receive(
+    axum::extract::State(send_fired): axum::extract::State<
+        tokio::sync::mpsc::UnboundedSender<(u32, chrono::DateTime<chrono::Utc>)>,
+    >,
+    body: String,
+) -> axum::http::StatusCode {
+    let received_at = chrono::Utc::now();
+    match body.parse::<u32>() {
+        Ok(index) => {
+            let _ = send_fired.send((index, received_at));
+            axum::http::StatusCode::NO_CONTENT
+        }
+        Err(_) => axum::http::StatusCode::BAD_REQUEST,
+    }
+}
+
+/// Formats a duration in microseconds as milliseconds with three decimals.
+fn fmt_millis(micros: i64) -> String {
+    let sign = if micros < 0 { "-" } else { "" };
+    let micros = micros.unsigned_abs();
+    format!("{sign}{}.{:03}", micros / 1000, micros % 1000)
+}
+
+/// Returns the `percentile` of the sorted `values` via the nearest-rank method.
+fn percentile(values: &[i64], percentile: usize) -> i64 {
+    let rank = (values.len() * percentile).div_ceil(100);
+    values[rank.saturating_sub(1).min(values.len() - 1)]
+}
+
+/// Waits until no task in the scratch database is pending (`todo` or
+/// `in_progress`) anymore, or `deadline` passed.
+async fn wait_until_settled(db_pool: &crate::db::DbPool, deadline: tokio::time::Instant) {
+    while tokio::time::Instant::now() < deadline {
+        let pending =
+            match db_pool {
+                crate::db::DbPool::Sqlite(db_pool) => sqlx::query_scalar!(
+                    "SELECT COUNT(*) FROM webhooks WHERE state = 'todo' OR state = 'in_progress';"
+                )
+                .fetch_one(db_pool)
+                .await,
+                crate::db::DbPool::Postgres(db_pool) => sqlx::query_scalar(
+                    "SELECT COUNT(*) FROM webhooks WHERE state = 'todo' OR state = 'in_progress';",
+                )
+                .fetch_one(db_pool)
+                .await,
+            };
+        match pending {
+            Ok(0) => return,
+            Ok(_) => tokio::time::sleep(std::time::Duration::from_millis(100)).await,
+            Err(e) => {
+                event!(
+                    Level::WARN,
+                    "Checking for pending benchmark tasks failed: {e}"
+                );
+                return;
+            }
+        }
+    }
+}
+
+/// Prints the distribution of how late the tasks fired, i.e., how long after
+/// their `execution_times` they were received at `fired_at`.
+fn report(
+    args: &BenchArgs,
+    execution_times: &[chrono::DateTime<chrono::Utc>],
+    fired_at: &std::collections::HashMap<u32, chrono::DateTime<chrono::Utc>>,
+) {
+    let mut lateness = fired_at
+        .iter()
+        .filter_map(|(index, received_at)| {
+            let execution_time = execution_times.get(usize::try_from(*index).ok()?)?;
+            (*received_at - *execution_time).num_microseconds()
+        })
+        .collect::<Vec<_>>();
+    lateness.sort_unstable();
+
+    println!(
+        "Scheduled {} webhook tasks over {} s, {} fired, {} missing",
+        args.tasks,
+        args.spread,
+        lateness.len(),
+        execution_times.len() - lateness.len(),
+    );
+    if let (Some(min), Some(max)) = (lateness.first(), lateness.last()) {
+        println!(
+            "Lateness of fire times in ms: min {}, p50 {}, p95 {}, p99 {}, max {}",
+            fmt_millis(*min),
+            fmt_millis(percentile(&lateness, 50)),
+            fmt_millis(percentile(&lateness, 95)),
+            fmt_millis(percentile(&lateness, 99)),
+            fmt_millis(*max),
+        );
+    }
+}
+
+/// Returns the webhook task with number `index` of the benchmark, which sends
+/// its index to the mock target at `mock_url` at `execution_time`.
+fn bench_task(
+    mock_url: &str,
+    index: usize,
+    execution_time: chrono::DateTime<chrono::Utc>,
+) -> crate::api::Task {
+    crate::api::Task::Webhook(crate::api::ApiWebhook {
+        id: uuid::Uuid::now_v7().to_string(),
+        state: "todo".to_string(),
+        execution_time: crate::db::timestamp(execution_time),
+        method: "POST".to_string(),
+        url: mock_url.to_string(),
+        body: index.to_string(),
+        idempotency_key: None,
+        cron: None,
+        calendar: None,
+        priority: crate::api::DEFAULT_PRIORITY,
+        shard_key: None,
+        max_retries: 5,
+        initial_backoff_ms: 100,
+        timeout_ms: None,
+        max_latency_ms: None,
+        signing_secret: None,
+        headers: None,
+        tags: None,
+        last_status_code: None,
+        last_response_body: None,
+        last_latency_ms: None,
+        created_at: Some(crate::db::timestamp(chrono::Utc::now())),
+        started_at: None,
+        completed_at: None,
+    })
+}
+
+/// Runs the scheduling benchmark: schedules `args.tasks` webhook tasks at known
+/// execution times, spread evenly over `args.spread` seconds, against an
+/// in-process mock target, and reports the distribution of how late each task
+/// fired compared to its execution time. The tasks are handled by the worker
+/// returned by `new_worker_ctx`, i.e., they take the real path through the
+/// `DelayQueue` and the worker, subject to the configured concurrency limits
+/// and claim batch size. Tasks are stored in a scratch database that is
+/// removed afterwards.
+pub(crate) async fn run(
+    args: &BenchArgs,
+    new_worker_ctx: impl FnOnce(
+        crate::db::DbPool,
+        tokio::sync::mpsc::Sender<(tokio::time::Duration, crate::api::Task)>,
+    ) -> crate::worker::WorkerCtx,
+) -> std::result::Result<(), crate::AppError> {
+    if args.tasks == 0 {
+        return Err(crate::AppError::Config(
+            "benchmark must schedule at least 1 task".to_string(),
+        ));
+    }
+    if args.spread > MAX_BENCH_SPREAD_SECS {
+        return Err(crate::AppError::Config(format!(
+            "benchmark tasks can be spread over at most {MAX_BENCH_SPREAD_SECS} seconds"
+        )));
+    }
+    let spread = std::time::Duration::from_secs(args.spread);
+
+    // Start the mock target of all webhook tasks on an ephemeral local port.
+    let (send_fired, mut recv_fired) = tokio::sync::mpsc::unbounded_channel();
+    let mock_sock = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let mock_url = format!("http://{}/", mock_sock.local_addr()?);
+    let mock_router = axum::Router::new()
+        .route("/", axum::routing::post(mock_receive))
+        .with_state(send_fired);
+    let mock_hdl = tokio::task::spawn(async move { axum::serve(mock_sock, mock_router).await });
+
+    // Open a scratch database, so that the benchmark never touches real tasks.
+    let db_file = std::env::temp_dir().join(format!("tasker-bench-{}.db", uuid::Uuid::now_v7()));
+    let db_pool = crate::db::init_open_db(&[format!("sqlite://{}", db_file.display())]).await?;
+
+    // Start the worker just as for serving the HTTP API.
+    let (send_shutdown, _) = tokio::sync::broadcast::channel::<()>(1);
+    let (send_task, recv_task) =
+        tokio::sync::mpsc::channel::<(tokio::time::Duration, crate::api::Task)>(256);
+    let (_send_cmd, recv_cmd) = tokio::sync::mpsc::channel::<crate::worker::WorkerCmd>(1);
+    let worker_hdl = tokio::task::spawn(new_worker_ctx(db_pool.clone(), send_task.clone()).run(
+        send_shutdown.subscribe(),
+        recv_task,
+        recv_cmd,
+    ));
+
+    // Prepare all tasks with their execution times relative to a common start.
+    let start = chrono::Utc::now() + chrono::TimeDelta::from_std(BENCH_LEAD).unwrap_or_default();
+    let execution_times = (0..args.tasks)
+        .map(|i| start + chrono::TimeDelta::from_std(spread * i / args.tasks).unwrap_or_default())
+        .collect::<Vec<_>>();
+    let tasks = execution_times
+        .iter()
+        .enumerate()
+        .map(|(i, execution_time)| bench_task(&mock_url, i, *execution_time))
+        .collect::<Vec<_>>();
+
+    // Insert all tasks at once and queue them for the worker.
+    let mut tx = db_pool.begin().await.map_err(crate::db::DbError::from)?;
+    for task in &tasks {
+        crate::api::insert_task(&mut tx, task)
+            .await
+            .map_err(crate::db::DbError::from)?;
+    }
+    tx.commit().await.map_err(crate::db::DbError::from)?;
+
+    for (task, execution_time) in tasks.into_iter().zip(&execution_times) {
+        let dur_from_now = (*execution_time - chrono::Utc::now())
+            .to_std()
+            .unwrap_or_default();
+        if send_task.send((dur_from_now, task)).await.is_err() {
+            event!(Level::WARN, "Worker stopped while queueing benchmark tasks");
+            break;
+        }
+    }
+    event!(
+        Level::INFO,
+        "Scheduled {} benchmark tasks, waiting for them to fire...",
+        args.tasks,
+    );
+
+    // Collect the time each task fired, until all did or the grace period passed.
+    let deadline = tokio::time::Instant::now() + BENCH_LEAD + spread + BENCH_GRACE;
+    let mut fired_at = std::collections::HashMap::with_capacity(execution_times.len());
+    while fired_at.len() < execution_times.len() {
+        match tokio::time::timeout_at(deadline, recv_fired.recv()).await {
+            Ok(Some((index, received_at))) => {
+                fired_at.entry(index).or_insert(received_at);
+            }
+            Ok(None) | Err(_) => break,
+        }
+    }
+
+    // Let the handlers of all fired tasks finalize their tasks' states before
+    // shutting down the worker and the mock target, and removing the scratch
+    // database.
+    wait_until_settled(&db_pool, deadline).await;
+    drop(send_shutdown);
+    drop(send_task);
+    let _ = worker_hdl.await;
+    mock_hdl.abort();
+    db_pool.close().await;
+    for suffix in ["", "-journal", "-wal", "-shm"] {
+        let mut file = db_file.clone().into_os_string();
+        file.push(suffix);
+        let _ = std::fs::remove_file(file);
+    }
+
+    report(args, &execution_times, &fired_at);
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use chrono::Datelike;
+
+/// Name of the business calendar, as referenced by field `calendar` of
+/// recurring webhook tasks.
+pub(crate) const BUSINESS: &str = "business";
+
+/// Number of consecutive days beyond which a fire of a recurring task isn't
+/// rolled forward anymore, if no business day was found among them.
+const MAX_ROLL_FORWARD_DAYS: u32 = 366;
+
+/// Calendar of business days, i.e., all days except Saturdays, Sundays, and
+/// the configured holidays. Days are evaluated in UTC, like cron expressions.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BusinessCalendar {
+    holidays: std::collections::BTreeSet<chrono::NaiveDate>,
+}
+
+impl BusinessCalendar {
+    pub(crate) fn new(holidays: &[chrono::NaiveDate]) -> Self {
+        BusinessCalendar {
+            holidays: holidays.iter().copied().collect(),
+        }
+    }
+
+    /// Returns whether `date` is neither on a weekend nor a holiday.
+    fn is_business_day(&self, date: chrono::NaiveDate) -> bool {
+        !matches!(date.weekday(), chrono::Weekday::Sat | chrono::Weekday::Sun)
+            && !self.holidays.contains(&date)
+    }
+
+    /// Rolls `time` forward by whole days until it falls on a business day,
+    /// keeping its time of day. Returns `time` unchanged if it already falls
+    /// on a business day.
+    pub(crate) fn roll_forward(
+        &self,
+        time: chrono::DateTime<chrono::Utc>,
+    ) -> std::result::Result<chrono::DateTime<chrono::Utc>, String> {
+        let mut rolled = time;
+        for _ in 0..=MAX_ROLL_FORWARD_DAYS {
+            if self.is_business_day(rolled.date_naive()) {
+                return Ok(rolled);
+            }
+            rolled += chrono::TimeDelta::days(1);
+        }
+        Err(format!(
+            "calendar '{BUSINESS}' has no business day within {MAX_ROLL_FORWARD_DAYS} days of {}",
+            time.date_naive()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BusinessCalendar;
+
+    /// Parses `date` in format `YYYY-MM-DD`.
+    fn date(date: &str) -> chrono::NaiveDate {
+        date.parse().unwrap()
+    }
+
+    /// Parses the UTC time `time` in RFC 3339 format.
+    fn utc(time: &str) -> chrono::DateTime<chrono::Utc> {
+        time.parse().unwrap()
+    }
+
+    #[test]
+    fn keeps_business_days() {
+        let calendar = BusinessCalendar::new(&[date("2026-12-25")]);
+        for time in ["2026-06-03T09:00:00Z", "2026-12-24T23:59:59.999Z"] {
+            assert_eq!(calendar.roll_forward(utc(time)), Ok(utc(time)));
+        }
+    }
+
+    #[test]
+    fn rolls_weekends_forward_to_monday() {
+        let calendar = BusinessCalendar::default();
+        assert_eq!(
+            calendar.roll_forward(utc("2026-06-06T09:00:00Z")),
+            Ok(utc("2026-06-08T09:00:00Z"))
+        );
+        assert_eq!(
+            calendar.roll_forward(utc("2026-06-07T23:59:59Z")),
+            Ok(utc("2026-06-08T23:59:59Z"))
+        );
+    }
+
+    #[test]
+    fn rolls_holidays_forward_past_adjacent_weekends() {
+        // A holiday on a Friday is followed by the weekend.
+        let calendar = BusinessCalendar::new(&[date("2026-12-25")]);
+        assert_eq!(
+            calendar.roll_forward(utc("2026-12-25T00:00:00Z")),
+            Ok(utc("2026-12-28T00:00:00Z"))
+        );
+
+        // A holiday on a Monday extends the weekend.
+        let calendar = BusinessCalendar::new(&[date("2026-06-08")]);
+        assert_eq!(
+            calendar.roll_forward(utc("2026-06-06T09:00:00Z")),
+            Ok(utc("2026-06-09T09:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn gives_up_without_business_day_in_reach() {
+        let start = date("2026-01-01");
+        let holidays = start.iter_days().take(400).collect::<Vec<_>>();
+        let calendar = BusinessCalendar::new(&holidays);
+        assert!(calendar.roll_forward(utc("2026-01-01T09:00:00Z")).is_err());
+    }
+}
@@ -1,10 +1,24 @@
 use std::str::FromStr;
+use tracing::{Level, event};
+
+/// Primary result code of `SQLite` reporting a corrupt database file.
+const SQLITE_CORRUPT: i32 = 11;
+
+/// Primary result code of `SQLite` reporting a file that is not a database.
+const SQLITE_NOTADB: i32 = 26;
 
 #[derive(Debug)]
 pub(crate) enum DbError {
     Sqlx(sqlx::Error),
     DateParse(chrono::ParseError),
     ChannelSend(Box<tokio::sync::mpsc::error::SendError<(std::time::Duration, crate::api::Task)>>),
+    Corrupt {
+        db_url: String,
+        details: Option<String>,
+    },
+    UnsupportedBackend(String),
+    UnsupportedFailover,
+    NoPrimary(usize),
 }
 
 impl std::fmt::Display for DbError {
@@ -13,6 +27,33 @@ impl std::fmt::Display for DbError {
             DbError::Sqlx(e) => write!(f, "{e}"),
             DbError::DateParse(e) => write!(f, "{e}"),
             DbError::ChannelSend(e) => write!(f, "{e}"),
+            DbError::Corrupt { db_url, details } => {
+                write!(
+                    f,
+                    "database at '{db_url}' is corrupt or not a SQLite database"
+                )?;
+                if let Some(details) = details {
+                    write!(f, " (integrity check reported: {details})")?;
+                }
+                write!(
+                    f,
+                    ", restore it from a backup or start with a different DATABASE_URL"
+                )
+            }
+            DbError::UnsupportedBackend(scheme) => write!(
+                f,
+                "database backend '{scheme}' is not supported, DATABASE_URL must start with 'sqlite:' \
+                or 'postgres:'"
+            ),
+            DbError::UnsupportedFailover => write!(
+                f,
+                "failing over between several databases is only supported for PostgreSQL, \
+                DATABASE_URL must list either a single database or only 'postgres:' ones"
+            ),
+            DbError::NoPrimary(count) => write!(
+                f,
+                "none of the {count} databases listed in DATABASE_URL is reachable and writable"
+            ),
         }
     }
 }
@@ -39,30 +80,235 @@ impl From<tokio::sync::mpsc::error::SendError<(std::time::Duration, crate::api::
     }
 }
 
+/// Connection pool to the database storing all tasks, which is either a
+/// `SQLite` or a `PostgreSQL` database depending on the scheme of
+/// `DATABASE_URL`. Queries on `SQLite` are checked at compile time, while the
+/// ones on `PostgreSQL` are written in its dialect next to them and only
+/// checked when run.
 #[derive(Debug, Clone)]
+pub(crate) enum DbPool {
+    Sqlite(sqlx::sqlite::SqlitePool),
+    Postgres(sqlx::postgres::PgPool),
+}
+
+impl DbPool {
+    /// Starts a transaction on a connection from the pool.
+    pub(crate) async fn begin(&self) -> std::result::Result<DbTx, sqlx::Error> {
+        Ok(match self {
+            DbPool::Sqlite(db_pool) => DbTx::Sqlite(db_pool.begin().await?),
+            DbPool::Postgres(db_pool) => DbTx::Postgres(db_pool.begin().await?),
+        })
+    }
+
+    /// Returns the operator for matching text against a `LIKE` pattern while
+    /// ignoring case. `SQLite`'s `LIKE` already does so for ASCII characters,
+    /// whereas `PostgreSQL` provides `ILIKE` for it.
+    pub(crate) fn like_operator(&self) -> &'static str {
+        match self {
+            DbPool::Sqlite(_) => "LIKE",
+            DbPool::Postgres(_) => "ILIKE",
+        }
+    }
+
+    /// Closes all connections of the pool, waiting for the ones in use to be
+    /// returned first.
+    pub(crate) async fn close(&self) {
+        match self {
+            DbPool::Sqlite(db_pool) => db_pool.close().await,
+            DbPool::Postgres(db_pool) => db_pool.close().await,
+        }
+    }
+}
+
+/// Transaction started via `DbPool::begin`, on the pool's backend. Dropping it
+/// without committing rolls it back.
+#[derive(Debug)]
+pub(crate) enum DbTx {
+    Sqlite(sqlx::Transaction<'static, sqlx::Sqlite>),
+    Postgres(sqlx::Transaction<'static, sqlx::Postgres>),
+}
+
+impl DbTx {
+    /// Sets a savepoint, to which `rollback_to_savepoint` rolls the transaction
+    /// back after a failed statement. On `PostgreSQL`, a failed statement aborts
+    /// the whole transaction otherwise, whereas `SQLite` only rolls back the
+    /// failed statement itself, so that this is a no-op there.
+    pub(crate) async fn savepoint(&mut self) -> std::result::Result<(), sqlx::Error> {
+        if let DbTx::Postgres(tx) = self {
+            sqlx::query("SAVEPOINT item;").execute(&mut **tx).await?;
+        }
+        Ok(())
+    }
+
+    /// Rolls the transaction back to the latest savepoint set via `savepoint`.
+    pub(crate) async fn rollback_to_savepoint(&mut self) -> std::result::Result<(), sqlx::Error> {
+        if let DbTx::Postgres(tx) = self {
+            sqlx::query("ROLLBACK TO SAVEPOINT item;")
+                .execute(&mut **tx)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Commits the transaction.
+    pub(crate) async fn commit(self) -> std::result::Result<(), sqlx::Error> {
+        match self {
+            DbTx::Sqlite(tx) => tx.commit().await,
+            DbTx::Postgres(tx) => tx.commit().await,
+        }
+    }
+}
+
+/// Columns of table `webhooks` making up a `crate::api::ApiWebhook`, for
+/// queries on `PostgreSQL`.
+pub(crate) const PG_WEBHOOK_COLUMNS: &str = "id, state, execution_time, method, url, body, \
+    idempotency_key, cron, calendar, priority, shard_key, max_retries, initial_backoff_ms, timeout_ms, \
+    max_latency_ms, signing_secret, last_status_code, last_response_body, last_latency_ms, \
+    created_at, started_at, completed_at, headers, tags";
+
+/// Columns of table `hashes` making up a `crate::api::ApiHash`, for queries on
+/// `PostgreSQL`.
+pub(crate) const PG_HASH_COLUMNS: &str = "id, state, execution_time, secret, algorithm, params, \
+    result, created_at, started_at, completed_at";
+
+#[derive(Debug, Clone, sqlx::FromRow)]
 struct DbWebhook {
     id: String,
     state: String,
     execution_time: String,
+    method: String,
     url: String,
     body: String,
+    idempotency_key: Option<String>,
+    cron: Option<String>,
+    calendar: Option<String>,
+    priority: i64,
+    shard_key: Option<String>,
+    max_retries: i64,
+    initial_backoff_ms: i64,
+    timeout_ms: Option<i64>,
+    max_latency_ms: Option<i64>,
+    signing_secret: Option<String>,
+    headers: Option<sqlx::types::Json<crate::api::WebhookHeaders>>,
+    tags: Option<sqlx::types::Json<Vec<String>>>,
+    last_status_code: Option<i64>,
+    last_response_body: Option<String>,
+    last_latency_ms: Option<i64>,
+    created_at: Option<String>,
+    started_at: Option<String>,
+    completed_at: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, sqlx::FromRow)]
 struct DbHash {
     id: String,
     state: String,
     execution_time: String,
     secret: String,
+    algorithm: String,
+    params: sqlx::types::Json<serde_json::Value>,
+    result: Option<String>,
+    created_at: Option<String>,
+    started_at: Option<String>,
+    completed_at: Option<String>,
 }
 
-/// Initializes a `SQLite` database at the supplied `db_url` location, if one
-/// doesn't already exist. Opens up a connection pool to the database and
-/// creates the tables required for this task scheduler, if they don't exist
-/// already. Returns the connection pool for usage in the scheduler.
-pub(crate) async fn init_open_db(
-    db_url: &str,
-) -> std::result::Result<sqlx::sqlite::SqlitePool, DbError> {
+/// Adds column `column` with the supplied `definition` to table `table`, if
+/// the table doesn't have such a column already. This allows databases created
+/// by earlier versions of this scheduler to be used with the current schema.
+async fn add_column_if_missing(
+    db_pool: &sqlx::sqlite::SqlitePool,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> std::result::Result<(), DbError> {
+    let num_cols: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM pragma_table_info($1) WHERE name = $2;")
+            .bind(table)
+            .bind(column)
+            .fetch_one(db_pool)
+            .await?;
+
+    if num_cols == 0 {
+        sqlx::query(&format!(
+            "ALTER TABLE {table} ADD COLUMN {column} {definition};"
+        ))
+        .execute(db_pool)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Renames column `from` of table `table` to `to`, if the table still has a
+/// column `from` but none named `to`. This allows databases created by earlier
+/// versions of this scheduler to be used with the current column names.
+async fn rename_column_if_present(
+    db_pool: &sqlx::sqlite::SqlitePool,
+    table: &str,
+    from: &str,
+    to: &str,
+) -> std::result::Result<(), DbError> {
+    let (num_from, num_to): (i64, i64) = sqlx::query_as(
+        "SELECT COUNT(*) FILTER (WHERE name = $2), COUNT(*) FILTER (WHERE name = $3) \
+        FROM pragma_table_info($1);",
+    )
+    .bind(table)
+    .bind(from)
+    .bind(to)
+    .fetch_one(db_pool)
+    .await?;
+
+    if num_from == 1 && num_to == 0 {
+        sqlx::query(&format!(
+            "ALTER TABLE {table} RENAME COLUMN {from} TO {to};"
+        ))
+        .execute(db_pool)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Formats `time` for storage in a timestamp column. All timestamps are stored
+/// in UTC with a fixed precision, so that they can be compared as strings.
+pub(crate) fn timestamp(time: chrono::DateTime<chrono::Utc>) -> String {
+    time.to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
+}
+
+/// Returns whether `db_url` is the URL of a `PostgreSQL` database, i.e., starts
+/// with `postgres:` or `postgresql:`.
+pub(crate) fn is_postgres_url(db_url: &str) -> bool {
+    db_url.split_once("://").is_some_and(|(scheme, _)| {
+        scheme.eq_ignore_ascii_case("postgres") || scheme.eq_ignore_ascii_case("postgresql")
+    })
+}
+
+/// Opens up a connection pool to the database at the supplied `db_urls`
+/// location, which is a `PostgreSQL` database for URLs starting with
+/// `postgres:` or `postgresql:` and a `SQLite` database otherwise. Several
+/// `PostgreSQL` URLs are tried in order, failing over to the next one whenever
+/// the database currently connected to becomes unreachable or read-only. A
+/// `SQLite` database is initialized if one doesn't already exist. Creates the
+/// tables required for this task scheduler, if they don't exist already.
+/// Returns the connection pool for usage in the scheduler.
+pub(crate) async fn init_open_db(db_urls: &[String]) -> std::result::Result<DbPool, DbError> {
+    if !db_urls.is_empty() && db_urls.iter().all(|db_url| is_postgres_url(db_url)) {
+        return init_open_postgres(db_urls).await;
+    }
+    let [db_url] = db_urls else {
+        return Err(DbError::UnsupportedFailover);
+    };
+
+    // Reject URLs of backends other than SQLite and PostgreSQL with a clear error
+    // instead of failing to parse them as SQLite URLs. Only the scheme is
+    // reported, as the rest of the URL may contain credentials.
+    if let Some((scheme, _)) = db_url.split_once("://")
+        && !scheme.eq_ignore_ascii_case("sqlite")
+    {
+        return Err(DbError::UnsupportedBackend(scheme.to_string()));
+    }
+
     // Create database if it doesn't exist already.
     let db_opts = sqlx::sqlite::SqliteConnectOptions::from_str(db_url)?.create_if_missing(true);
 
@@ -71,6 +317,73 @@ pub(crate) async fn init_open_db(
         .connect_with(db_opts)
         .await?;
 
+    // Create the tables required for this task scheduler. A database that turns
+    // out to be corrupt is reported as such, including the details found by an
+    // integrity check, if it can be run at all.
+    if let Err(e) = create_schema(&db_pool).await {
+        if let DbError::Sqlx(sqlx_err) = &e
+            && is_corruption(sqlx_err)
+        {
+            let err = DbError::Corrupt {
+                db_url: db_url.clone(),
+                details: integrity_check(&db_pool).await,
+            };
+            event!(Level::ERROR, "{err}");
+            return Err(err);
+        }
+        return Err(e);
+    }
+
+    Ok(DbPool::Sqlite(db_pool))
+}
+
+/// Opens up a connection pool to the first of the existing `PostgreSQL`
+/// databases at the supplied `db_urls` locations that is reachable and
+/// writable, and creates the tables required for this task scheduler, if they
+/// don't exist already. If more than one database is supplied, the pool is
+/// pointed to the next writable one whenever the current one fails.
+async fn init_open_postgres(db_urls: &[String]) -> std::result::Result<DbPool, DbError> {
+    let candidates = db_urls
+        .iter()
+        .map(|db_url| sqlx::postgres::PgConnectOptions::from_str(db_url))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    if let [db_opts] = candidates.as_slice() {
+        let db_pool = sqlx::postgres::PgPoolOptions::new()
+            .connect_with(db_opts.clone())
+            .await?;
+        create_postgres_schema(&db_pool).await?;
+        return Ok(DbPool::Postgres(db_pool));
+    }
+    let failover = std::sync::Arc::new(crate::failover::Failover::new(candidates));
+
+    let primary = failover.find_primary().await?;
+    let db_pool = sqlx::postgres::PgPoolOptions::new()
+        .before_acquire({
+            let failover = std::sync::Arc::clone(&failover);
+            move |_, meta| {
+                // Discard connections that may still point to the database failed
+                // over from.
+                let keep = !failover.predates_failover(meta.age);
+                Box::pin(async move { Ok(keep) })
+            }
+        })
+        .connect_with(primary)
+        .await?;
+
+    create_postgres_schema(&db_pool).await?;
+
+    // Keep checking the database connected to, until the pool is closed.
+    tokio::spawn(crate::failover::run(db_pool.clone(), failover));
+
+    Ok(DbPool::Postgres(db_pool))
+}
+
+/// Creates the table keeping track of webhook tasks and its indices, if they
+/// don't exist already, and adds columns missing from tables created by
+/// earlier versions.
+async fn create_webhooks_table(
+    db_pool: &sqlx::sqlite::SqlitePool,
+) -> std::result::Result<(), DbError> {
     // Create table keeping track of webhook tasks, if it doesn't exist already.
     sqlx::query(
         "CREATE TABLE IF NOT EXISTS webhooks ( \
@@ -81,21 +394,137 @@ pub(crate) async fn init_open_db(
             body TEXT NOT NULL \
         ) STRICT;",
     )
-    .execute(&db_pool)
+    .execute(db_pool)
+    .await?;
+
+    // Add column `sequence` to `webhooks` table, if it doesn't exist already.
+    add_column_if_missing(
+        db_pool,
+        "webhooks",
+        "sequence",
+        "INTEGER NOT NULL DEFAULT 0",
+    )
+    .await?;
+
+    // Rename column `finished_at` of `webhooks` table to `completed_at`, for
+    // tables created before it was renamed.
+    rename_column_if_present(db_pool, "webhooks", "finished_at", "completed_at").await?;
+
+    // Add columns `last_response_body`, `last_status_code`, and `completed_at` to
+    // `webhooks` table, if they don't exist already.
+    add_column_if_missing(db_pool, "webhooks", "last_response_body", "TEXT").await?;
+    add_column_if_missing(db_pool, "webhooks", "last_status_code", "INTEGER").await?;
+    add_column_if_missing(db_pool, "webhooks", "completed_at", "TEXT").await?;
+
+    // Add column `idempotency_key` to `webhooks` table, if it doesn't exist
+    // already. Webhook tasks without a key are delivered without one.
+    add_column_if_missing(db_pool, "webhooks", "idempotency_key", "TEXT").await?;
+
+    // Add column `method` to `webhooks` table, if it doesn't exist already.
+    // Webhook tasks created before it existed are delivered via POST.
+    add_column_if_missing(
+        db_pool,
+        "webhooks",
+        "method",
+        "TEXT NOT NULL DEFAULT 'POST'",
+    )
+    .await?;
+
+    // Add column `headers` to `webhooks` table, if it doesn't exist already. It
+    // holds the custom headers of a webhook task as a JSON object, if any.
+    add_column_if_missing(db_pool, "webhooks", "headers", "TEXT").await?;
+
+    // Add columns `max_retries` and `initial_backoff_ms` to `webhooks` table, if
+    // they don't exist already. Webhook tasks created before they existed keep
+    // being retried 5 times, starting with a backoff of 100 milliseconds.
+    add_column_if_missing(
+        db_pool,
+        "webhooks",
+        "max_retries",
+        "INTEGER NOT NULL DEFAULT 5",
+    )
+    .await?;
+    add_column_if_missing(
+        db_pool,
+        "webhooks",
+        "initial_backoff_ms",
+        "INTEGER NOT NULL DEFAULT 100",
+    )
+    .await?;
+
+    // Add column `cron` to `webhooks` table, if it doesn't exist already. It holds
+    // the cron expression of recurring webhook tasks.
+    add_column_if_missing(db_pool, "webhooks", "cron", "TEXT").await?;
+
+    // Add columns `tags` and `timeout_ms` to `webhooks` table, if they don't
+    // exist already. Column `tags` holds the tags of a webhook task as a JSON
+    // array, if any, and `timeout_ms` the timeout of its requests inherited from
+    // the policy of its tags, if any.
+    add_column_if_missing(db_pool, "webhooks", "tags", "TEXT").await?;
+    add_column_if_missing(db_pool, "webhooks", "timeout_ms", "INTEGER").await?;
+
+    // Add column `created_at` to `webhooks` table, if it doesn't exist already.
+    // Tasks created before this column existed have none and are considered the
+    // oldest ones.
+    add_column_if_missing(db_pool, "webhooks", "created_at", "TEXT").await?;
+
+    // Add column `started_at` to `webhooks` table, if it doesn't exist already.
+    // It holds the time at which the worker last claimed the task.
+    add_column_if_missing(db_pool, "webhooks", "started_at", "TEXT").await?;
+
+    // Add columns `max_latency_ms` and `last_latency_ms` to `webhooks` table, if
+    // they don't exist already. Column `max_latency_ms` holds the time after
+    // which a response counts as failed, if any, and `last_latency_ms` the time
+    // the last successful request took.
+    add_column_if_missing(db_pool, "webhooks", "max_latency_ms", "INTEGER").await?;
+    add_column_if_missing(db_pool, "webhooks", "last_latency_ms", "INTEGER").await?;
+
+    // Add column `signing_secret` to `webhooks` table, if it doesn't exist
+    // already. It holds the secret with which the body of every request of the
+    // task is signed, if any, which is needed again for every retry.
+    add_column_if_missing(db_pool, "webhooks", "signing_secret", "TEXT").await?;
+
+    // Add column `calendar` to `webhooks` table, if it doesn't exist already. It
+    // holds the calendar restricting the days on which a recurring webhook task
+    // fires, if any.
+    add_column_if_missing(db_pool, "webhooks", "calendar", "TEXT").await?;
+
+    // Add column `priority` to `webhooks` table, if it doesn't exist already.
+    // Webhook tasks created before it existed get the default priority of 5.
+    add_column_if_missing(
+        db_pool,
+        "webhooks",
+        "priority",
+        "INTEGER NOT NULL DEFAULT 5",
+    )
     .await?;
 
+    // Add column `shard_key` to `webhooks` table, if it doesn't exist already.
+    // It holds the key of webhook tasks that are handled one after another, if
+    // any.
+    add_column_if_missing(db_pool, "webhooks", "shard_key", "TEXT").await?;
+
     // Create index on `id` field of `webhooks` table.
     sqlx::query("CREATE UNIQUE INDEX IF NOT EXISTS webhooks_id ON webhooks ( id );")
-        .execute(&db_pool)
+        .execute(db_pool)
         .await?;
 
     // Create composite index on fields `state` and `execution_time` in `webhooks`.
     sqlx::query(
         "CREATE INDEX IF NOT EXISTS webhooks_state_time ON webhooks ( state, execution_time );",
     )
-    .execute(&db_pool)
+    .execute(db_pool)
     .await?;
 
+    Ok(())
+}
+
+/// Creates the table keeping track of hash tasks and its indices, if they
+/// don't exist already, and adds columns missing from tables created by
+/// earlier versions.
+async fn create_hashes_table(
+    db_pool: &sqlx::sqlite::SqlitePool,
+) -> std::result::Result<(), DbError> {
     // Create table keeping track of hash tasks, if it doesn't exist already.
     sqlx::query(
         "CREATE TABLE IF NOT EXISTS hashes ( \
@@ -105,121 +534,1131 @@ pub(crate) async fn init_open_db(
             secret TEXT NOT NULL \
         ) STRICT;",
     )
-    .execute(&db_pool)
+    .execute(db_pool)
+    .await?;
+
+    // Add column `params` to `hashes` table, if it doesn't exist already.
+    add_column_if_missing(db_pool, "hashes", "params", "TEXT NOT NULL DEFAULT '{}'").await?;
+
+    // Add column `algorithm` to `hashes` table, if it doesn't exist already.
+    // Hash tasks created before its introduction were all PBKDF2 hashes.
+    add_column_if_missing(
+        db_pool,
+        "hashes",
+        "algorithm",
+        "TEXT NOT NULL DEFAULT 'pbkdf2'",
+    )
     .await?;
 
+    // Rename column `finished_at` of `hashes` table to `completed_at`, for tables
+    // created before it was renamed.
+    rename_column_if_present(db_pool, "hashes", "finished_at", "completed_at").await?;
+
+    // Add columns `result` and `completed_at` to `hashes` table, if they don't
+    // exist already.
+    add_column_if_missing(db_pool, "hashes", "result", "TEXT").await?;
+    add_column_if_missing(db_pool, "hashes", "completed_at", "TEXT").await?;
+
+    // Add column `created_at` to `hashes` table, if it doesn't exist already.
+    add_column_if_missing(db_pool, "hashes", "created_at", "TEXT").await?;
+
+    // Add column `started_at` to `hashes` table, if it doesn't exist already.
+    add_column_if_missing(db_pool, "hashes", "started_at", "TEXT").await?;
+
     // Create index on `id` field of `hashes` table.
     sqlx::query("CREATE UNIQUE INDEX IF NOT EXISTS hashes_id ON hashes ( id );")
-        .execute(&db_pool)
+        .execute(db_pool)
         .await?;
 
     // Create composite index on fields `state` and `execution_time` in `hashes`.
     sqlx::query(
         "CREATE INDEX IF NOT EXISTS hashes_state_time ON hashes ( state, execution_time );",
     )
-    .execute(&db_pool)
+    .execute(db_pool)
     .await?;
 
+    Ok(())
+}
+
+/// Creates the tables required for this task scheduler in the database behind
+/// `db_pool`, if they don't exist already, migrates tables created by earlier
+/// versions, and resets tasks interrupted while being handled.
+async fn create_schema(db_pool: &sqlx::sqlite::SqlitePool) -> std::result::Result<(), DbError> {
+    create_webhooks_table(db_pool).await?;
+    create_hashes_table(db_pool).await?;
+
+    // Create table keeping track of permanently failed tasks, if it doesn't exist
+    // already. Column `task` holds the failed task in its API representation.
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS dead_letters ( \
+            id TEXT PRIMARY KEY NOT NULL, \
+            type TEXT NOT NULL, \
+            task TEXT NOT NULL, \
+            reason TEXT NOT NULL, \
+            attempts TEXT NOT NULL, \
+            failed_at TEXT NOT NULL \
+        ) STRICT;",
+    )
+    .execute(db_pool)
+    .await?;
+
+    // Create table keeping track of named templates of tasks, if it doesn't exist
+    // already. Column `fields` holds the fields of the template's task of type
+    // `type` as a JSON object, in the format of the HTTP API.
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS templates ( \
+            name TEXT PRIMARY KEY NOT NULL, \
+            type TEXT NOT NULL, \
+            fields TEXT NOT NULL, \
+            created_at TEXT NOT NULL \
+        ) STRICT;",
+    )
+    .execute(db_pool)
+    .await?;
+
+    // Create table keeping track of the `Idempotency-Key` headers of requests
+    // that created tasks, if it doesn't exist already. Keys are scoped per task
+    // type, and column `task_id` holds the ID of the task created for the key.
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS idempotency_keys ( \
+            type TEXT NOT NULL, \
+            key TEXT NOT NULL, \
+            task_id TEXT NOT NULL, \
+            created_at TEXT NOT NULL, \
+            PRIMARY KEY ( type, key ) \
+        ) STRICT;",
+    )
+    .execute(db_pool)
+    .await?;
+
+    // Add column `request_hash` to `idempotency_keys` table, if it doesn't exist
+    // already. It holds the fingerprint of the request that created the task, to
+    // detect a key being reused for a different request. Keys recorded before it
+    // existed match any request.
+    add_column_if_missing(db_pool, "idempotency_keys", "request_hash", "TEXT").await?;
+
     // Reset any `webhook` tasks in state `in_progress` to `todo`.
-    sqlx::query!(
+    let webhooks = sqlx::query_scalar!(
         "UPDATE webhooks \
         SET state = 'todo' \
-        WHERE state = 'in_progress';",
+        WHERE state = 'in_progress' \
+        RETURNING id;",
     )
-    .execute(&db_pool)
+    .fetch_all(db_pool)
     .await?;
+    for id in &webhooks {
+        audit_reset("webhook", id);
+    }
 
     // Reset any `hash` tasks in state `in_progress` to `todo`.
-    sqlx::query!(
+    let hashes = sqlx::query_scalar!(
         "UPDATE hashes \
         SET state = 'todo' \
-        WHERE state = 'in_progress';",
+        WHERE state = 'in_progress' \
+        RETURNING id;",
     )
-    .execute(&db_pool)
+    .fetch_all(db_pool)
     .await?;
+    for id in &hashes {
+        audit_reset("hash", id);
+    }
+
+    Ok(())
+}
+
+/// Renames column `from` of table `table` to `to` in the `PostgreSQL` database
+/// behind `db_pool`, if the table still has a column `from` but none named `to`.
+async fn rename_postgres_column_if_present(
+    db_pool: &sqlx::postgres::PgPool,
+    table: &str,
+    from: &str,
+    to: &str,
+) -> std::result::Result<(), DbError> {
+    let (num_from, num_to): (i64, i64) = sqlx::query_as(
+        "SELECT COUNT(*) FILTER (WHERE column_name = $2), \
+        COUNT(*) FILTER (WHERE column_name = $3) \
+        FROM information_schema.columns \
+        WHERE table_schema = current_schema() AND table_name = $1;",
+    )
+    .bind(table)
+    .bind(from)
+    .bind(to)
+    .fetch_one(db_pool)
+    .await?;
+
+    if num_from == 1 && num_to == 0 {
+        sqlx::query(&format!(
+            "ALTER TABLE {table} RENAME COLUMN {from} TO {to};"
+        ))
+        .execute(db_pool)
+        .await?;
+    }
 
-    Ok(db_pool)
+    Ok(())
+}
+
+/// Creates the tables required for this task scheduler in the `PostgreSQL`
+/// database behind `db_pool`, if they don't exist already, and resets tasks
+/// interrupted while being handled. The tables mirror the ones created by
+/// `create_schema` on `SQLite`, except that columns holding JSON are of type
+/// `JSONB` and integer columns of type `BIGINT`.
+#[allow(clippy::too_many_lines)]
+async fn create_postgres_schema(
+    db_pool: &sqlx::postgres::PgPool,
+) -> std::result::Result<(), DbError> {
+    // Create table keeping track of webhook tasks, if it doesn't exist already.
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS webhooks ( \
+            id TEXT PRIMARY KEY NOT NULL, \
+            state TEXT NOT NULL, \
+            execution_time TEXT NOT NULL, \
+            method TEXT NOT NULL DEFAULT 'POST', \
+            url TEXT NOT NULL, \
+            body TEXT NOT NULL, \
+            idempotency_key TEXT, \
+            cron TEXT, \
+            calendar TEXT, \
+            priority BIGINT NOT NULL DEFAULT 5, \
+            shard_key TEXT, \
+            max_retries BIGINT NOT NULL DEFAULT 5, \
+            initial_backoff_ms BIGINT NOT NULL DEFAULT 100, \
+            timeout_ms BIGINT, \
+            max_latency_ms BIGINT, \
+            signing_secret TEXT, \
+            headers JSONB, \
+            tags JSONB, \
+            sequence BIGINT NOT NULL DEFAULT 0, \
+            last_status_code BIGINT, \
+            last_response_body TEXT, \
+            last_latency_ms BIGINT, \
+            created_at TEXT, \
+            started_at TEXT, \
+            completed_at TEXT \
+        );",
+    )
+    .execute(db_pool)
+    .await?;
+
+    // Rename column `finished_at` of `webhooks` table to `completed_at`, for
+    // tables created before it was renamed.
+    rename_postgres_column_if_present(db_pool, "webhooks", "finished_at", "completed_at").await?;
+
+    // Add column `signing_secret` to `webhooks` table, if it doesn't exist
+    // already, for tables created before it was introduced.
+    sqlx::query("ALTER TABLE webhooks ADD COLUMN IF NOT EXISTS signing_secret TEXT;")
+        .execute(db_pool)
+        .await?;
+
+    // Add column `calendar` to `webhooks` table, if it doesn't exist already.
+    sqlx::query("ALTER TABLE webhooks ADD COLUMN IF NOT EXISTS calendar TEXT;")
+        .execute(db_pool)
+        .await?;
+
+    // Add column `priority` to `webhooks` table, if it doesn't exist already.
+    sqlx::query(
+        "ALTER TABLE webhooks ADD COLUMN IF NOT EXISTS priority BIGINT NOT NULL DEFAULT 5;",
+    )
+    .execute(db_pool)
+    .await?;
+
+    // Add column `shard_key` to `webhooks` table, if it doesn't exist already.
+    sqlx::query("ALTER TABLE webhooks ADD COLUMN IF NOT EXISTS shard_key TEXT;")
+        .execute(db_pool)
+        .await?;
+
+    // Add column `started_at` to `webhooks` table, if it doesn't exist already.
+    sqlx::query("ALTER TABLE webhooks ADD COLUMN IF NOT EXISTS started_at TEXT;")
+        .execute(db_pool)
+        .await?;
+
+    // Create composite index on fields `state` and `execution_time` in `webhooks`.
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS webhooks_state_time ON webhooks ( state, execution_time );",
+    )
+    .execute(db_pool)
+    .await?;
+
+    // Create table keeping track of hash tasks, if it doesn't exist already.
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS hashes ( \
+            id TEXT PRIMARY KEY NOT NULL, \
+            state TEXT NOT NULL, \
+            execution_time TEXT NOT NULL, \
+            secret TEXT NOT NULL, \
+            algorithm TEXT NOT NULL DEFAULT 'pbkdf2', \
+            params JSONB NOT NULL DEFAULT '{}', \
+            result TEXT, \
+            created_at TEXT, \
+            started_at TEXT, \
+            completed_at TEXT \
+        );",
+    )
+    .execute(db_pool)
+    .await?;
+
+    // Rename column `finished_at` of `hashes` table to `completed_at`, for tables
+    // created before it was renamed.
+    rename_postgres_column_if_present(db_pool, "hashes", "finished_at", "completed_at").await?;
+
+    // Add column `started_at` to `hashes` table, if it doesn't exist already.
+    sqlx::query("ALTER TABLE hashes ADD COLUMN IF NOT EXISTS started_at TEXT;")
+        .execute(db_pool)
+        .await?;
+
+    // Create composite index on fields `state` and `execution_time` in `hashes`.
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS hashes_state_time ON hashes ( state, execution_time );",
+    )
+    .execute(db_pool)
+    .await?;
+
+    // Create table keeping track of permanently failed tasks, if it doesn't exist
+    // already.
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS dead_letters ( \
+            id TEXT PRIMARY KEY NOT NULL, \
+            type TEXT NOT NULL, \
+            task JSONB NOT NULL, \
+            reason TEXT NOT NULL, \
+            attempts JSONB NOT NULL, \
+            failed_at TEXT NOT NULL \
+        );",
+    )
+    .execute(db_pool)
+    .await?;
+
+    // Create table keeping track of named templates of tasks, if it doesn't exist
+    // already.
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS templates ( \
+            name TEXT PRIMARY KEY NOT NULL, \
+            type TEXT NOT NULL, \
+            fields TEXT NOT NULL, \
+            created_at TEXT NOT NULL \
+        );",
+    )
+    .execute(db_pool)
+    .await?;
+
+    // Create table keeping track of the `Idempotency-Key` headers of requests
+    // that created tasks, if it doesn't exist already.
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS idempotency_keys ( \
+            type TEXT NOT NULL, \
+            key TEXT NOT NULL, \
+            task_id TEXT NOT NULL, \
+            created_at TEXT NOT NULL, \
+            request_hash TEXT, \
+            PRIMARY KEY ( type, key ) \
+        );",
+    )
+    .execute(db_pool)
+    .await?;
+
+    // Add column `request_hash` to `idempotency_keys` table, if it doesn't exist
+    // already.
+    sqlx::query("ALTER TABLE idempotency_keys ADD COLUMN IF NOT EXISTS request_hash TEXT;")
+        .execute(db_pool)
+        .await?;
+
+    // Reset any `webhook` tasks in state `in_progress` to `todo`.
+    let webhooks: Vec<String> = sqlx::query_scalar(
+        "UPDATE webhooks \
+        SET state = 'todo' \
+        WHERE state = 'in_progress' \
+        RETURNING id;",
+    )
+    .fetch_all(db_pool)
+    .await?;
+    for id in &webhooks {
+        audit_reset("webhook", id);
+    }
+
+    // Reset any `hash` tasks in state `in_progress` to `todo`.
+    let hashes: Vec<String> = sqlx::query_scalar(
+        "UPDATE hashes \
+        SET state = 'todo' \
+        WHERE state = 'in_progress' \
+        RETURNING id;",
+    )
+    .fetch_all(db_pool)
+    .await?;
+    for id in &hashes {
+        audit_reset("hash", id);
+    }
+
+    Ok(())
+}
+
+/// Records in the audit log that the task of type `task_type` with ID `id` was
+/// reset to `todo` upon start, as its handling was interrupted.
+fn audit_reset(task_type: &str, id: &str) {
+    crate::audit::record(
+        crate::audit::Actor::Startup,
+        task_type,
+        id,
+        "in_progress",
+        "todo",
+        "handling interrupted by shutdown",
+    );
+}
+
+/// Number of attempts after which a database operation failing with a
+/// transient error is given up on.
+const TRANSIENT_RETRY_ATTEMPTS: u32 = 8;
+
+/// Delay before the first retry of a database operation that failed with a
+/// transient error, which doubles with every further retry.
+const TRANSIENT_RETRY_BACKOFF: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Upper bound on the delay between retries of a database operation that
+/// failed with a transient error.
+const TRANSIENT_RETRY_MAX_BACKOFF: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Errors of database operations that may succeed when retried.
+pub(crate) trait Transient: std::fmt::Display {
+    /// Returns whether the failed operation may succeed when retried, e.g.,
+    /// because the connection to the database dropped or the database is failing
+    /// over to a standby.
+    fn is_transient(&self) -> bool;
+}
+
+impl Transient for sqlx::Error {
+    fn is_transient(&self) -> bool {
+        match self {
+            sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => true,
+            // Connection exceptions (class 08), server shutdowns (57P01 to 57P03),
+            // writes on a read-only standby (25006), and serialization failures
+            // and deadlocks (40001, 40P01) on `PostgreSQL`.
+            sqlx::Error::Database(e) => e.code().is_some_and(|c| {
+                c.starts_with("08")
+                    || matches!(
+                        &*c,
+                        "57P01" | "57P02" | "57P03" | "25006" | "40001" | "40P01"
+                    )
+            }),
+            _ => false,
+        }
+    }
+}
+
+impl Transient for DbError {
+    fn is_transient(&self) -> bool {
+        matches!(self, DbError::Sqlx(e) if e.is_transient())
+    }
+}
+
+/// Runs the database operation `op` and retries it with exponential backoff
+/// for as long as it fails with a transient error, up to
+/// `TRANSIENT_RETRY_ATTEMPTS` times. Returns the result of the last attempt.
+pub(crate) async fn retry_transient<T, E, F>(mut op: impl FnMut() -> F) -> std::result::Result<T, E>
+where
+    E: Transient,
+    F: std::future::Future<Output = std::result::Result<T, E>>,
+{
+    let mut backoff = TRANSIENT_RETRY_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Err(e) if e.is_transient() && attempt < TRANSIENT_RETRY_ATTEMPTS => {
+                event!(
+                    Level::WARN,
+                    "Database operation failed transiently (attempt {attempt} / \
+                    {TRANSIENT_RETRY_ATTEMPTS}), retrying in {backoff:?}: {e}"
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(TRANSIENT_RETRY_MAX_BACKOFF);
+                attempt += 1;
+            }
+            res => return res,
+        }
+    }
+}
+
+/// Returns whether `err` reports that the database file is corrupt or not a
+/// `SQLite` database at all (`SQLITE_CORRUPT` or `SQLITE_NOTADB`, including
+/// their extended result codes).
+fn is_corruption(err: &sqlx::Error) -> bool {
+    let sqlx::Error::Database(e) = err else {
+        return false;
+    };
+    e.code()
+        .and_then(|c| c.parse::<i32>().ok())
+        .is_some_and(|c| matches!(c & 0xff, SQLITE_CORRUPT | SQLITE_NOTADB))
+}
+
+/// Runs `SQLite`'s integrity check on a database found to be corrupt and
+/// returns the problems it reports, if it could be run.
+async fn integrity_check(db_pool: &sqlx::sqlite::SqlitePool) -> Option<String> {
+    let problems: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check(10);")
+        .fetch_all(db_pool)
+        .await
+        .ok()?;
+    Some(problems.join("; "))
+}
+
+/// Delay after which the first task whose execution time passed while the
+/// application wasn't running is yielded upon reinsertion.
+const OVERDUE_DELAY: tokio::time::Duration = tokio::time::Duration::from_millis(100);
+
+/// Determines the delays after which reinserted tasks are yielded by the
+/// `DelayQueue`. Tasks whose execution time passed are yielded after
+/// `OVERDUE_DELAY`, spaced `interval` apart if a reinsertion rate is
+/// configured, so that a backlog of overdue tasks doesn't fire in one burst.
+struct ReinsertPacer {
+    interval: Option<tokio::time::Duration>,
+    next_overdue: tokio::time::Duration,
+    overdue: u64,
+}
+
+impl ReinsertPacer {
+    fn new(rate_per_sec: Option<u32>) -> Self {
+        ReinsertPacer {
+            interval: rate_per_sec
+                .filter(|r| *r > 0)
+                .map(|r| tokio::time::Duration::from_secs(1) / r),
+            next_overdue: OVERDUE_DELAY,
+            overdue: 0,
+        }
+    }
+
+    /// Returns the delay from now after which a task due at `execution_time`
+    /// is to be yielded.
+    fn delay(
+        &mut self,
+        execution_time: chrono::DateTime<chrono::FixedOffset>,
+    ) -> tokio::time::Duration {
+        if let Ok(dur_from_now) = (execution_time - chrono::Utc::now().fixed_offset()).to_std() {
+            return dur_from_now;
+        }
+
+        self.overdue += 1;
+        let delay = self.next_overdue;
+        if let Some(interval) = self.interval {
+            self.next_overdue += interval;
+        }
+        delay
+    }
 }
 
 /// When the application restarts, the in-memory `DelayQueue` (yielding tasks
 /// for handling once their deadline expired) is empty. This would prevent any
 /// task from being handled whose execution time expired while the application
 /// wasn't running. To remedy this, we populate the `DelayQueue` with all
-/// `webhook` and `hash` tasks in state `todo` each time we start up again. Any
+/// `webhook` and `hash` tasks in state `todo` each time we start up again, as
+/// far as their task type is enabled in `task_types`. Any
 /// deadline which now lies in the past is set to 100 milliseconds as of time of
-/// consideration.
+/// consideration. If `rate_per_sec` is set, such overdue tasks are instead
+/// released at most at that rate, in order of their execution times. Tasks of
+/// all types are reinserted in one order of their execution times. Returns the
+/// number of reinserted tasks.
+#[allow(clippy::too_many_lines)]
 pub(crate) async fn reinsert_tasks(
-    db_pool: &sqlx::sqlite::SqlitePool,
+    db_pool: &DbPool,
     send_task: tokio::sync::mpsc::Sender<(tokio::time::Duration, crate::api::Task)>,
-) -> std::result::Result<(), DbError> {
-    // Retrieve all 'todo' webhook tasks.
-    let webhooks = sqlx::query_as!(
-        DbWebhook,
-        "SELECT id, state, execution_time, url, body \
-        FROM webhooks \
-        WHERE state = 'todo' \
-        ORDER BY execution_time ASC;",
-    )
-    .fetch_all(db_pool)
-    .await?;
+    rate_per_sec: Option<u32>,
+    task_types: crate::api::TaskTypes,
+) -> std::result::Result<usize, DbError> {
+    // Retrieve all 'todo' webhook tasks, unless webhook tasks are disabled.
+    let webhooks =
+        if task_types.webhook {
+            match db_pool {
+                DbPool::Sqlite(db_pool) => sqlx::query_as!(
+                    DbWebhook,
+                    "SELECT id, state, execution_time, method, url, body, idempotency_key, cron, \
+                    calendar, max_retries, initial_backoff_ms, timeout_ms, max_latency_ms, \
+                    signing_secret, priority, shard_key, last_status_code, last_response_body, \
+                    last_latency_ms, created_at, started_at, completed_at, \
+                    headers AS \"headers: sqlx::types::Json<crate::api::WebhookHeaders>\", \
+                    tags AS \"tags: sqlx::types::Json<Vec<String>>\" \
+                    FROM webhooks \
+                    WHERE state = 'todo';",
+                )
+                .fetch_all(db_pool)
+                .await?,
+                DbPool::Postgres(db_pool) => {
+                    sqlx::query_as::<_, DbWebhook>(&format!(
+                        "SELECT {PG_WEBHOOK_COLUMNS} \
+                        FROM webhooks \
+                        WHERE state = 'todo';"
+                    ))
+                    .fetch_all(db_pool)
+                    .await?
+                }
+            }
+        } else {
+            Vec::new()
+        };
+
+    // Retrieve all 'todo' hash tasks, unless hash tasks are disabled.
+    let hashes = if task_types.hash {
+        match db_pool {
+            DbPool::Sqlite(db_pool) => {
+                sqlx::query_as!(
+                    DbHash,
+                    "SELECT id, state, execution_time, secret, algorithm, \
+                    params AS \"params: sqlx::types::Json<serde_json::Value>\", result, \
+                    created_at, started_at, completed_at \
+                    FROM hashes \
+                    WHERE state = 'todo';",
+                )
+                .fetch_all(db_pool)
+                .await?
+            }
+            DbPool::Postgres(db_pool) => {
+                sqlx::query_as::<_, DbHash>(&format!(
+                    "SELECT {PG_HASH_COLUMNS} \
+                    FROM hashes \
+                    WHERE state = 'todo';"
+                ))
+                .fetch_all(db_pool)
+                .await?
+            }
+        }
+    } else {
+        Vec::new()
+    };
 
+    // Parse the specified execution times from RFC 3339 format to chrono DateTime.
+    let mut tasks = Vec::with_capacity(webhooks.len() + hashes.len());
     for wh in webhooks {
-        // Parse specified execution time from RFC 3339 format to chrono DateTime.
         let execution_time = chrono::DateTime::parse_from_rfc3339(&wh.execution_time)?;
+        tasks.push((
+            execution_time,
+            crate::api::Task::Webhook(crate::api::ApiWebhook {
+                id: wh.id,
+                state: wh.state,
+                execution_time: wh.execution_time,
+                method: wh.method,
+                url: wh.url,
+                body: wh.body,
+                idempotency_key: wh.idempotency_key,
+                cron: wh.cron,
+                calendar: wh.calendar,
+                priority: wh.priority,
+                shard_key: wh.shard_key,
+                max_retries: wh.max_retries,
+                initial_backoff_ms: wh.initial_backoff_ms,
+                timeout_ms: wh.timeout_ms,
+                max_latency_ms: wh.max_latency_ms,
+                signing_secret: wh.signing_secret,
+                headers: wh.headers,
+                tags: wh.tags,
+                last_status_code: wh.last_status_code,
+                last_response_body: wh.last_response_body,
+                last_latency_ms: wh.last_latency_ms,
+                created_at: wh.created_at,
+                started_at: wh.started_at,
+                completed_at: wh.completed_at,
+            }),
+        ));
+    }
+    for h in hashes {
+        let execution_time = chrono::DateTime::parse_from_rfc3339(&h.execution_time)?;
+        tasks.push((
+            execution_time,
+            crate::api::Task::Hash(crate::api::ApiHash {
+                id: h.id,
+                state: h.state,
+                execution_time: h.execution_time,
+                secret: h.secret,
+                algorithm: h.algorithm,
+                params: h.params,
+                result: h.result,
+                created_at: h.created_at,
+                started_at: h.started_at,
+                completed_at: h.completed_at,
+            }),
+        ));
+    }
+
+    // Reinsert the tasks of all types in a single global order of their execution
+    // times, regardless of the offsets they were specified in, and break ties by
+    // ID. This way, a restart doesn't change the relative order in which tasks
+    // sharing deadlines across types are handled.
+    tasks.sort_by(|(a_time, a), (b_time, b)| a_time.cmp(b_time).then_with(|| a.id().cmp(b.id())));
 
-        // Obtain number of milliseconds between now and the specified execution time,
-        // if the latter lies in the future. If it doesn't, set a default execution time
-        // for the task in 100 milliseconds.
-        let dur_from_now_millis =
-            u64::try_from((execution_time - chrono::Utc::now().fixed_offset()).num_milliseconds())
-                .unwrap_or(100u64);
+    let reinserted = tasks.len();
+    let mut pacer = ReinsertPacer::new(rate_per_sec);
+    for (execution_time, task) in tasks {
+        // Obtain the duration between now and the specified execution time, if the
+        // latter lies in the future. If it doesn't, the pacer determines when the
+        // overdue task is yielded.
+        let dur_from_now = pacer.delay(execution_time);
 
         // Send task with duration for which to wait until it will be yielded by the
         // DelayQueue via channel to worker task managing the DelayQueue for insertion.
-        send_task
-            .send((
-                tokio::time::Duration::from_millis(dur_from_now_millis),
-                crate::api::Task::Webhook(crate::api::ApiWebhook {
-                    id: wh.id,
-                    state: wh.state,
-                    execution_time: wh.execution_time,
-                    url: wh.url,
-                    body: wh.body,
-                }),
-            ))
+        send_task.send((dur_from_now, task)).await?;
+    }
+
+    if let Some(rate_per_sec) = rate_per_sec
+        && pacer.overdue > 0
+    {
+        event!(
+            Level::INFO,
+            "Releasing {} overdue tasks at {rate_per_sec} per second...",
+            pacer.overdue,
+        );
+    }
+
+    Ok(reinserted)
+}
+
+/// Checks that the database is reachable by running a trivial query on it.
+pub(crate) async fn ping(db_pool: &DbPool) -> std::result::Result<(), DbError> {
+    match db_pool {
+        DbPool::Sqlite(db_pool) => {
+            sqlx::query("SELECT 1;").execute(db_pool).await?;
+        }
+        DbPool::Postgres(db_pool) => {
+            sqlx::query("SELECT 1;").execute(db_pool).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Purges the result payloads (the last response body of `webhook` tasks and
+/// the computed hash of `hash` tasks) of all tasks that finished before
+/// `cutoff`, while keeping the tasks themselves. Returns the number of tasks
+/// whose result payload was purged.
+pub(crate) async fn purge_results(
+    db_pool: &DbPool,
+    cutoff: &str,
+) -> std::result::Result<u64, DbError> {
+    match db_pool {
+        DbPool::Sqlite(db_pool) => {
+            let webhooks = sqlx::query!(
+                "UPDATE webhooks \
+                SET last_response_body = NULL \
+                WHERE last_response_body IS NOT NULL AND completed_at < $1;",
+                cutoff,
+            )
+            .execute(db_pool)
+            .await?;
+
+            let hashes = sqlx::query!(
+                "UPDATE hashes \
+                SET result = NULL \
+                WHERE result IS NOT NULL AND completed_at < $1;",
+                cutoff,
+            )
+            .execute(db_pool)
+            .await?;
+
+            Ok(webhooks.rows_affected() + hashes.rows_affected())
+        }
+        DbPool::Postgres(db_pool) => {
+            let webhooks = sqlx::query(
+                "UPDATE webhooks \
+                SET last_response_body = NULL \
+                WHERE last_response_body IS NOT NULL AND completed_at < $1;",
+            )
+            .bind(cutoff)
+            .execute(db_pool)
+            .await?;
+
+            let hashes = sqlx::query(
+                "UPDATE hashes \
+                SET result = NULL \
+                WHERE result IS NOT NULL AND completed_at < $1;",
+            )
+            .bind(cutoff)
+            .execute(db_pool)
+            .await?;
+
+            Ok(webhooks.rows_affected() + hashes.rows_affected())
+        }
+    }
+}
+
+/// Returns the number of tasks in each state as `(type, state, count)`, for
+/// every task type and state that at least one task has.
+pub(crate) async fn count_task_states(
+    db_pool: &DbPool,
+) -> std::result::Result<Vec<(String, String, i64)>, DbError> {
+    match db_pool {
+        DbPool::Sqlite(db_pool) => {
+            let counts = sqlx::query!(
+                "SELECT 'webhook' AS \"type!: String\", state, COUNT(*) AS \"count!: i64\" \
+                FROM webhooks GROUP BY state \
+                UNION ALL \
+                SELECT 'hash', state, COUNT(*) FROM hashes GROUP BY state;",
+            )
+            .fetch_all(db_pool)
             .await?;
+
+            Ok(counts
+                .into_iter()
+                .map(|c| (c.r#type, c.state, c.count))
+                .collect())
+        }
+        DbPool::Postgres(db_pool) => Ok(sqlx::query_as(
+            "SELECT 'webhook', state, COUNT(*) FROM webhooks GROUP BY state \
+            UNION ALL \
+            SELECT 'hash', state, COUNT(*) FROM hashes GROUP BY state;",
+        )
+        .fetch_all(db_pool)
+        .await?),
     }
+}
 
-    // Conduct the same steps for any `hash` task that is marked 'todo'.
-    let hashes = sqlx::query_as!(
-        DbHash,
-        "SELECT id, state, execution_time, secret \
-        FROM hashes \
-        WHERE state = 'todo' \
-        ORDER BY execution_time ASC;",
-    )
-    .fetch_all(db_pool)
-    .await?;
+/// IDs and states of tasks deleted by `prune_finished_tasks`.
+type PrunedTasks = Vec<(String, String)>;
 
-    for h in hashes {
-        let execution_time = chrono::DateTime::parse_from_rfc3339(&h.execution_time)?;
+/// Deletes all tasks of both types in state `done` or `failed` whose execution
+/// time lies before `cutoff`. Returns the number of deleted tasks.
+pub(crate) async fn prune_finished_tasks(
+    db_pool: &DbPool,
+    cutoff: &str,
+) -> std::result::Result<u64, DbError> {
+    let (webhooks, hashes): (PrunedTasks, PrunedTasks) = match db_pool {
+        DbPool::Sqlite(db_pool) => {
+            let mut tx = db_pool.begin().await?;
+
+            let webhooks = sqlx::query!(
+                "DELETE FROM webhooks \
+                WHERE state IN ( 'done', 'failed' ) AND execution_time < $1 \
+                RETURNING id, state;",
+                cutoff,
+            )
+            .fetch_all(&mut *tx)
+            .await?;
+
+            let hashes = sqlx::query!(
+                "DELETE FROM hashes \
+                WHERE state IN ( 'done', 'failed' ) AND execution_time < $1 \
+                RETURNING id, state;",
+                cutoff,
+            )
+            .fetch_all(&mut *tx)
+            .await?;
+
+            tx.commit().await?;
+
+            (
+                webhooks.into_iter().map(|wh| (wh.id, wh.state)).collect(),
+                hashes.into_iter().map(|h| (h.id, h.state)).collect(),
+            )
+        }
+        DbPool::Postgres(db_pool) => {
+            let mut tx = db_pool.begin().await?;
+
+            let webhooks = sqlx::query_as(
+                "DELETE FROM webhooks \
+                WHERE state IN ( 'done', 'failed' ) AND execution_time < $1 \
+                RETURNING id, state;",
+            )
+            .bind(cutoff)
+            .fetch_all(&mut *tx)
+            .await?;
+
+            let hashes = sqlx::query_as(
+                "DELETE FROM hashes \
+                WHERE state IN ( 'done', 'failed' ) AND execution_time < $1 \
+                RETURNING id, state;",
+            )
+            .bind(cutoff)
+            .fetch_all(&mut *tx)
+            .await?;
+
+            tx.commit().await?;
+
+            (webhooks, hashes)
+        }
+    };
+
+    let pruned = webhooks
+        .iter()
+        .map(|(id, state)| ("webhook", id, state))
+        .chain(hashes.iter().map(|(id, state)| ("hash", id, state)));
+    for (task_type, id, state) in pruned {
+        crate::audit::record(
+            crate::audit::Actor::Maintenance,
+            task_type,
+            id,
+            state,
+            crate::audit::DELETED,
+            "retention expired",
+        );
+    }
+
+    Ok(u64::try_from(webhooks.len() + hashes.len()).unwrap_or(u64::MAX))
+}
+
+/// Deletes all idempotency keys recorded before `cutoff`. Returns the number of
+/// deleted keys.
+pub(crate) async fn purge_idempotency_keys(
+    db_pool: &DbPool,
+    cutoff: &str,
+) -> std::result::Result<u64, DbError> {
+    let keys = match db_pool {
+        DbPool::Sqlite(db_pool) => sqlx::query!(
+            "DELETE FROM idempotency_keys WHERE created_at < $1;",
+            cutoff,
+        )
+        .execute(db_pool)
+        .await?
+        .rows_affected(),
+        DbPool::Postgres(db_pool) => {
+            sqlx::query("DELETE FROM idempotency_keys WHERE created_at < $1;")
+                .bind(cutoff)
+                .execute(db_pool)
+                .await?
+                .rows_affected()
+        }
+    };
+
+    Ok(keys)
+}
+
+/// A failed attempt at handling a task, as recorded in the attempt history of a
+/// dead letter.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct FailedAttempt {
+    pub(crate) attempt: usize,
+    pub(crate) at: String,
+    pub(crate) error: String,
+}
+
+impl FailedAttempt {
+    /// Records that attempt number `attempt` failed just now with `error`.
+    pub(crate) fn now(attempt: usize, error: &impl std::fmt::Display) -> Self {
+        FailedAttempt {
+            attempt,
+            at: timestamp(chrono::Utc::now()),
+            error: error.to_string(),
+        }
+    }
+}
+
+/// Moves the permanently failed webhook task `id` from the `webhooks` table to
+/// the `dead_letters` table, together with the `reason` for its failure and
+/// the history of its failed `attempts`. The `reason` is also kept as the
+/// task's `last_response_body`, in place of a response.
+pub(crate) async fn dead_letter_webhook(
+    db_pool: &DbPool,
+    id: &str,
+    reason: &str,
+    attempts: &[FailedAttempt],
+) -> std::result::Result<(), DbError> {
+    let attempts = sqlx::types::Json(attempts);
+    let failed_at = timestamp(chrono::Utc::now());
+
+    match db_pool {
+        DbPool::Sqlite(db_pool) => {
+            let mut tx = db_pool.begin().await?;
+            sqlx::query!(
+                "INSERT INTO dead_letters ( id, type, task, reason, attempts, failed_at ) \
+                SELECT id, 'webhook', json_object('webhook', json_object( \
+                    'id', id, 'state', 'failed', 'execution_time', execution_time, \
+                    'method', method, 'url', url, 'body', body, \
+                    'idempotency_key', idempotency_key, 'cron', cron, 'calendar', calendar, \
+                    'priority', priority, 'shard_key', shard_key, \
+                    'max_retries', max_retries, 'initial_backoff_ms', initial_backoff_ms, \
+                    'timeout_ms', timeout_ms, 'max_latency_ms', max_latency_ms, \
+                    'signing_secret', signing_secret, \
+                    'headers', json(headers), 'tags', json(tags), \
+                    'sequence', sequence, 'last_response_body', $2 \
+                )), $2, $3, $4 \
+                FROM webhooks \
+                WHERE id = $1;",
+                id,
+                reason,
+                attempts,
+                failed_at,
+            )
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query!("DELETE FROM webhooks WHERE id = $1;", id)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+        }
+        DbPool::Postgres(db_pool) => {
+            let mut tx = db_pool.begin().await?;
+            sqlx::query(
+                "INSERT INTO dead_letters ( id, type, task, reason, attempts, failed_at ) \
+                SELECT id, 'webhook', jsonb_build_object('webhook', jsonb_build_object( \
+                    'id', id, 'state', 'failed', 'execution_time', execution_time, \
+                    'method', method, 'url', url, 'body', body, \
+                    'idempotency_key', idempotency_key, 'cron', cron, 'calendar', calendar, \
+                    'priority', priority, 'shard_key', shard_key, \
+                    'max_retries', max_retries, 'initial_backoff_ms', initial_backoff_ms, \
+                    'timeout_ms', timeout_ms, 'max_latency_ms', max_latency_ms, \
+                    'signing_secret', signing_secret, \
+                    'headers', headers, 'tags', tags, \
+                    'sequence', sequence, 'last_response_body', $2 \
+                )), $2, $3, $4 \
+                FROM webhooks \
+                WHERE id = $1;",
+            )
+            .bind(id)
+            .bind(reason)
+            .bind(attempts)
+            .bind(failed_at)
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query("DELETE FROM webhooks WHERE id = $1;")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+        }
+    }
+
+    Ok(())
+}
 
-        let dur_from_now_millis =
-            u64::try_from((execution_time - chrono::Utc::now().fixed_offset()).num_milliseconds())
-                .unwrap_or(100u64);
-
-        send_task
-            .send((
-                tokio::time::Duration::from_millis(dur_from_now_millis),
-                crate::api::Task::Hash(crate::api::ApiHash {
-                    id: h.id,
-                    state: h.state,
-                    execution_time: h.execution_time,
-                    secret: h.secret,
-                }),
-            ))
+/// Moves the permanently failed hash task `id` from the `hashes` table to the
+/// `dead_letters` table, analogous to `dead_letter_webhook`.
+pub(crate) async fn dead_letter_hash(
+    db_pool: &DbPool,
+    id: &str,
+    reason: &str,
+    attempts: &[FailedAttempt],
+) -> std::result::Result<(), DbError> {
+    let attempts = sqlx::types::Json(attempts);
+    let failed_at = timestamp(chrono::Utc::now());
+
+    match db_pool {
+        DbPool::Sqlite(db_pool) => {
+            let mut tx = db_pool.begin().await?;
+            sqlx::query!(
+                "INSERT INTO dead_letters ( id, type, task, reason, attempts, failed_at ) \
+                SELECT id, 'hash', json_object('hash', json_object( \
+                    'id', id, 'state', 'failed', 'execution_time', execution_time, \
+                    'secret', secret, 'algorithm', algorithm, 'params', json(params) \
+                )), $2, $3, $4 \
+                FROM hashes \
+                WHERE id = $1;",
+                id,
+                reason,
+                attempts,
+                failed_at,
+            )
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query!("DELETE FROM hashes WHERE id = $1;", id)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+        }
+        DbPool::Postgres(db_pool) => {
+            let mut tx = db_pool.begin().await?;
+            sqlx::query(
+                "INSERT INTO dead_letters ( id, type, task, reason, attempts, failed_at ) \
+                SELECT id, 'hash', jsonb_build_object('hash', jsonb_build_object( \
+                    'id', id, 'state', 'failed', 'execution_time', execution_time, \
+                    'secret', secret, 'algorithm', algorithm, 'params', params \
+                )), $2, $3, $4 \
+                FROM hashes \
+                WHERE id = $1;",
+            )
+            .bind(id)
+            .bind(reason)
+            .bind(attempts)
+            .bind(failed_at)
+            .execute(&mut *tx)
             .await?;
+            sqlx::query("DELETE FROM hashes WHERE id = $1;")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+        }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    async fn reinserts_tasks_in_global_execution_time_order() {
+        let app = crate::testing::TestApp::without_worker(&[]).await;
+        let base = chrono::Utc::now() + chrono::TimeDelta::hours(1);
+        let at = |hours: i64, offset: chrono::FixedOffset| {
+            (base + chrono::TimeDelta::hours(hours))
+                .with_timezone(&offset)
+                .to_rfc3339()
+        };
+        let utc = chrono::FixedOffset::east_opt(0).unwrap();
+        let plus_five = chrono::FixedOffset::east_opt(5 * 60 * 60).unwrap();
+
+        let webhook = |execution_time: String| {
+            serde_json::json!({
+                "webhook": {
+                    "url": "http://127.0.0.1:9/",
+                    "body": "{}",
+                    "execution_time": execution_time,
+                }
+            })
+        };
+        let hash = |execution_time: String| {
+            serde_json::json!({
+                "hash": { "secret": "secret", "execution_time": execution_time }
+            })
+        };
+
+        let mut ids = Vec::new();
+        for task in [
+            webhook(at(2, utc)),
+            hash(at(0, utc)),
+            webhook(at(1, utc)),
+            hash(at(1, plus_five)),
+        ] {
+            let (status, body) = app.post("/tasks/new", &task).await;
+            assert_eq!(status, reqwest::StatusCode::CREATED, "{body}");
+            ids.push(body["id"].as_str().unwrap().to_string());
+        }
+
+        let (send_task, mut recv_task) = tokio::sync::mpsc::channel(8);
+        super::reinsert_tasks(
+            &app.db_pool,
+            send_task,
+            None,
+            crate::api::TaskTypes {
+                webhook: true,
+                hash: true,
+            },
+        )
+        .await
+        .unwrap();
+        let mut reinserted_ids = Vec::new();
+        while let Ok((_, task)) = recv_task.try_recv() {
+            reinserted_ids.push(task.id().to_string());
+        }
+
+        // The webhook and hash due at the same time are ordered by ID.
+        let (tied_first, tied_second) = if ids[2] < ids[3] {
+            (&ids[2], &ids[3])
+        } else {
+            (&ids[3], &ids[2])
+        };
+        assert_eq!(
+            reinserted_ids.iter().collect::<Vec<_>>(),
+            [&ids[1], tied_first, tied_second, &ids[0]]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_failover_between_sqlite_databases() {
+        let db_urls = ["sqlite::memory:".to_string(), "sqlite::memory:".to_string()];
+        let res = super::init_open_db(&db_urls).await;
+        assert!(
+            matches!(res, Err(super::DbError::UnsupportedFailover)),
+            "{res:?}"
+        );
+
+        let db_urls = [
+            "postgres://127.0.0.1:9/tasker".to_string(),
+            "sqlite::memory:".to_string(),
+        ];
+        let res = super::init_open_db(&db_urls).await;
+        assert!(
+            matches!(res, Err(super::DbError::UnsupportedFailover)),
+            "{res:?}"
+        );
+    }
+}
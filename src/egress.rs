@@ -0,0 +1,161 @@
+/// Maximum number of redirects followed for a webhook request, as by default.
+const MAX_REDIRECTS: usize = 10;
+
+/// Policy on which addresses requests of webhook tasks may be sent to, if
+/// private ranges are blocked. Requests to loopback, link-local, private, and
+/// unspecified addresses are refused, unless the target host is explicitly
+/// allowed as a trusted internal host.
+#[derive(Debug, Clone)]
+pub(crate) struct EgressPolicy {
+    allowed_hosts: std::collections::HashSet<String>,
+}
+
+/// A webhook target host that resolved to a blocked address.
+#[derive(Debug)]
+pub(crate) struct Blocked {
+    host: String,
+    addr: std::net::IpAddr,
+}
+
+impl std::fmt::Display for Blocked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "host '{}' resolves to blocked address {}",
+            self.host, self.addr
+        )
+    }
+}
+
+impl std::error::Error for Blocked {}
+
+/// Returns whether requests to `ip` are refused, i.e., whether it is a
+/// loopback, link-local, private, or unspecified address. IPv4 addresses mapped
+/// into IPv6 are checked as the IPv4 address.
+fn is_blocked(ip: std::net::IpAddr) -> bool {
+    match ip {
+        std::net::IpAddr::V4(v4) => {
+            v4.is_loopback() || v4.is_link_local() || v4.is_private() || v4.is_unspecified()
+        }
+        std::net::IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_blocked(std::net::IpAddr::V4(v4)),
+            None => {
+                v6.is_loopback()
+                    || v6.is_unicast_link_local()
+                    || v6.is_unique_local()
+                    || v6.is_unspecified()
+            }
+        },
+    }
+}
+
+/// Returns the address a host of a URL denotes literally, if it is an IP
+/// address, with IPv6 addresses enclosed in brackets.
+fn ip_literal(host: &str) -> Option<std::net::IpAddr> {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
+impl EgressPolicy {
+    /// Creates the policy, allowing requests to `allowed_hosts` regardless of
+    /// the addresses they resolve to. Hosts are matched case-insensitively.
+    pub(crate) fn new(allowed_hosts: &[String]) -> Self {
+        EgressPolicy {
+            allowed_hosts: allowed_hosts
+                .iter()
+                .map(|h| h.trim().to_lowercase())
+                .filter(|h| !h.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Returns whether `host` is allowed regardless of its addresses.
+    fn allows_host(&self, host: &str) -> bool {
+        self.allowed_hosts.contains(&host.to_lowercase())
+    }
+
+    /// Resolves `host` to its addresses, with `port` set on each address. Fails
+    /// if any of the addresses is blocked, unless `host` is allowed.
+    async fn resolve(
+        &self,
+        host: &str,
+        port: u16,
+    ) -> std::result::Result<Vec<std::net::SocketAddr>, Box<dyn std::error::Error + Send + Sync>>
+    {
+        let lookup_host = host.trim_start_matches('[').trim_end_matches(']');
+        let addrs = tokio::net::lookup_host((lookup_host, port))
+            .await?
+            .collect::<Vec<_>>();
+        if self.allows_host(host) {
+            return Ok(addrs);
+        }
+        match addrs.iter().find(|a| is_blocked(a.ip())) {
+            Some(blocked) => Err(Box::new(Blocked {
+                host: host.to_string(),
+                addr: blocked.ip(),
+            })),
+            None => Ok(addrs),
+        }
+    }
+
+    /// Checks whether requests to the host of `url` may be sent, by resolving
+    /// it right now. Only a host resolving to a blocked address is reported.
+    /// Hosts that can't be resolved are left to fail when sending the request,
+    /// so that they are retried.
+    pub(crate) async fn check(&self, url: &str) -> std::result::Result<(), Blocked> {
+        let Some((host, port)) = reqwest::Url::parse(url).ok().and_then(|u| {
+            let port = u.port_or_known_default()?;
+            Some((u.host_str()?.to_string(), port))
+        }) else {
+            return Ok(());
+        };
+
+        match self.resolve(&host, port).await {
+            Err(e) => match e.downcast::<Blocked>() {
+                Ok(blocked) => Err(*blocked),
+                Err(_) => Ok(()),
+            },
+            Ok(_) => Ok(()),
+        }
+    }
+
+    /// Returns the redirect policy for webhook requests, refusing to follow
+    /// redirects to blocked IP addresses. Redirects to host names are checked
+    /// when resolving them via `EgressResolver`.
+    pub(crate) fn redirect_policy(self: &std::sync::Arc<Self>) -> reqwest::redirect::Policy {
+        let policy = self.clone();
+        reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                return attempt.error(format!("too many redirects (>{MAX_REDIRECTS})"));
+            }
+            let blocked = attempt.url().host_str().and_then(|host| {
+                let addr = ip_literal(host)?;
+                (is_blocked(addr) && !policy.allows_host(host)).then(|| Blocked {
+                    host: host.to_string(),
+                    addr,
+                })
+            });
+            match blocked {
+                Some(blocked) => attempt.error(blocked),
+                None => attempt.follow(),
+            }
+        })
+    }
+}
+
+/// Resolves the hosts of webhook requests, failing for hosts resolving to
+/// blocked addresses, so that requests can't reach them through redirects or
+/// host names changing their addresses after being checked.
+pub(crate) struct EgressResolver(pub(crate) std::sync::Arc<EgressPolicy>);
+
+impl reqwest::dns::Resolve for EgressResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let policy = self.0.clone();
+        Box::pin(async move {
+            let addrs = policy.resolve(name.as_str(), 0).await?;
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
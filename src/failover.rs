@@ -0,0 +1,157 @@
+use tracing::{Level, event};
+
+/// Interval at which the database the connection pool currently connects to is
+/// checked for still being reachable and writable.
+const FAILOVER_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Time after which a database that didn't respond to a check counts as
+/// unreachable.
+const FAILOVER_CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// The `PostgreSQL` databases listed in `DATABASE_URL` in order of preference,
+/// between which the connection pool fails over if the database it currently
+/// connects to becomes unreachable or read-only.
+#[derive(Debug)]
+pub(crate) struct Failover {
+    candidates: Vec<sqlx::postgres::PgConnectOptions>,
+    /// Time of the last failover. Connections opened before it may still point
+    /// to the database failed over from, and are discarded.
+    failed_over_at: std::sync::Mutex<Option<std::time::Instant>>,
+}
+
+impl Failover {
+    pub(crate) fn new(candidates: Vec<sqlx::postgres::PgConnectOptions>) -> Self {
+        Failover {
+            candidates,
+            failed_over_at: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Returns whether a connection opened `age` ago was opened before the last
+    /// failover.
+    pub(crate) fn predates_failover(&self, age: std::time::Duration) -> bool {
+        self.failed_over_at
+            .lock()
+            .is_ok_and(|at| at.is_some_and(|at| age > at.elapsed()))
+    }
+
+    /// Returns the connect options of the first database in order of preference
+    /// that is reachable and accepts writes, i.e., that is a primary rather than
+    /// a read-only standby.
+    pub(crate) async fn find_primary(
+        &self,
+    ) -> std::result::Result<sqlx::postgres::PgConnectOptions, crate::db::DbError> {
+        for (i, candidate) in self.candidates.iter().enumerate() {
+            match is_writable(candidate).await {
+                Ok(true) => return Ok(candidate.clone()),
+                Ok(false) => event!(
+                    Level::WARN,
+                    "Database #{} at '{}' is a read-only standby, skipping it",
+                    i + 1,
+                    describe(candidate),
+                ),
+                Err(e) => event!(
+                    Level::WARN,
+                    "Database #{} at '{}' is unreachable: {e}",
+                    i + 1,
+                    describe(candidate),
+                ),
+            }
+        }
+
+        Err(crate::db::DbError::NoPrimary(self.candidates.len()))
+    }
+}
+
+/// Describes the database at `db_opts` by its host and port, leaving out any
+/// credentials.
+fn describe(db_opts: &sqlx::postgres::PgConnectOptions) -> String {
+    format!("{}:{}", db_opts.get_host(), db_opts.get_port())
+}
+
+/// Connects to the database at `db_opts` outside of the connection pool, and
+/// checks whether it accepts writes.
+async fn is_writable(
+    db_opts: &sqlx::postgres::PgConnectOptions,
+) -> std::result::Result<bool, sqlx::Error> {
+    let check = async {
+        let mut conn =
+            <sqlx::postgres::PgConnection as sqlx::Connection>::connect_with(db_opts).await?;
+        let in_recovery: bool = sqlx::query_scalar("SELECT pg_is_in_recovery();")
+            .fetch_one(&mut conn)
+            .await?;
+        let _ = sqlx::Connection::close(conn).await;
+        Ok(!in_recovery)
+    };
+
+    tokio::time::timeout(FAILOVER_CHECK_TIMEOUT, check)
+        .await
+        .unwrap_or_else(|_| {
+            Err(sqlx::Error::Io(std::io::Error::from(
+                std::io::ErrorKind::TimedOut,
+            )))
+        })
+}
+
+/// Periodically checks that the database `db_pool` connects to is still
+/// reachable and writable, and otherwise points the pool to the first database
+/// in order of preference that is, until the pool is closed. Connections that
+/// were opened before a failover are discarded once they are acquired next.
+pub(crate) async fn run(db_pool: sqlx::postgres::PgPool, failover: std::sync::Arc<Failover>) {
+    let mut interval = tokio::time::interval(FAILOVER_CHECK_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            () = db_pool.close_event() => {
+                event!(Level::DEBUG, "Database failover task shutting down...");
+                return;
+            }
+        }
+
+        let current = db_pool.connect_options();
+        match is_writable(&current).await {
+            Ok(true) => continue,
+            Ok(false) => event!(
+                Level::WARN,
+                "Database at '{}' turned into a read-only standby, failing over...",
+                describe(&current),
+            ),
+            Err(e) => event!(
+                Level::WARN,
+                "Database at '{}' became unreachable ({e}), failing over...",
+                describe(&current),
+            ),
+        }
+
+        match failover.find_primary().await {
+            Ok(primary) if describe(&primary) == describe(&current) => {
+                event!(
+                    Level::INFO,
+                    "Database at '{}' is reachable and writable again",
+                    describe(&current),
+                );
+            }
+            Ok(primary) => {
+                event!(
+                    Level::WARN,
+                    "Failing over from database at '{}' to database at '{}'",
+                    describe(&current),
+                    describe(&primary),
+                );
+                // Point new connections to the new primary before discarding the
+                // ones opened so far, so that no connection to the previous
+                // database outlives the failover.
+                db_pool.set_connect_options(primary);
+                if let Ok(mut failed_over_at) = failover.failed_over_at.lock() {
+                    *failed_over_at = Some(std::time::Instant::now());
+                }
+            }
+            Err(e) => event!(
+                Level::WARN,
+                "Failing over failed: {e}, checking again in {FAILOVER_CHECK_INTERVAL:?}"
+            ),
+        }
+    }
+}
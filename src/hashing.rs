@@ -0,0 +1,307 @@
+use pbkdf2::password_hash::PasswordHasher;
+
+/// The algorithms available for computing the hash of a hash task.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Algorithm {
+    #[default]
+    Pbkdf2,
+    Argon2,
+    Bcrypt,
+}
+
+impl Algorithm {
+    /// Returns the name under which the algorithm is stored and exposed.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Algorithm::Pbkdf2 => "pbkdf2",
+            Algorithm::Argon2 => "argon2",
+            Algorithm::Bcrypt => "bcrypt",
+        }
+    }
+}
+
+impl std::str::FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "pbkdf2" => Ok(Algorithm::Pbkdf2),
+            "argon2" => Ok(Algorithm::Argon2),
+            "bcrypt" => Ok(Algorithm::Bcrypt),
+            _ => Err(format!(
+                "field 'algorithm' must be one of 'pbkdf2', 'argon2', or 'bcrypt', not '{s}'"
+            )),
+        }
+    }
+}
+
+/// Upper bounds for the cost parameters of hash tasks, configurable via CLI or
+/// ENV arguments, which prevent a single task from exhausting the resources of
+/// the host.
+#[derive(Debug, Clone)]
+pub(crate) struct HashLimits {
+    /// Maximum memory in KiB a single argon2 hash may use.
+    pub(crate) argon2_max_memory_kib: u32,
+    /// Maximum number of lanes a single argon2 hash may use.
+    pub(crate) argon2_max_parallelism: u32,
+}
+
+/// Tuning parameters for computing a PBKDF2 hash. Parameters not supplied by
+/// the user fall back to the defaults of 600,000 rounds and a 32-byte output.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "snake_case")]
+pub(crate) struct Pbkdf2Params {
+    pub(crate) rounds: u32,
+    pub(crate) output_length: usize,
+}
+
+impl Default for Pbkdf2Params {
+    fn default() -> Self {
+        Pbkdf2Params {
+            rounds: 600_000,
+            output_length: 32,
+        }
+    }
+}
+
+impl Pbkdf2Params {
+    /// Makes sure the parameters lie within bounds that yield a meaningful hash
+    /// without allowing a single task to occupy the worker for too long.
+    fn validate(&self) -> std::result::Result<(), String> {
+        if !(1_000..=10_000_000).contains(&self.rounds) {
+            return Err("field 'rounds' must lie between 1000 and 10000000".to_string());
+        }
+        if !(16..=64).contains(&self.output_length) {
+            return Err("field 'output_length' must lie between 16 and 64".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Tuning parameters for computing an argon2id hash. Parameters not supplied
+/// by the user fall back to the defaults recommended by OWASP, i.e., 19 MiB of
+/// memory, 2 iterations, and 1 lane, with a 32-byte output.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "snake_case")]
+pub(crate) struct Argon2Params {
+    pub(crate) memory_kib: u32,
+    pub(crate) iterations: u32,
+    pub(crate) parallelism: u32,
+    pub(crate) output_length: usize,
+}
+
+impl Default for Argon2Params {
+    fn default() -> Self {
+        Argon2Params {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+            output_length: 32,
+        }
+    }
+}
+
+impl Argon2Params {
+    /// Makes sure the parameters lie within bounds that yield a meaningful hash
+    /// and don't exceed the configured `limits`.
+    fn validate(&self, limits: &HashLimits) -> std::result::Result<(), String> {
+        if !(1..=limits.argon2_max_parallelism).contains(&self.parallelism) {
+            return Err(format!(
+                "field 'parallelism' must lie between 1 and {}",
+                limits.argon2_max_parallelism
+            ));
+        }
+        let min_memory_kib = 8 * self.parallelism;
+        if !(min_memory_kib..=limits.argon2_max_memory_kib).contains(&self.memory_kib) {
+            return Err(format!(
+                "field 'memory_kib' must lie between {min_memory_kib} and {}",
+                limits.argon2_max_memory_kib
+            ));
+        }
+        if !(1..=10).contains(&self.iterations) {
+            return Err("field 'iterations' must lie between 1 and 10".to_string());
+        }
+        if !(16..=64).contains(&self.output_length) {
+            return Err("field 'output_length' must lie between 16 and 64".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Maximum length in bytes of a secret hashed with bcrypt, which only considers
+/// this many bytes. Longer secrets are rejected rather than truncated.
+pub(crate) const BCRYPT_MAX_SECRET_LEN: usize = 72;
+
+/// Tuning parameters for computing a bcrypt hash. The cost is the base-2
+/// logarithm of the number of key expansion rounds and defaults to 12.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "snake_case")]
+pub(crate) struct BcryptParams {
+    pub(crate) cost: u32,
+}
+
+impl Default for BcryptParams {
+    fn default() -> Self {
+        BcryptParams {
+            cost: bcrypt::DEFAULT_COST,
+        }
+    }
+}
+
+impl BcryptParams {
+    /// Makes sure the cost yields a meaningful hash without allowing a single
+    /// task to occupy the worker for too long, as every increment doubles the
+    /// time the hash takes.
+    fn validate(&self) -> std::result::Result<(), String> {
+        if !(4..=16).contains(&self.cost) {
+            return Err("field 'cost' must lie between 4 and 16".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// The validated parameters of a hash task for its algorithm.
+#[derive(Debug, Clone)]
+pub(crate) enum HashParams {
+    Pbkdf2(Pbkdf2Params),
+    Argon2(Argon2Params),
+    Bcrypt(BcryptParams),
+}
+
+/// Parses and validates the `params` object of a hash task for `algorithm`.
+pub(crate) fn parse_params(
+    algorithm: Algorithm,
+    params: serde_json::Value,
+    limits: &HashLimits,
+) -> std::result::Result<HashParams, String> {
+    let invalid = |e: serde_json::Error| format!("field 'params' is invalid: {e}");
+    match algorithm {
+        Algorithm::Pbkdf2 => {
+            let params = serde_json::from_value::<Pbkdf2Params>(params).map_err(invalid)?;
+            params.validate()?;
+            Ok(HashParams::Pbkdf2(params))
+        }
+        Algorithm::Argon2 => {
+            let params = serde_json::from_value::<Argon2Params>(params).map_err(invalid)?;
+            params.validate(limits)?;
+            Ok(HashParams::Argon2(params))
+        }
+        Algorithm::Bcrypt => {
+            let params = serde_json::from_value::<BcryptParams>(params).map_err(invalid)?;
+            params.validate()?;
+            Ok(HashParams::Bcrypt(params))
+        }
+    }
+}
+
+/// Parses and validates the user-supplied `params` object of a hash task and
+/// fills in defaults for missing parameters, so that the returned object fully
+/// describes how the hash will be computed.
+pub(crate) fn normalize_params(
+    algorithm: Algorithm,
+    params: Option<serde_json::Value>,
+    limits: &HashLimits,
+) -> std::result::Result<serde_json::Value, String> {
+    let params = parse_params(
+        algorithm,
+        params.unwrap_or_else(|| serde_json::json!({})),
+        limits,
+    )?;
+    match params {
+        HashParams::Pbkdf2(p) => serde_json::to_value(p),
+        HashParams::Argon2(p) => serde_json::to_value(p),
+        HashParams::Bcrypt(p) => serde_json::to_value(p),
+    }
+    .map_err(|e| format!("field 'params' is invalid: {e}"))
+}
+
+/// Computes the hash of `secret` with a fresh random salt, using the algorithm
+/// and configuration described by `params`. Returns the hash in PHC string
+/// format, or in the modular crypt format for bcrypt, both of which name the
+/// algorithm and its parameters alongside the hash.
+fn hash(secret: &[u8], params: &HashParams) -> std::result::Result<String, String> {
+    let salt = pbkdf2::password_hash::SaltString::generate(&mut rand::rngs::OsRng);
+    match params {
+        HashParams::Pbkdf2(params) => pbkdf2::Pbkdf2
+            .hash_password_customized(
+                secret,
+                None,
+                None,
+                pbkdf2::Params {
+                    rounds: params.rounds,
+                    output_length: params.output_length,
+                },
+                &salt,
+            )
+            .map(|h| h.to_string())
+            .map_err(|e| e.to_string()),
+        HashParams::Argon2(params) => {
+            let params = argon2::Params::new(
+                params.memory_kib,
+                params.iterations,
+                params.parallelism,
+                Some(params.output_length),
+            )
+            .map_err(|e| e.to_string())?;
+            argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+                .hash_password(secret, &salt)
+                .map(|h| h.to_string())
+                .map_err(|e| e.to_string())
+        }
+        HashParams::Bcrypt(params) => {
+            bcrypt::non_truncating_hash(secret, params.cost).map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Computes the hashes of hash tasks on the blocking thread pool. The memory
+/// used by concurrently running argon2 hashes is capped by a budget: a hash
+/// only starts once enough of the budget is available for the memory it
+/// requires.
+#[derive(Debug)]
+pub(crate) struct Hasher {
+    limits: HashLimits,
+    argon2_memory_budget: std::sync::Arc<tokio::sync::Semaphore>,
+    argon2_memory_budget_kib: u32,
+}
+
+impl Hasher {
+    pub(crate) fn new(limits: HashLimits, argon2_memory_budget_kib: u32) -> Self {
+        Hasher {
+            limits,
+            argon2_memory_budget: std::sync::Arc::new(tokio::sync::Semaphore::new(
+                argon2_memory_budget_kib as usize,
+            )),
+            argon2_memory_budget_kib,
+        }
+    }
+
+    /// Parses and validates the stored parameters of a hash task against the
+    /// currently configured limits and computes the hash of `secret`.
+    pub(crate) async fn compute(
+        &self,
+        secret: Vec<u8>,
+        algorithm: Algorithm,
+        params: serde_json::Value,
+    ) -> std::result::Result<String, String> {
+        let params = parse_params(algorithm, params, &self.limits)?;
+
+        // Reserve the memory an argon2 hash requires from the budget for as long
+        // as it is being computed. The budget is never closed.
+        let _reservation = match &params {
+            HashParams::Argon2(p) => Some(
+                self.argon2_memory_budget
+                    .clone()
+                    .acquire_many_owned(p.memory_kib.min(self.argon2_memory_budget_kib))
+                    .await
+                    .map_err(|e| e.to_string())?,
+            ),
+            HashParams::Pbkdf2(_) | HashParams::Bcrypt(_) => None,
+        };
+
+        tokio::task::spawn_blocking(move || hash(&secret, &params))
+            .await
+            .map_err(|e| e.to_string())?
+    }
+}
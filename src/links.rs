@@ -0,0 +1,139 @@
+use base64::prelude::*;
+use hmac::Mac;
+
+type HmacSha256 = hmac::Hmac<sha2::Sha256>;
+
+/// Longest time in seconds for which a signed link may be valid.
+pub(crate) const MAX_LINK_TTL_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// The shape of the task a signed link authorizes the creation of: its type,
+/// and the values some of its fields must have. Fields not listed may take any
+/// value.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub(crate) struct LinkGrant {
+    #[serde(rename = "type")]
+    pub(crate) task_type: String,
+    #[serde(default)]
+    pub(crate) fields: serde_json::Map<String, serde_json::Value>,
+    /// Time as UNIX timestamp in seconds after which the link is invalid.
+    pub(crate) expires_at: i64,
+}
+
+impl LinkGrant {
+    /// Makes sure the prepared `task` has the type and field values this grant
+    /// authorizes. Fields are compared against the task as it would be stored,
+    /// i.e., after defaults were filled in and values normalized.
+    pub(crate) fn authorizes(&self, task: &crate::api::Task) -> std::result::Result<(), String> {
+        if task.type_name() != self.task_type {
+            return Err(format!(
+                "link only authorizes creating {} tasks",
+                self.task_type
+            ));
+        }
+
+        let task = serde_json::to_value(task).map_err(|e| e.to_string())?;
+        let task = task.get(&self.task_type).and_then(|t| t.as_object());
+        for (field, value) in &self.fields {
+            if task.and_then(|t| t.get(field)) != Some(value) {
+                return Err(format!("field '{field}' of task doesn't match the link"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Token with which clients authenticate as bearer token in header
+/// `Authorization`, e.g., admins to admin endpoints, configured via CLI or ENV
+/// arguments.
+#[derive(Clone)]
+pub(crate) struct BearerToken(String);
+
+// Never print the token, e.g., as part of the API's configuration.
+impl std::fmt::Debug for BearerToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BearerToken").finish_non_exhaustive()
+    }
+}
+
+impl BearerToken {
+    pub(crate) fn new(token: String) -> Self {
+        BearerToken(token)
+    }
+
+    /// Returns whether `token` is the configured token, comparing in constant
+    /// time.
+    pub(crate) fn matches(&self, token: &str) -> bool {
+        let (a, b) = (token.as_bytes(), self.0.as_bytes());
+        a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+    }
+}
+
+/// Secret for issuing and verifying signed links, configured via CLI or ENV
+/// arguments. Links are issued by admins and signed with `signing_key`.
+#[derive(Clone)]
+pub(crate) struct Links {
+    signing_key: Vec<u8>,
+}
+
+// Never print the secret, e.g., as part of the API's configuration.
+impl std::fmt::Debug for Links {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Links").finish_non_exhaustive()
+    }
+}
+
+impl Links {
+    pub(crate) fn new(signing_key: &str) -> Self {
+        Links {
+            signing_key: signing_key.as_bytes().to_vec(),
+        }
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        // HMAC accepts keys of any length, so this can't fail.
+        <HmacSha256 as Mac>::new_from_slice(&self.signing_key)
+            .unwrap_or_else(|_| unreachable!("HMAC accepts keys of any length"))
+    }
+
+    /// Encodes `grant` into a token in the format `PAYLOAD.SIGNATURE`, both
+    /// parts URL-safe Base64, with the signature being the HMAC-SHA256 of the
+    /// payload.
+    pub(crate) fn sign(&self, grant: &LinkGrant) -> std::result::Result<String, String> {
+        let payload = serde_json::to_vec(grant).map_err(|e| e.to_string())?;
+        let mut mac = self.mac();
+        mac.update(&payload);
+        let signature = mac.finalize().into_bytes();
+
+        Ok(format!(
+            "{}.{}",
+            BASE64_URL_SAFE_NO_PAD.encode(payload),
+            BASE64_URL_SAFE_NO_PAD.encode(signature)
+        ))
+    }
+
+    /// Verifies the signature of `token` and that it hasn't expired yet, and
+    /// returns the grant it encodes.
+    pub(crate) fn verify(&self, token: &str) -> std::result::Result<LinkGrant, String> {
+        let invalid = || "link is invalid".to_string();
+        let (payload, signature) = token.split_once('.').ok_or_else(invalid)?;
+        let payload = BASE64_URL_SAFE_NO_PAD
+            .decode(payload)
+            .map_err(|_| invalid())?;
+        let signature = BASE64_URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| invalid())?;
+
+        let mut mac = self.mac();
+        mac.update(&payload);
+        mac.verify_slice(&signature).map_err(|_| invalid())?;
+
+        let grant = serde_json::from_slice::<LinkGrant>(&payload).map_err(|_| invalid())?;
+        if grant.expires_at <= chrono::Utc::now().timestamp() {
+            return Err("link has expired".to_string());
+        }
+
+        Ok(grant)
+    }
+}
@@ -3,7 +3,21 @@ use tracing::{Level, event};
 use tracing_subscriber::prelude::*;
 
 mod api;
+mod audit;
+mod bench;
+mod calendar;
 mod db;
+mod egress;
+mod failover;
+mod hashing;
+mod links;
+mod maintenance;
+mod metrics;
+mod policy;
+mod shutdown;
+mod telemetry;
+#[cfg(test)]
+mod testing;
 mod worker;
 
 #[derive(Debug)]
@@ -11,6 +25,9 @@ enum AppError {
     Tracing(tracing_subscriber::filter::ParseError),
     Db(crate::db::DbError),
     Io(std::io::Error),
+    Config(String),
+    Metrics(metrics_exporter_prometheus::BuildError),
+    Telemetry(opentelemetry_otlp::ExporterBuildError),
 }
 
 impl std::fmt::Display for AppError {
@@ -19,6 +36,9 @@ impl std::fmt::Display for AppError {
             AppError::Tracing(e) => write!(f, "{e}"),
             AppError::Db(e) => write!(f, "{e}"),
             AppError::Io(e) => write!(f, "{e}"),
+            AppError::Config(e) => write!(f, "{e}"),
+            AppError::Metrics(e) => write!(f, "{e}"),
+            AppError::Telemetry(e) => write!(f, "{e}"),
         }
     }
 }
@@ -41,12 +61,63 @@ impl From<std::io::Error> for AppError {
     }
 }
 
+impl From<metrics_exporter_prometheus::BuildError> for AppError {
+    fn from(err: metrics_exporter_prometheus::BuildError) -> Self {
+        Self::Metrics(err)
+    }
+}
+
+impl From<opentelemetry_otlp::ExporterBuildError> for AppError {
+    fn from(err: opentelemetry_otlp::ExporterBuildError) -> Self {
+        Self::Telemetry(err)
+    }
+}
+
+#[derive(Debug, Clone, clap::Subcommand)]
+enum Command {
+    /// Measure the achievable scheduling precision with the current settings by
+    /// scheduling webhook tasks at known execution times against an in-process
+    /// mock target and reporting how late they fired.
+    Bench(bench::BenchArgs),
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum LogFormat {
+    Compact,
+    Json,
+}
+
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Parser)]
 #[command(about, author, version)]
 struct Args {
-    #[arg(long, env, default_value = "sqlite://tasks.db")]
-    /// Connection URL to database used to store this scheduler's tasks.
-    database_url: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[arg(long, env, value_enum, default_value_t = LogFormat::Compact)]
+    /// Format of log output, either human-readable lines (`compact`) or one
+    /// JSON object per line (`json`).
+    log_format: LogFormat,
+
+    #[arg(long, env)]
+    /// Full URL of the traces endpoint of an OpenTelemetry collector (e.g.
+    /// `http://localhost:4318/v1/traces`) to which spans of webhook deliveries
+    /// are exported via OTLP over HTTP. If not set, no traces are exported.
+    otlp_endpoint: Option<String>,
+
+    #[arg(long, env)]
+    /// Path of a file to which every state transition of a task is appended as
+    /// one JSON object per line, for auditing. Transitions are always emitted as
+    /// log events with target `audit` as well.
+    audit_log: Option<std::path::PathBuf>,
+
+    #[arg(long, env, default_value = "sqlite://tasks.db", value_delimiter = ',')]
+    /// Connection URL to database used to store this scheduler's tasks, either a
+    /// `SQLite` database (`sqlite://...`) or a `PostgreSQL` database
+    /// (`postgres://...`). Several comma-separated `PostgreSQL` URLs are tried in
+    /// order, failing over to the next reachable and writable one whenever the
+    /// current one becomes unreachable or read-only.
+    database_url: Vec<String>,
 
     #[arg(long, env, default_value = "127.0.0.1")]
     /// IP address on which the task scheduler's HTTP handler listens.
@@ -55,69 +126,918 @@ struct Args {
     #[arg(long, env, default_value_t = 8080)]
     /// Port on which the task scheduler's HTTP handler listens.
     listen_port: u16,
+
+    #[arg(long, env)]
+    /// Maximum number of concurrent webhook requests to a single host, at least
+    /// 1. Unlimited if not set.
+    max_connections_per_host: Option<usize>,
+
+    #[arg(long, env, value_delimiter = ',')]
+    /// Per-host overrides of the maximum number of concurrent webhook requests,
+    /// in the format `HOST=LIMIT` with a limit of at least 1.
+    host_connection_limit: Vec<String>,
+
+    #[arg(long, env, default_value_t = 90)]
+    /// Time in seconds after which idle connections to webhook targets are
+    /// closed. Until then, they are reused by later requests to the same host.
+    webhook_pool_idle_timeout: u64,
+
+    #[arg(long, env, default_value_t = 15)]
+    /// Interval in seconds between TCP keepalive probes on connections to
+    /// webhook targets. A value of 0 disables TCP keepalive.
+    webhook_tcp_keepalive: u64,
+
+    #[arg(long, env)]
+    /// Refuse to send requests of webhook tasks to hosts resolving to loopback,
+    /// link-local, or private addresses (e.g. `localhost`, `169.254.169.254`, or
+    /// `10.0.0.0/8`). Webhook tasks targeting such hosts are moved to the dead
+    /// letters without sending any request.
+    block_private_ranges: bool,
+
+    #[arg(long, env, value_delimiter = ',', requires = "block_private_ranges")]
+    /// Trusted internal hosts to which requests of webhook tasks may be sent
+    /// despite `--block-private-ranges`, as host names or IP addresses.
+    allow_private_host: Vec<String>,
+
+    #[arg(long, env)]
+    /// Delay in seconds after submission at which webhook tasks without an
+    /// `execution_time` are executed. If not set, `execution_time` is required.
+    default_webhook_delay: Option<u64>,
+
+    #[arg(long, env)]
+    /// Delay in seconds after submission at which hash tasks without an
+    /// `execution_time` are executed. If not set, `execution_time` is required.
+    default_hash_delay: Option<u64>,
+
+    #[arg(long, env, default_value_t = 5)]
+    /// Time in seconds by which the execution time of a new or rescheduled task
+    /// may lie in the past and still be accepted, in which case the task is
+    /// handled right away. Tolerates clients whose clocks are slightly behind.
+    clock_skew_tolerance: u64,
+
+    #[arg(long, env, value_enum, default_value_t = api::TimePrecision::Nanos)]
+    /// Precision of the fractional seconds of times in API responses, either
+    /// whole seconds (`seconds`), milliseconds (`millis`), or nanoseconds
+    /// (`nanos`). Times are always stored with full precision.
+    time_output_precision: api::TimePrecision,
+
+    #[arg(long, env)]
+    /// Body of webhook tasks that don't specify a `body`. If not set, `body` is
+    /// required.
+    default_webhook_body: Option<String>,
+
+    #[arg(long, env, default_value_t = 2048)]
+    /// Maximum length in bytes of the URL of a webhook task. Longer URLs are
+    /// rejected upon creation.
+    max_webhook_url_len: usize,
+
+    #[arg(long, env, default_value_t = 64 * 1024)]
+    /// Maximum length in bytes of the body of a webhook task. Longer bodies are
+    /// rejected upon creation.
+    max_webhook_body_len: usize,
+
+    #[arg(long, env, default_value_t = 1024 * 1024)]
+    /// Maximum size in bytes of the body of requests to the HTTP API. Larger
+    /// requests are rejected with `413 Payload Too Large` before being buffered
+    /// in full, so that oversized requests can't exhaust the memory.
+    max_body_bytes: usize,
+
+    #[arg(long, env)]
+    /// Time in seconds after a task finished at which its result payload (the
+    /// response body of a webhook task or the computed hash of a hash task) is
+    /// purged, keeping the task itself. If not set, results are kept.
+    result_ttl: Option<u64>,
+
+    #[arg(long, env, default_value_t = 30)]
+    /// Number of days after their execution time at which tasks in state
+    /// `done` or `failed` are deleted automatically.
+    retention_days: u64,
+
+    #[arg(long, env, default_value_t = 60 * 60)]
+    /// Interval in seconds at which tasks past their retention are deleted.
+    cleanup_interval_secs: u64,
+
+    #[arg(long, env, default_value_t = 64)]
+    /// Maximum number of tasks of any type the worker handles concurrently.
+    /// Further ready tasks wait until a running one finished.
+    max_concurrent_tasks: usize,
+
+    #[arg(long, env)]
+    /// Maximum number of ready webhook tasks waiting in the outbound queue for
+    /// a sender. If set, webhook tasks are delivered by a fixed pool of
+    /// `--outbound-senders` senders, and ready webhook tasks not fitting into
+    /// the full queue are deferred. Webhook tasks are handed to their own
+    /// handlers right away if not set.
+    outbound_queue_depth: Option<usize>,
+
+    #[arg(long, env, default_value_t = 16)]
+    /// Number of senders draining the outbound queue, each delivering one
+    /// webhook task at a time.
+    outbound_senders: usize,
+
+    #[arg(long, env, default_value_t = 1)]
+    /// Maximum number of tasks of the same type that are claimed for handling
+    /// in a single database statement when they become ready at the same time.
+    /// A value of 1 claims each task on its own.
+    claim_batch_size: usize,
+
+    #[arg(long, env)]
+    /// Time in seconds beyond which tasks are kept in a far-future bucket that
+    /// is only checked every half horizon, instead of in the actively polled
+    /// delay queue. Keeps the delay queue small when many tasks are scheduled
+    /// far ahead. All tasks are kept in the delay queue if not set.
+    near_term_horizon_secs: Option<u64>,
+
+    #[arg(long, env, default_value_t = 100)]
+    /// Maximum number of distinct webhook target hosts that get their own
+    /// `host` label in exported metrics. Further hosts are labeled `other`.
+    metrics_max_hosts: usize,
+
+    #[arg(long, env)]
+    /// Minimum free space in megabytes on the database's filesystem. While less
+    /// space is available, new tasks are rejected. Unchecked if not set.
+    min_free_disk_mb: Option<u64>,
+
+    #[arg(long, env, default_value_t = 65_536)]
+    /// Maximum memory in KiB a single argon2 hash task may request via its
+    /// `memory_kib` parameter.
+    argon2_max_memory_kib: u32,
+
+    #[arg(long, env, default_value_t = 4)]
+    /// Maximum number of lanes a single argon2 hash task may request via its
+    /// `parallelism` parameter.
+    argon2_max_parallelism: u32,
+
+    #[arg(long, env, default_value_t = 262_144)]
+    /// Total memory in KiB that concurrently computed argon2 hashes may use.
+    /// Further argon2 hash tasks wait until enough memory is available.
+    argon2_memory_budget_kib: u32,
+
+    #[arg(long, env, default_value = "Idempotency-Key")]
+    /// Name of the header carrying the idempotency key of webhook tasks that
+    /// have one.
+    idempotency_key_header: String,
+
+    #[arg(long, env, default_value_t = true, action = clap::ArgAction::Set)]
+    /// Back off for a random time between zero and the current backoff before
+    /// each retry of a webhook task's request, so that tasks failing against
+    /// the same target don't retry in lockstep. Set to `false` to back off for
+    /// exactly the current backoff.
+    retry_jitter: bool,
+
+    #[arg(long, env)]
+    /// Time in milliseconds to wait before retrying the first request of a
+    /// webhook task once, if it failed to connect to the target. This grace
+    /// retry doesn't count as a failed attempt, so that targets momentarily
+    /// unreachable at the scheduled time don't use up a retry. Connection
+    /// errors count as failed attempts right away if not set.
+    webhook_connect_grace_ms: Option<u64>,
+
+    #[arg(long, env)]
+    /// Make batch requests all-or-nothing: a single failing item rolls back the
+    /// whole batch. By default, the items of a batch succeed or fail
+    /// individually.
+    batch_atomic: bool,
+
+    #[arg(long, env)]
+    /// Maximum number of overdue tasks per second released to the worker upon
+    /// start, i.e., tasks whose execution time passed while `tasker` wasn't
+    /// running. Overdue tasks are released right away if not set.
+    reinsert_rate_per_sec: Option<u32>,
+
+    #[arg(long, env)]
+    /// Path of a file to write a JSON report to upon shutdown, listing the
+    /// number of tasks left in each state and how many in-flight handlers were
+    /// awaited or abandoned. The report is logged either way.
+    shutdown_report: Option<std::path::PathBuf>,
+
+    #[arg(long, env, default_value_t = 30)]
+    /// Time in seconds to wait upon shutdown for handlers still working on a
+    /// task to finish, while no further tasks are started. Handlers still
+    /// working afterwards are abandoned and their tasks reset to `todo` upon
+    /// the next start. Set to `0` to not wait at all.
+    shutdown_grace_secs: u64,
+
+    #[arg(long, env, value_delimiter = ',')]
+    /// Holidays in the format `YYYY-MM-DD`, on which recurring webhook tasks
+    /// restricted to the `business` calendar don't fire. Weekends are always
+    /// excluded from the `business` calendar.
+    business_holidays: Vec<chrono::NaiveDate>,
+
+    #[arg(long, env, value_enum, default_value_t = worker::PanicPolicy::FailTask)]
+    /// How to recover the tasks of a handler that panicked, e.g. due to a bug:
+    /// mark them as `failed` (`fail-task`), or reset them to `todo` and handle
+    /// them again 10 seconds later (`requeue`). Either way, the panic is logged
+    /// with the IDs of the tasks.
+    panic_policy: worker::PanicPolicy,
+
+    #[arg(long, env)]
+    /// Run only the worker, without serving the HTTP API. Tasks in the database
+    /// are handled until shutdown is initiated, but tasks created afterwards,
+    /// e.g. via a separate instance serving the HTTP API, are only picked up
+    /// upon the next start.
+    no_api: bool,
+
+    #[arg(long, env, default_value_t = true, action = clap::ArgAction::Set)]
+    /// Accept and handle webhook tasks. Set to `false` for deployments only
+    /// handling hash tasks, in which creating webhook tasks is rejected.
+    enable_webhook_tasks: bool,
+
+    #[arg(long, env, default_value_t = true, action = clap::ArgAction::Set)]
+    /// Accept and handle hash tasks. Set to `false` for deployments only
+    /// handling webhook tasks, in which creating hash tasks is rejected.
+    enable_hash_tasks: bool,
+
+    #[arg(long, env)]
+    /// Path to a JSON file defining named policies for delivering webhook tasks
+    /// (`max_retries`, `initial_backoff_ms`, `timeout_ms`) and mapping tags to
+    /// them. Webhook tasks carrying a mapped tag inherit the settings of its
+    /// policy that they don't specify themselves.
+    tag_policies: Option<std::path::PathBuf>,
+
+    #[arg(long, env)]
+    /// Maximum number of tasks stored per task type. Once reached, the oldest
+    /// finished tasks (`done`, `failed`, or `cancelled`) are evicted to make
+    /// room for new ones. If only pending tasks remain, new tasks are rejected.
+    /// Unbounded if not set.
+    max_rows_per_table: Option<u64>,
+
+    #[arg(long, env, default_value_t = 100)]
+    /// Number of overdue tasks at which the overdue component of the health
+    /// score reported via `GET /admin/health-score` drops to zero.
+    health_max_overdue_tasks: u64,
+
+    #[arg(long, env, default_value_t = 0.5)]
+    /// Share of failed tasks among the tasks finished within the last hour at
+    /// which the failure component of the health score drops to zero.
+    health_max_failure_rate: f64,
+
+    #[arg(long, env, default_value_t = 10_000)]
+    /// Number of pending tasks at which the queue depth component of the health
+    /// score drops to zero.
+    health_max_queue_depth: u64,
+
+    #[arg(long, env)]
+    /// Maximum number of webhook tasks created per minute, allowing bursts of
+    /// up to as many tasks. Further tasks are rejected with `429 Too Many
+    /// Requests` until the budget recovered. Unlimited if not set.
+    webhook_create_rate: Option<u32>,
+
+    #[arg(long, env)]
+    /// Maximum number of hash tasks created per minute, allowing bursts of up to
+    /// as many tasks. Further tasks are rejected with `429 Too Many Requests`
+    /// until the budget recovered. Unlimited if not set.
+    hash_create_rate: Option<u32>,
+
+    #[arg(long, env, requires = "admin_token")]
+    /// Key with which signed links authorizing the creation of tasks of a
+    /// specific shape are signed. Signed links are unavailable if not set.
+    link_signing_key: Option<String>,
+
+    #[arg(long, env)]
+    /// Token with which admins authenticate as bearer token to the admin
+    /// endpoints under `/admin/` and to the debug view of tasks, e.g. to inspect
+    /// the worker's status, to issue signed links, and to change the
+    /// concurrency limit at runtime. These endpoints are unavailable if not set.
+    admin_token: Option<String>,
+
+    #[arg(long, env)]
+    /// Key with which clients authenticate as bearer token to all endpoints
+    /// managing tasks and templates. Requests without it are rejected with
+    /// `401 Unauthorized`. Health, readiness, and metrics endpoints remain
+    /// open. These endpoints are open to everyone if not set.
+    api_key: Option<String>,
+
+    #[arg(long, env)]
+    /// URL to which a summary (`id`, `type`, `execution_time`) of every created
+    /// task is sent as JSON in a POST request, so that external systems can
+    /// mirror the task log without polling. Notifications are best-effort and
+    /// never delay or fail the creation of tasks. No notifications are sent if
+    /// not set.
+    on_task_created_url: Option<String>,
 }
 
-// Properly handle the CTRL+C signal and shut everything down.
-async fn shutdown_upon_signal(send_shutdown: tokio::sync::broadcast::Sender<()>) {
-    let _ = tokio::signal::ctrl_c().await;
-    event!(Level::INFO, "Received signal to shut down gracefully");
-    drop(send_shutdown);
+/// Range of the maximum number of concurrent webhook requests to a host. A
+/// limit of 0 would keep all requests to the host waiting forever.
+const HOST_CONNECTION_LIMIT: std::ops::RangeInclusive<usize> =
+    1..=tokio::sync::Semaphore::MAX_PERMITS;
+
+/// Parses the `HOST=LIMIT` pairs supplied via `--host-connection-limit` into a
+/// map from host to its maximum number of concurrent webhook requests.
+fn parse_host_connection_limits(
+    limits: &[String],
+) -> std::result::Result<std::collections::HashMap<String, usize>, AppError> {
+    let mut host_limits = std::collections::HashMap::with_capacity(limits.len());
+    for limit in limits {
+        let Some((host, max)) = limit.split_once('=') else {
+            return Err(AppError::Config(format!(
+                "host connection limit '{limit}' must be in the format 'HOST=LIMIT'"
+            )));
+        };
+        let Some(max) = max
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|m| HOST_CONNECTION_LIMIT.contains(m))
+        else {
+            return Err(AppError::Config(format!(
+                "host connection limit '{limit}' must specify a limit between {} and {}",
+                HOST_CONNECTION_LIMIT.start(),
+                HOST_CONNECTION_LIMIT.end(),
+            )));
+        };
+        host_limits.insert(host.trim().to_lowercase(), max);
+    }
+    Ok(host_limits)
 }
 
-#[tokio::main]
-async fn main() -> std::result::Result<(), AppError> {
-    // Accept and parse CLI and ENV arguments.
-    let args = Args::parse();
+/// Sets up the per-host limits for concurrent webhook requests.
+fn host_limiter(args: &Args) -> std::result::Result<std::sync::Arc<worker::HostLimiter>, AppError> {
+    if let Some(max) = args.max_connections_per_host
+        && !HOST_CONNECTION_LIMIT.contains(&max)
+    {
+        return Err(AppError::Config(format!(
+            "maximum connections per host must lie between {} and {}",
+            HOST_CONNECTION_LIMIT.start(),
+            HOST_CONNECTION_LIMIT.end(),
+        )));
+    }
+    Ok(std::sync::Arc::new(worker::HostLimiter::new(
+        args.max_connections_per_host,
+        parse_host_connection_limits(&args.host_connection_limit)?,
+    )))
+}
 
-    // Set up tracing.
-    let format_layer = tracing_subscriber::fmt::layer()
-        .with_file(true)
-        .with_line_number(true)
-        .compact();
-    let filter_layer = tracing_subscriber::EnvFilter::try_from_default_env()
-        .or_else(|_| tracing_subscriber::EnvFilter::try_new("info"))?;
-    tracing_subscriber::registry()
-        .with(filter_layer)
-        .with(format_layer)
-        .init();
-    event!(Level::INFO, "Launching tasker...");
+/// Sets up monitoring of the free space on the database's filesystem if a
+/// minimum is configured, and checks the free space right away. In-memory
+/// databases and databases on a `PostgreSQL` server are not monitored.
+fn disk_space(
+    args: &Args,
+) -> std::result::Result<Option<std::sync::Arc<maintenance::DiskSpace>>, AppError> {
+    let Some(min_free_disk_mb) = args.min_free_disk_mb else {
+        return Ok(None);
+    };
+    let [db_url] = args.database_url.as_slice() else {
+        return Ok(None);
+    };
+    if db::is_postgres_url(db_url) {
+        return Ok(None);
+    }
 
-    // Open and potentially initialize our SQLite database.
-    let db_pool = db::init_open_db(&args.database_url).await?;
+    let db_opts = <sqlx::sqlite::SqliteConnectOptions as std::str::FromStr>::from_str(db_url)
+        .map_err(db::DbError::from)?;
+    let db_file = db_opts.get_filename();
+    if db_file.as_os_str().is_empty() || db_file.as_os_str() == ":memory:" {
+        return Ok(None);
+    }
+    let db_dir = match db_file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => std::path::PathBuf::from("."),
+    };
 
-    // Prepare channel which upon dropping one half initiates shutdown.
-    let (send_shutdown, _) = tokio::sync::broadcast::channel::<()>(1);
+    let disk_space =
+        maintenance::DiskSpace::new(db_dir, min_free_disk_mb.saturating_mul(1024 * 1024));
+    disk_space.check();
+    Ok(Some(std::sync::Arc::new(disk_space)))
+}
 
-    // Prepare channel for inserting tasks into the DelayQueue we're using for
-    // time-based task scheduling.
-    let (send_task, recv_task) =
-        tokio::sync::mpsc::channel::<(tokio::time::Duration, crate::api::Task)>(256);
+/// Validates the limits for the cost parameters of hash tasks supplied via CLI
+/// or ENV arguments.
+fn hash_limits(args: &Args) -> std::result::Result<hashing::HashLimits, AppError> {
+    if args.argon2_max_parallelism == 0 {
+        return Err(AppError::Config(
+            "argon2 maximum parallelism must be at least 1".to_string(),
+        ));
+    }
+    if args.argon2_max_memory_kib < args.argon2_max_parallelism.saturating_mul(8) {
+        return Err(AppError::Config(
+            "argon2 maximum memory must be at least 8 KiB per lane of maximum parallelism"
+                .to_string(),
+        ));
+    }
+    if args.argon2_max_memory_kib > args.argon2_memory_budget_kib {
+        return Err(AppError::Config(
+            "argon2 maximum memory must not exceed the argon2 memory budget".to_string(),
+        ));
+    }
 
-    // Create background worker context and tokio task, in which the tasks stored in
-    // the database will be handled.
-    let worker_ctx = worker::WorkerCtx::new(db_pool.clone());
-    let worker_shutdown = send_shutdown.subscribe();
-    let worker_hdl = tokio::task::spawn(worker_ctx.run(worker_shutdown, recv_task));
+    Ok(hashing::HashLimits {
+        argon2_max_memory_kib: args.argon2_max_memory_kib,
+        argon2_max_parallelism: args.argon2_max_parallelism,
+    })
+}
 
-    // Reinsert tasks from database into DelayQueue before making REST API to insert
-    // new ones available to clients.
-    db::reinsert_tasks(&db_pool, send_task.clone()).await?;
+/// Longest time in seconds idle connections to webhook targets may be kept.
+const MAX_WEBHOOK_POOL_IDLE_TIMEOUT_SECS: u64 = 60 * 60;
 
-    // Prepare context struct that is passed to each Axum HTTP API handler below.
-    let api_ctx = api::ApiCtx::new(db_pool.clone(), send_task);
+/// Longest interval in seconds between TCP keepalive probes on connections to
+/// webhook targets.
+const MAX_WEBHOOK_TCP_KEEPALIVE_SECS: u64 = 2 * 60 * 60;
 
-    // Define all routes and assign the respective handler to each.
-    let router = axum::Router::new()
+/// Validates the connection settings for webhook requests and builds the HTTP
+/// client shared by all webhook tasks, so that connections to the same host
+/// are reused across tasks.
+fn webhook_client(
+    args: &Args,
+    egress_policy: Option<&std::sync::Arc<egress::EgressPolicy>>,
+) -> std::result::Result<reqwest::Client, AppError> {
+    if !(1..=MAX_WEBHOOK_POOL_IDLE_TIMEOUT_SECS).contains(&args.webhook_pool_idle_timeout) {
+        return Err(AppError::Config(format!(
+            "webhook pool idle timeout must lie between 1 and {MAX_WEBHOOK_POOL_IDLE_TIMEOUT_SECS} seconds"
+        )));
+    }
+    if args.webhook_tcp_keepalive > MAX_WEBHOOK_TCP_KEEPALIVE_SECS {
+        return Err(AppError::Config(format!(
+            "webhook TCP keepalive interval must lie between 0 and {MAX_WEBHOOK_TCP_KEEPALIVE_SECS} seconds"
+        )));
+    }
+
+    let builder = reqwest::Client::builder()
+        .pool_idle_timeout(std::time::Duration::from_secs(
+            args.webhook_pool_idle_timeout,
+        ))
+        .tcp_keepalive(
+            (args.webhook_tcp_keepalive > 0)
+                .then(|| std::time::Duration::from_secs(args.webhook_tcp_keepalive)),
+        );
+
+    // Check every address connected to against the egress policy, including
+    // those of redirects, if private ranges are blocked.
+    let builder = match egress_policy {
+        Some(policy) => builder
+            .dns_resolver(egress::EgressResolver(policy.clone()))
+            .redirect(policy.redirect_policy()),
+        None => builder,
+    };
+
+    builder
+        .build()
+        .map_err(|e| AppError::Config(format!("failed to build webhook HTTP client: {e}")))
+}
+
+/// Validates the settings of the outbound queue of webhook tasks and creates
+/// it, if configured.
+fn outbound_queue(
+    args: &Args,
+) -> std::result::Result<Option<std::sync::Arc<worker::OutboundQueue>>, AppError> {
+    let Some(depth) = args.outbound_queue_depth else {
+        return Ok(None);
+    };
+    if depth == 0 {
+        return Err(AppError::Config(
+            "outbound queue depth must be at least 1".to_string(),
+        ));
+    }
+    if args.outbound_senders == 0 {
+        return Err(AppError::Config(
+            "number of outbound senders must be at least 1".to_string(),
+        ));
+    }
+    Ok(Some(std::sync::Arc::new(worker::OutboundQueue::new(
+        depth,
+        args.outbound_senders,
+    ))))
+}
+
+/// Longest time in seconds to wait upon shutdown for in-flight handlers.
+const MAX_SHUTDOWN_GRACE_SECS: u64 = 60 * 60;
+
+/// Computes hashes within the configured limits, capping the memory used by
+/// concurrent argon2 hashes at the configured budget.
+fn hasher(args: &Args, api_config: &api::ApiConfig) -> std::sync::Arc<hashing::Hasher> {
+    std::sync::Arc::new(hashing::Hasher::new(
+        api_config.hash_limits.clone(),
+        args.argon2_memory_budget_kib,
+    ))
+}
+
+/// Validates the time to wait upon shutdown for in-flight handlers.
+fn shutdown_grace(args: &Args) -> std::result::Result<std::time::Duration, AppError> {
+    if args.shutdown_grace_secs > MAX_SHUTDOWN_GRACE_SECS {
+        return Err(AppError::Config(format!(
+            "shutdown grace period must lie between 0 and {MAX_SHUTDOWN_GRACE_SECS} seconds"
+        )));
+    }
+    Ok(std::time::Duration::from_secs(args.shutdown_grace_secs))
+}
+
+/// Validates the settings of how the worker handles tasks.
+fn worker_options(args: &Args) -> std::result::Result<worker::WorkerOptions, AppError> {
+    Ok(worker::WorkerOptions {
+        claim_batch_size: claim_batch_size(args)?,
+        shutdown_grace: shutdown_grace(args)?,
+        panic_policy: args.panic_policy,
+        retry_jitter: args.retry_jitter,
+        near_term_horizon: near_term_horizon(args)?,
+        connect_grace: connect_grace(args)?,
+        egress_policy: args
+            .block_private_ranges
+            .then(|| std::sync::Arc::new(egress::EgressPolicy::new(&args.allow_private_host))),
+        trace_webhooks: args.otlp_endpoint.is_some(),
+    })
+}
+
+/// Range of the time in milliseconds to wait before the grace retry of a
+/// webhook task's first request that failed to connect.
+const WEBHOOK_CONNECT_GRACE_MS: std::ops::RangeInclusive<u64> = 1..=60 * 1000;
+
+/// Validates the time to wait before the grace retry of a webhook task's first
+/// request that failed to connect.
+fn connect_grace(args: &Args) -> std::result::Result<Option<std::time::Duration>, AppError> {
+    match args.webhook_connect_grace_ms {
+        Some(ms) if !WEBHOOK_CONNECT_GRACE_MS.contains(&ms) => Err(AppError::Config(format!(
+            "webhook connect grace must lie between {} and {} milliseconds",
+            WEBHOOK_CONNECT_GRACE_MS.start(),
+            WEBHOOK_CONNECT_GRACE_MS.end(),
+        ))),
+        ms => Ok(ms.map(std::time::Duration::from_millis)),
+    }
+}
+
+/// Range of the time in seconds beyond which tasks are kept in the far-future
+/// bucket instead of the delay queue.
+const NEAR_TERM_HORIZON_SECS: std::ops::RangeInclusive<u64> = 60..=30 * 24 * 60 * 60;
+
+/// Validates the time beyond which tasks are kept in the far-future bucket.
+fn near_term_horizon(args: &Args) -> std::result::Result<Option<std::time::Duration>, AppError> {
+    match args.near_term_horizon_secs {
+        Some(secs) if !NEAR_TERM_HORIZON_SECS.contains(&secs) => Err(AppError::Config(format!(
+            "near-term horizon must lie between {} and {} seconds",
+            NEAR_TERM_HORIZON_SECS.start(),
+            NEAR_TERM_HORIZON_SECS.end(),
+        ))),
+        secs => Ok(secs.map(std::time::Duration::from_secs)),
+    }
+}
+
+/// Maximum number of days for which finished tasks are retained.
+const MAX_RETENTION_DAYS: u64 = 100 * 365;
+
+/// Validates the time after their execution time at which finished tasks are
+/// deleted.
+fn retention(args: &Args) -> std::result::Result<std::time::Duration, AppError> {
+    if !(1..=MAX_RETENTION_DAYS).contains(&args.retention_days) {
+        return Err(AppError::Config(format!(
+            "retention must lie between 1 and {MAX_RETENTION_DAYS} days"
+        )));
+    }
+    Ok(std::time::Duration::from_secs(
+        args.retention_days * 24 * 60 * 60,
+    ))
+}
+
+/// Validates the interval at which tasks past their retention are deleted.
+fn cleanup_interval(args: &Args) -> std::result::Result<std::time::Duration, AppError> {
+    if args.cleanup_interval_secs == 0 {
+        return Err(AppError::Config(
+            "cleanup interval must be at least 1 second".to_string(),
+        ));
+    }
+    Ok(std::time::Duration::from_secs(args.cleanup_interval_secs))
+}
+
+/// Validates the number of due tasks the worker claims at once.
+fn claim_batch_size(args: &Args) -> std::result::Result<usize, AppError> {
+    if args.claim_batch_size == 0 {
+        return Err(AppError::Config(
+            "claim batch size must be at least 1".to_string(),
+        ));
+    }
+    Ok(args.claim_batch_size)
+}
+
+/// Validates the number of tasks the worker handles concurrently.
+fn max_concurrent_tasks(args: &Args) -> std::result::Result<usize, AppError> {
+    if args.max_concurrent_tasks == 0 {
+        return Err(AppError::Config(
+            "maximum number of concurrent tasks must be at least 1".to_string(),
+        ));
+    }
+    Ok(args.max_concurrent_tasks)
+}
+
+/// Sets up the limits of the rate at which tasks of each type are created.
+fn create_rate_limits(args: &Args) -> std::result::Result<api::CreateRateLimits, AppError> {
+    if args.webhook_create_rate == Some(0) || args.hash_create_rate == Some(0) {
+        return Err(AppError::Config(
+            "creation rates for task types must be at least 1 task per minute".to_string(),
+        ));
+    }
+    Ok(api::CreateRateLimits {
+        webhook: args.webhook_create_rate.map(api::CreateRateLimiter::new),
+        hash: args.hash_create_rate.map(api::CreateRateLimiter::new),
+    })
+}
+
+/// Validates the rate at which overdue tasks are released upon start.
+fn reinsert_rate_per_sec(args: &Args) -> std::result::Result<Option<u32>, AppError> {
+    if args.reinsert_rate_per_sec == Some(0) {
+        return Err(AppError::Config(
+            "reinsertion rate must be at least 1 task per second".to_string(),
+        ));
+    }
+    Ok(args.reinsert_rate_per_sec)
+}
+
+/// Parses the name of the header carrying the idempotency key of webhook tasks.
+fn idempotency_key_header(
+    args: &Args,
+) -> std::result::Result<reqwest::header::HeaderName, AppError> {
+    reqwest::header::HeaderName::from_bytes(args.idempotency_key_header.as_bytes())
+        .map_err(|e| AppError::Config(format!("invalid idempotency key header: {e}")))
+}
+
+/// Longest time in seconds by which execution times may lie in the past and
+/// still be accepted.
+const MAX_CLOCK_SKEW_TOLERANCE_SECS: u64 = 60;
+
+/// Validates the settings of the HTTP API supplied via CLI or ENV arguments and
+/// collects them into the API's configuration.
+fn api_config(args: &Args) -> std::result::Result<api::ApiConfig, AppError> {
+    // A default delay of zero would place tasks without an execution time
+    // (slightly) into the past by the time they are handled.
+    if args.default_webhook_delay == Some(0) || args.default_hash_delay == Some(0) {
+        return Err(AppError::Config(
+            "default delays for task types must be at least 1 second".to_string(),
+        ));
+    }
+    if args.clock_skew_tolerance > MAX_CLOCK_SKEW_TOLERANCE_SECS {
+        return Err(AppError::Config(format!(
+            "clock skew tolerance must lie between 0 and {MAX_CLOCK_SKEW_TOLERANCE_SECS} seconds"
+        )));
+    }
+    if args.max_rows_per_table == Some(0) {
+        return Err(AppError::Config(
+            "maximum number of tasks per type must be at least 1".to_string(),
+        ));
+    }
+    if args.link_signing_key.as_deref() == Some("")
+        || args.admin_token.as_deref() == Some("")
+        || args.api_key.as_deref() == Some("")
+    {
+        return Err(AppError::Config(
+            "link signing key, admin token, and API key must not be empty".to_string(),
+        ));
+    }
+    if args.health_max_overdue_tasks == 0
+        || args.health_max_queue_depth == 0
+        || !(args.health_max_failure_rate > 0.0 && args.health_max_failure_rate <= 1.0)
+    {
+        return Err(AppError::Config(
+            "health score thresholds must be positive, and the failure rate at most 1".to_string(),
+        ));
+    }
+    if args.default_webhook_body.as_deref() == Some("") {
+        return Err(AppError::Config(
+            "default webhook body must not be empty".to_string(),
+        ));
+    }
+    if args.max_body_bytes == 0 {
+        return Err(AppError::Config(
+            "maximum size of request bodies must be at least 1 byte".to_string(),
+        ));
+    }
+    if !args.enable_webhook_tasks && !args.enable_hash_tasks {
+        return Err(AppError::Config(
+            "at least one task type must be enabled".to_string(),
+        ));
+    }
+    if args.max_webhook_url_len == 0 || args.max_webhook_body_len == 0 {
+        return Err(AppError::Config(
+            "maximum lengths of webhook URLs and bodies must be at least 1 byte".to_string(),
+        ));
+    }
+    if args
+        .default_webhook_body
+        .as_ref()
+        .is_some_and(|b| b.len() > args.max_webhook_body_len)
+    {
+        return Err(AppError::Config(
+            "default webhook body must not exceed the maximum webhook body length".to_string(),
+        ));
+    }
+
+    Ok(api::ApiConfig {
+        webhook_delay: args
+            .default_webhook_delay
+            .map(tokio::time::Duration::from_secs),
+        hash_delay: args
+            .default_hash_delay
+            .map(tokio::time::Duration::from_secs),
+        webhook_body: args.default_webhook_body.clone(),
+        max_webhook_url_len: args.max_webhook_url_len,
+        max_webhook_body_len: args.max_webhook_body_len,
+        hash_limits: hash_limits(args)?,
+        batch_atomic: args.batch_atomic,
+        tag_policies: match &args.tag_policies {
+            Some(path) => policy::TagPolicies::load(path).map_err(AppError::Config)?,
+            None => policy::TagPolicies::default(),
+        },
+        max_rows_per_table: args.max_rows_per_table,
+        links: args.link_signing_key.as_deref().map(links::Links::new),
+        admin_token: args.admin_token.clone().map(links::BearerToken::new),
+        api_key: args.api_key.clone().map(links::BearerToken::new),
+        clock_skew_tolerance: tokio::time::Duration::from_secs(args.clock_skew_tolerance),
+        time_precision: args.time_output_precision,
+        on_task_created: creation_notifier(args)?,
+        business_calendar: std::sync::Arc::new(calendar::BusinessCalendar::new(
+            &args.business_holidays,
+        )),
+        health_thresholds: api::HealthThresholds {
+            overdue_tasks: args.health_max_overdue_tasks,
+            failure_rate: args.health_max_failure_rate,
+            queue_depth: args.health_max_queue_depth,
+        },
+        task_types: api::TaskTypes {
+            webhook: args.enable_webhook_tasks,
+            hash: args.enable_hash_tasks,
+        },
+    })
+}
+
+/// Validates the URL to notify of created tasks and creates the notifier for
+/// it, if configured.
+fn creation_notifier(args: &Args) -> std::result::Result<Option<api::CreationNotifier>, AppError> {
+    let Some(url) = &args.on_task_created_url else {
+        return Ok(None);
+    };
+
+    let url = reqwest::Url::parse(url)
+        .map_err(|e| AppError::Config(format!("malformed URL to notify of created tasks: {e}")))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(AppError::Config(
+            "URL to notify of created tasks must use scheme 'http' or 'https'".to_string(),
+        ));
+    }
+
+    Ok(Some(api::CreationNotifier::new(url)))
+}
+
+/// Sets up tracing, logging at the level configured via `RUST_LOG`, exporting
+/// traces if an OTLP endpoint is configured, and appending to the audit log if
+/// one is configured. Returns the guard that flushes outstanding traces once
+/// dropped.
+fn init_tracing(args: &Args) -> std::result::Result<telemetry::TelemetryGuard, AppError> {
+    // Exactly one of both layers is set, depending on the configured format.
+    let (compact_layer, json_layer) = match args.log_format {
+        LogFormat::Compact => (
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .with_file(true)
+                    .with_line_number(true)
+                    .compact(),
+            ),
+            None,
+        ),
+        LogFormat::Json => (
+            None,
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .with_file(true)
+                    .with_line_number(true)
+                    .json(),
+            ),
+        ),
+    };
+    // Only log warnings by default while benchmarking, so that they don't drown
+    // out the report.
+    let default_filter = if args.command.is_some() {
+        "warn"
+    } else {
+        "info"
+    };
+    let filter_layer = tracing_subscriber::EnvFilter::try_from_default_env()
+        .or_else(|_| tracing_subscriber::EnvFilter::try_new(default_filter))?;
+    let (otel_layer, telemetry_guard) = match &args.otlp_endpoint {
+        Some(endpoint) => {
+            let (layer, guard) = telemetry::init(endpoint)?;
+            (Some(layer), guard)
+        }
+        None => (None, telemetry::TelemetryGuard::disabled()),
+    };
+    tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(compact_layer)
+        .with(json_layer)
+        .with(otel_layer)
+        .init();
+    if let Some(path) = &args.audit_log {
+        audit::init(path)?;
+    }
+    Ok(telemetry_guard)
+}
+
+// Properly handle the CTRL+C signal and shut everything down.
+async fn shutdown_upon_signal(send_shutdown: tokio::sync::broadcast::Sender<()>) {
+    let _ = tokio::signal::ctrl_c().await;
+    event!(Level::INFO, "Received signal to shut down gracefully");
+    drop(send_shutdown);
+}
+
+// Define the routes of the HTTP API for operators of the scheduler. They
+// authenticate via the admin token instead of the API key.
+fn admin_routes() -> axum::Router<api::ApiCtx> {
+    axum::Router::new()
+        .without_v07_checks()
+        .route(
+            "/admin/status",
+            axum::routing::get(crate::api::get_admin_status),
+        )
+        .route(
+            "/admin/concurrency",
+            axum::routing::post(crate::api::post_admin_concurrency),
+        )
+        .route(
+            "/admin/links",
+            axum::routing::post(crate::api::post_admin_links),
+        )
+        .route(
+            "/admin/reload",
+            axum::routing::post(crate::api::post_admin_reload),
+        )
+        .route(
+            "/admin/drain-estimate",
+            axum::routing::get(crate::api::get_admin_drain_estimate),
+        )
+        .route(
+            "/admin/host-stats",
+            axum::routing::get(crate::api::get_admin_host_stats),
+        )
+        .route(
+            "/admin/health-score",
+            axum::routing::get(crate::api::get_admin_health_score),
+        )
+        .route(
+            "/admin/schema",
+            axum::routing::get(crate::api::get_admin_schema),
+        )
+        .route(
+            "/tasks/{id}/debug",
+            axum::routing::get(crate::api::get_task_debug),
+        )
+}
+
+// Define all routes of the HTTP API and assign the respective handler to each,
+// limiting the size of request bodies to `max_body_bytes`.
+fn build_router(api_ctx: api::ApiCtx, max_body_bytes: usize) -> axum::Router {
+    // All endpoints managing tasks and templates require the API key, if one is
+    // configured.
+    let tasks = axum::Router::new()
         .without_v07_checks()
         .route(
             "/tasks/new",
             axum::routing::post(crate::api::post_tasks_new),
         )
+        .route(
+            "/tasks/dead-letters",
+            axum::routing::get(crate::api::get_dead_letters),
+        )
+        .route(
+            "/tasks/dead-letters/{id}/requeue",
+            axum::routing::post(crate::api::requeue_dead_letter),
+        )
+        .route(
+            "/tasks/new/from-template/{name}",
+            axum::routing::post(crate::api::post_tasks_new_from_template),
+        )
+        .route(
+            "/templates",
+            axum::routing::post(crate::api::post_templates),
+        )
+        .route(
+            "/tasks/new/batch",
+            axum::routing::post(crate::api::post_tasks_new_batch),
+        )
+        .route(
+            "/tasks/validate",
+            axum::routing::post(crate::api::post_tasks_validate),
+        )
+        .route(
+            "/tasks/delete/batch",
+            axum::routing::post(crate::api::post_tasks_delete_batch),
+        )
+        .route("/tasks", axum::routing::get(crate::api::get_tasks))
+        .route(
+            "/tasks/scheduled",
+            axum::routing::get(crate::api::get_tasks_scheduled),
+        )
+        .route(
+            "/tasks/count",
+            axum::routing::get(crate::api::get_tasks_count),
+        )
         .route("/tasks/{id}", axum::routing::get(crate::api::get_task))
+        .route(
+            "/tasks/{id}/eta",
+            axum::routing::get(crate::api::get_task_eta),
+        )
         .route(
             "/tasks/state/{state}",
             axum::routing::get(crate::api::get_tasks_by_state),
         )
+        .route(
+            "/tasks/state/{state}",
+            axum::routing::delete(crate::api::delete_tasks_by_state),
+        )
         .route(
             "/tasks/type/{type}",
             axum::routing::get(crate::api::get_tasks_by_type),
@@ -126,7 +1046,47 @@ async fn main() -> std::result::Result<(), AppError> {
             "/tasks/{id}",
             axum::routing::delete(crate::api::delete_task),
         )
-        .with_state(api_ctx);
+        .route("/tasks/{id}", axum::routing::patch(crate::api::patch_task))
+        .route(
+            "/tasks/{id}/cancel",
+            axum::routing::post(crate::api::cancel_task),
+        )
+        .route(
+            "/tasks/{id}/hold",
+            axum::routing::post(crate::api::hold_task),
+        )
+        .route(
+            "/tasks/{id}/release",
+            axum::routing::post(crate::api::release_task),
+        )
+        .route(
+            "/tasks/{id}/trigger",
+            axum::routing::post(crate::api::trigger_task),
+        )
+        .route_layer(axum::middleware::from_fn_with_state(
+            api_ctx.clone(),
+            crate::api::require_api_key,
+        ));
+
+    axum::Router::new()
+        .without_v07_checks()
+        .merge(tasks)
+        .merge(admin_routes())
+        .route("/metrics", axum::routing::get(crate::api::get_metrics))
+        .route("/healthz", axum::routing::get(crate::api::get_healthz))
+        .route("/readyz", axum::routing::get(crate::api::get_readyz))
+        .layer(axum::extract::DefaultBodyLimit::max(max_body_bytes))
+        .with_state(api_ctx)
+}
+
+// Serve the HTTP API on the configured IP and port until shutdown is initiated.
+async fn serve_api(
+    args: &Args,
+    api_ctx: api::ApiCtx,
+    send_shutdown: tokio::sync::broadcast::Sender<()>,
+) -> std::result::Result<(), AppError> {
+    // Define all routes and assign the respective handler to each.
+    let router = build_router(api_ctx, args.max_body_bytes);
 
     // Open a TCP socket using tokio, on the configured IP and port.
     let api_sock_url = format!("{}:{}", args.listen_ip, args.listen_port);
@@ -141,8 +1101,197 @@ async fn main() -> std::result::Result<(), AppError> {
         .with_graceful_shutdown(shutdown_upon_signal(send_shutdown))
         .await?;
 
-    let _ = worker_hdl.await;
+    Ok(())
+}
+
+/// Spawns the periodic maintenance task performing the configured maintenance
+/// duties.
+fn spawn_maintenance(
+    args: &Args,
+    db_pool: &db::DbPool,
+    disk_space: Option<std::sync::Arc<maintenance::DiskSpace>>,
+    send_shutdown: &tokio::sync::broadcast::Sender<()>,
+) -> std::result::Result<tokio::task::JoinHandle<()>, AppError> {
+    let maintenance_config = maintenance::MaintenanceConfig {
+        result_ttl: args.result_ttl.map(tokio::time::Duration::from_secs),
+        retention: retention(args)?,
+        cleanup_interval: cleanup_interval(args)?,
+        disk_space,
+    };
+    Ok(tokio::task::spawn(maintenance::run(
+        db_pool.clone(),
+        maintenance_config,
+        send_shutdown.subscribe(),
+    )))
+}
+
+/// Waits for the worker to shut down, including its grace period for in-flight
+/// handlers, and closes the database after collecting the shutdown report,
+/// which is logged and written to the file configured via `--shutdown-report`,
+/// if any.
+async fn close_and_report(
+    args: &Args,
+    db_pool: db::DbPool,
+    worker_hdl: tokio::task::JoinHandle<usize>,
+    task_limiter: &worker::TaskLimiter,
+) -> std::result::Result<(), AppError> {
+    let in_flight_at_stop = worker_hdl.await.unwrap_or_default();
+    let report = shutdown::ShutdownReport::collect(&db_pool, in_flight_at_stop, task_limiter).await;
     db_pool.close().await;
 
+    report.log();
+    if let Some(path) = &args.shutdown_report {
+        report.write(path)?;
+    }
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> std::result::Result<(), AppError> {
+    // Accept and parse CLI and ENV arguments.
+    let args = Args::parse();
+
+    let _telemetry_guard = init_tracing(&args)?;
+    event!(Level::INFO, "Launching tasker...");
+
+    let host_limiter = host_limiter(&args)?;
+
+    // Limit the number of tasks the worker handles concurrently.
+    let task_limiter = std::sync::Arc::new(worker::TaskLimiter::new(max_concurrent_tasks(&args)?));
+    let outbound = outbound_queue(&args)?;
+
+    // Keep track of how many tasks the worker finished within the last five
+    // minutes, for estimating when all pending tasks will have been handled.
+    let throughput =
+        std::sync::Arc::new(worker::Throughput::new(tokio::time::Duration::from_mins(5)));
+
+    // Keep track of how late the DelayQueue yields tasks compared to their
+    // deadlines, for judging the achievable scheduling precision.
+    let scheduling_error = std::sync::Arc::new(worker::SchedulingError::default());
+
+    // Install the recorder for the metrics exported via `GET /metrics`.
+    let metrics = std::sync::Arc::new(metrics::Metrics::install(args.metrics_max_hosts)?);
+
+    // Keep track of the webhook tasks currently being handled, so that their
+    // in-flight requests can be aborted upon cancellation.
+    let cancel_registry = std::sync::Arc::new(worker::CancelRegistry::default());
+
+    // Keep track of whether the worker is running, so that the API can reject
+    // new tasks once it stopped.
+    let worker_alive = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
+
+    let worker_options = worker_options(&args)?;
+    let api_config = api_config(&args)?;
+    let create_rate_limits = create_rate_limits(&args)?;
+    let idempotency_key_header = idempotency_key_header(&args)?;
+    let webhook_client = webhook_client(&args, worker_options.egress_policy.as_ref())?;
+    let reinsert_rate_per_sec = reinsert_rate_per_sec(&args)?;
+
+    let hasher = hasher(&args, &api_config);
+
+    // Run the benchmark with a worker configured as for serving the HTTP API,
+    // if requested, instead of serving the HTTP API.
+    if let Some(Command::Bench(bench_args)) = &args.command {
+        return bench::run(bench_args, |db_pool, send_task| {
+            worker::WorkerCtx::new(
+                db_pool,
+                send_task,
+                host_limiter,
+                task_limiter,
+                throughput,
+                scheduling_error,
+                cancel_registry,
+                worker_alive,
+                worker_options,
+                api_config.business_calendar.clone(),
+                metrics,
+                hasher,
+                idempotency_key_header,
+                webhook_client,
+                outbound,
+            )
+        })
+        .await;
+    }
+
+    // Open our database, potentially initializing it first.
+    let db_pool = db::init_open_db(&args.database_url).await?;
+
+    // Prepare channel which upon dropping one half initiates shutdown.
+    let (send_shutdown, _) = tokio::sync::broadcast::channel::<()>(1);
+
+    // Prepare channel for inserting tasks into the DelayQueue we're using for
+    // time-based task scheduling.
+    let (send_task, recv_task) =
+        tokio::sync::mpsc::channel::<(tokio::time::Duration, crate::api::Task)>(256);
+
+    // Prepare channel for sending commands concerning queued tasks to the worker.
+    let (send_cmd, recv_cmd) = tokio::sync::mpsc::channel::<worker::WorkerCmd>(256);
+
+    // Create background worker context and tokio task, in which the tasks stored in
+    // the database will be handled.
+    let worker_ctx = worker::WorkerCtx::new(
+        db_pool.clone(),
+        send_task.clone(),
+        host_limiter.clone(),
+        task_limiter.clone(),
+        throughput.clone(),
+        scheduling_error.clone(),
+        cancel_registry.clone(),
+        worker_alive.clone(),
+        worker_options,
+        api_config.business_calendar.clone(),
+        metrics.clone(),
+        hasher,
+        idempotency_key_header,
+        webhook_client,
+        outbound.clone(),
+    );
+    let worker_hdl =
+        tokio::task::spawn(worker_ctx.run(send_shutdown.subscribe(), recv_task, recv_cmd));
+
+    let disk_space = disk_space(&args)?;
+    let maintenance_hdl = spawn_maintenance(&args, &db_pool, disk_space.clone(), &send_shutdown)?;
+
+    // Reinsert tasks from database into DelayQueue before making REST API to insert
+    // new ones available to clients.
+    db::reinsert_tasks(
+        &db_pool,
+        send_task.clone(),
+        reinsert_rate_per_sec,
+        api_config.task_types,
+    )
+    .await?;
+
+    if args.no_api {
+        // Without the HTTP API, only the worker handles the reinserted tasks until
+        // shutdown is initiated.
+        event!(Level::INFO, "Running worker without HTTP API...");
+        shutdown_upon_signal(send_shutdown).await;
+    } else {
+        // Prepare context struct that is passed to each Axum HTTP API handler.
+        let api_ctx = api::ApiCtx::new(
+            db_pool.clone(),
+            send_task,
+            send_cmd,
+            host_limiter,
+            task_limiter.clone(),
+            outbound,
+            throughput,
+            scheduling_error,
+            cancel_registry,
+            worker_alive,
+            metrics,
+            disk_space,
+            std::sync::Arc::new(create_rate_limits),
+            api_config,
+        );
+        serve_api(&args, api_ctx, send_shutdown).await?;
+    }
+
+    let _ = maintenance_hdl.await;
+    close_and_report(&args, db_pool, worker_hdl, &task_limiter).await?;
+
     Ok(())
 }
@@ -0,0 +1,170 @@
+use tracing::{Level, event};
+
+/// Interval at which the maintenance task performs its duties.
+const MAINTENANCE_INTERVAL: std::time::Duration = std::time::Duration::from_mins(1);
+
+/// Settings of the periodic maintenance task configurable via CLI or ENV
+/// arguments.
+#[derive(Debug, Clone)]
+pub(crate) struct MaintenanceConfig {
+    /// Time after a task finished at which its result payload is purged. If not
+    /// set, results are kept for as long as the task exists.
+    pub(crate) result_ttl: Option<std::time::Duration>,
+    /// Time after their execution time at which tasks in state `done` or
+    /// `failed` are deleted.
+    pub(crate) retention: std::time::Duration,
+    /// Interval at which tasks past their retention are deleted.
+    pub(crate) cleanup_interval: std::time::Duration,
+    /// Monitor of the free space on the database's filesystem, if a minimum of
+    /// free space is configured.
+    pub(crate) disk_space: Option<std::sync::Arc<DiskSpace>>,
+}
+
+/// Monitors the free space on the filesystem holding the database. Once less
+/// than `min_free` bytes are available, the space is reported as low, so that
+/// no new tasks are accepted before `SQLite` runs out of space to write to.
+#[derive(Debug)]
+pub(crate) struct DiskSpace {
+    dir: std::path::PathBuf,
+    min_free: u64,
+    low: std::sync::atomic::AtomicBool,
+}
+
+impl DiskSpace {
+    pub(crate) fn new(dir: std::path::PathBuf, min_free: u64) -> Self {
+        DiskSpace {
+            dir,
+            min_free,
+            low: std::sync::atomic::AtomicBool::new(false),
+        }
+    }
+
+    /// Checks the currently available space and updates whether it is low. If
+    /// the available space can't be determined, the previous result is kept.
+    pub(crate) fn check(&self) {
+        let available = match fs4::available_space(&self.dir) {
+            Ok(a) => a,
+            Err(e) => {
+                event!(
+                    Level::WARN,
+                    "Determining available disk space at '{}' failed with: {e}",
+                    self.dir.display(),
+                );
+                return;
+            }
+        };
+
+        let low = available < self.min_free;
+        if low {
+            event!(
+                Level::WARN,
+                "Only {available} bytes of disk space available at '{}', below the minimum of {} \
+                bytes, rejecting new tasks",
+                self.dir.display(),
+                self.min_free,
+            );
+        } else if self.is_low() {
+            event!(
+                Level::INFO,
+                "Disk space available at '{}' is back above the minimum, accepting new tasks",
+                self.dir.display(),
+            );
+        }
+        self.low.store(low, std::sync::atomic::Ordering::Release);
+    }
+
+    /// Returns whether the available space was below the minimum when last
+    /// checked.
+    pub(crate) fn is_low(&self) -> bool {
+        self.low.load(std::sync::atomic::Ordering::Acquire)
+    }
+}
+
+/// Purges the result payloads of all tasks that finished longer than
+/// `result_ttl` ago. The task rows themselves, including their final state,
+/// are kept.
+async fn purge_results(db_pool: &crate::db::DbPool, result_ttl: std::time::Duration) {
+    let Some(cutoff) = chrono::TimeDelta::from_std(result_ttl)
+        .ok()
+        .and_then(|ttl| chrono::Utc::now().checked_sub_signed(ttl))
+    else {
+        return;
+    };
+
+    match crate::db::purge_results(db_pool, &crate::db::timestamp(cutoff)).await {
+        Ok(0) => {}
+        Ok(n) => event!(Level::INFO, "Purged result payloads of {n} finished tasks"),
+        Err(e) => event!(Level::WARN, "Purging result payloads failed with: {e}"),
+    }
+}
+
+/// Deletes all tasks in state `done` or `failed` whose execution time lies
+/// longer than `retention` ago.
+async fn prune_finished_tasks(db_pool: &crate::db::DbPool, retention: std::time::Duration) {
+    let Some(cutoff) = chrono::TimeDelta::from_std(retention)
+        .ok()
+        .and_then(|retention| chrono::Utc::now().checked_sub_signed(retention))
+    else {
+        return;
+    };
+
+    match crate::db::prune_finished_tasks(db_pool, &crate::db::timestamp(cutoff)).await {
+        Ok(n) => event!(
+            Level::INFO,
+            "Pruned {n} finished tasks past their retention"
+        ),
+        Err(e) => event!(Level::WARN, "Pruning finished tasks failed with: {e}"),
+    }
+}
+
+/// Deletes all idempotency keys of task creation requests that were recorded
+/// longer than `IDEMPOTENCY_KEY_TTL` ago, as they no longer apply.
+async fn purge_idempotency_keys(db_pool: &crate::db::DbPool) {
+    let Some(cutoff) = crate::api::idempotency_key_cutoff() else {
+        return;
+    };
+
+    match crate::db::purge_idempotency_keys(db_pool, &crate::db::timestamp(cutoff)).await {
+        Ok(0) => {}
+        Ok(n) => event!(Level::DEBUG, "Purged {n} expired idempotency keys"),
+        Err(e) => event!(
+            Level::WARN,
+            "Purging expired idempotency keys failed with: {e}"
+        ),
+    }
+}
+
+/// Periodically performs the configured maintenance duties on the database, as
+/// well as purging expired idempotency keys and pruning finished tasks past
+/// their retention, until shutdown is initiated.
+pub(crate) async fn run(
+    db_pool: crate::db::DbPool,
+    config: MaintenanceConfig,
+    mut recv_shutdown: tokio::sync::broadcast::Receiver<()>,
+) {
+    let mut interval = tokio::time::interval(MAINTENANCE_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut cleanup_interval = tokio::time::interval(config.cleanup_interval);
+    cleanup_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                if let Some(disk_space) = &config.disk_space {
+                    disk_space.check();
+                }
+                if let Some(result_ttl) = config.result_ttl {
+                    purge_results(&db_pool, result_ttl).await;
+                }
+                purge_idempotency_keys(&db_pool).await;
+            }
+            _ = cleanup_interval.tick() => {
+                prune_finished_tasks(&db_pool, config.retention).await;
+            }
+            _ = recv_shutdown.recv() => {
+                event!(Level::DEBUG, "Maintenance task shutting down...");
+                return;
+            }
+        }
+    }
+}
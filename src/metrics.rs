@@ -0,0 +1,148 @@
+/// Name of the counter of tasks that reached a terminal state in the worker,
+/// labeled by `type`, `outcome`, and for webhook tasks by target `host`.
+const TASK_OUTCOMES_TOTAL: &str = "tasker_task_outcomes_total";
+
+/// Name of the counter of tasks that reached a terminal state in the worker,
+/// labeled by `type` and `outcome` only. Unlike `TASK_OUTCOMES_TOTAL`, all its
+/// series are known upfront and thus rendered before any task finished.
+const TASKS_TOTAL: &str = "task_scheduler_tasks_total";
+
+/// Name of the histogram of the durations of webhook requests until their
+/// response arrived, in seconds.
+const WEBHOOK_REQUEST_DURATION_SECONDS: &str = "task_scheduler_webhook_request_duration_seconds";
+
+/// Upper bounds in seconds of the buckets of `WEBHOOK_REQUEST_DURATION_SECONDS`.
+const WEBHOOK_REQUEST_DURATION_BUCKETS: [f64; 12] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// Terminal states each task type can reach in the worker.
+const TASK_OUTCOMES: [(&str, &[&str]); 2] = [
+    ("webhook", &["done", "failed", "cancelled"]),
+    ("hash", &["done", "failed"]),
+];
+
+/// Label value used for all webhook target hosts beyond the first
+/// `max_hosts` distinct ones.
+const OTHER_HOST: &str = "other";
+
+/// Exports the metrics recorded by this scheduler in Prometheus format. To
+/// keep the number of exported series bounded, only the first `max_hosts`
+/// distinct webhook target hosts get their own `host` label value, while all
+/// further hosts are bucketed into `other`.
+#[derive(Debug)]
+pub(crate) struct Metrics {
+    handle: metrics_exporter_prometheus::PrometheusHandle,
+    max_hosts: usize,
+    hosts: std::sync::Mutex<std::collections::HashSet<String>>,
+}
+
+impl Metrics {
+    /// Installs the Prometheus recorder globally. Must only be called once.
+    pub(crate) fn install(
+        max_hosts: usize,
+    ) -> std::result::Result<Self, metrics_exporter_prometheus::BuildError> {
+        let handle = metrics_exporter_prometheus::PrometheusBuilder::new()
+            .set_buckets_for_metric(
+                metrics_exporter_prometheus::Matcher::Full(
+                    WEBHOOK_REQUEST_DURATION_SECONDS.to_string(),
+                ),
+                &WEBHOOK_REQUEST_DURATION_BUCKETS,
+            )?
+            .install_recorder()?;
+        metrics::describe_counter!(
+            TASK_OUTCOMES_TOTAL,
+            "Number of tasks that reached a terminal state in the worker."
+        );
+        metrics::describe_counter!(
+            TASKS_TOTAL,
+            "Number of tasks that reached a terminal state in the worker."
+        );
+        metrics::describe_histogram!(
+            WEBHOOK_REQUEST_DURATION_SECONDS,
+            metrics::Unit::Seconds,
+            "Duration of webhook requests until their response arrived."
+        );
+
+        // Register every series of the counter without host label right away, so
+        // that it is rendered at zero before the first task finished.
+        for (task_type, outcomes) in TASK_OUTCOMES {
+            for outcome in outcomes {
+                metrics::counter!(TASKS_TOTAL, "type" => task_type, "outcome" => *outcome)
+                    .absolute(0);
+            }
+        }
+
+        Ok(Metrics {
+            handle,
+            max_hosts,
+            hosts: std::sync::Mutex::new(std::collections::HashSet::new()),
+        })
+    }
+
+    /// Creates the metrics without installing their recorder globally, so that
+    /// any number of tests can create them.
+    #[cfg(test)]
+    pub(crate) fn unregistered(max_hosts: usize) -> Self {
+        Metrics {
+            handle: metrics_exporter_prometheus::PrometheusBuilder::new()
+                .build_recorder()
+                .handle(),
+            max_hosts,
+            hosts: std::sync::Mutex::new(std::collections::HashSet::new()),
+        }
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub(crate) fn render(&self) -> String {
+        self.handle.render()
+    }
+
+    /// Returns the `host` label value for webhook target `host`.
+    fn host_label(&self, host: &str) -> String {
+        let mut hosts = self
+            .hosts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if hosts.contains(host) {
+            return host.to_string();
+        }
+        if hosts.len() < self.max_hosts {
+            hosts.insert(host.to_string());
+            return host.to_string();
+        }
+        OTHER_HOST.to_string()
+    }
+
+    /// Records that a webhook task to target `host` reached terminal state
+    /// `outcome`.
+    pub(crate) fn webhook_finished(&self, host: &str, outcome: &'static str) {
+        metrics::counter!(
+            TASK_OUTCOMES_TOTAL,
+            "type" => "webhook",
+            "host" => self.host_label(host),
+            "outcome" => outcome,
+        )
+        .increment(1);
+        metrics::counter!(TASKS_TOTAL, "type" => "webhook", "outcome" => outcome).increment(1);
+    }
+
+    /// Records the `duration` of a webhook request until its response arrived.
+    #[allow(clippy::unused_self)]
+    pub(crate) fn webhook_request(&self, duration: std::time::Duration) {
+        metrics::histogram!(WEBHOOK_REQUEST_DURATION_SECONDS).record(duration.as_secs_f64());
+    }
+
+    /// Records that a hash task reached terminal state `outcome`. Hash tasks
+    /// don't have a host, but recording still requires the installed recorder.
+    #[allow(clippy::unused_self)]
+    pub(crate) fn hash_finished(&self, outcome: &'static str) {
+        metrics::counter!(
+            TASK_OUTCOMES_TOTAL,
+            "type" => "hash",
+            "outcome" => outcome,
+        )
+        .increment(1);
+        metrics::counter!(TASKS_TOTAL, "type" => "hash", "outcome" => outcome).increment(1);
+    }
+}
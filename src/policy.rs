@@ -0,0 +1,155 @@
+/// Number of times the request of a webhook task is retried if neither the task
+/// nor a policy of its tags specifies `max_retries`.
+pub(crate) const DEFAULT_MAX_RETRIES: u32 = 5;
+
+/// Maximum number of retries a webhook task or policy may specify.
+pub(crate) const MAX_MAX_RETRIES: u32 = 20;
+
+/// Time in milliseconds before the first retry of a webhook task's request, if
+/// neither the task nor a policy of its tags specifies `initial_backoff_ms`.
+pub(crate) const DEFAULT_INITIAL_BACKOFF_MS: u64 = 100;
+
+/// Maximum time in milliseconds before the first retry a webhook task or policy
+/// may specify.
+pub(crate) const MAX_INITIAL_BACKOFF_MS: u64 = 60_000;
+
+/// Time in milliseconds a single request of a webhook task may take, if neither
+/// the task nor a policy of its tags specifies `timeout_ms`.
+pub(crate) const DEFAULT_TIMEOUT_MS: u64 = 30_000;
+
+/// Maximum time in milliseconds a webhook task or policy may allow a single
+/// request of the task to take.
+pub(crate) const MAX_TIMEOUT_MS: u64 = 60 * 60 * 1000;
+
+/// Settings for delivering webhook tasks shared by all tasks carrying a tag
+/// mapped to the policy. Settings not specified by a policy fall back to the
+/// defaults.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub(crate) struct Policy {
+    pub(crate) max_retries: Option<u32>,
+    pub(crate) initial_backoff_ms: Option<u64>,
+    pub(crate) timeout_ms: Option<u64>,
+}
+
+impl Policy {
+    /// Makes sure the settings lie within the same bounds as those of single
+    /// tasks.
+    fn validate(&self) -> std::result::Result<(), String> {
+        if self.max_retries.is_some_and(|r| r > MAX_MAX_RETRIES) {
+            return Err(format!(
+                "field 'max_retries' must lie between 0 and {MAX_MAX_RETRIES}"
+            ));
+        }
+        if self
+            .initial_backoff_ms
+            .is_some_and(|b| !(1..=MAX_INITIAL_BACKOFF_MS).contains(&b))
+        {
+            return Err(format!(
+                "field 'initial_backoff_ms' must lie between 1 and {MAX_INITIAL_BACKOFF_MS}"
+            ));
+        }
+        if self
+            .timeout_ms
+            .is_some_and(|t| !(1..=MAX_TIMEOUT_MS).contains(&t))
+        {
+            return Err(format!(
+                "field 'timeout_ms' must lie between 1 and {MAX_TIMEOUT_MS}"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fills in the settings not specified by this policy from the `inherited`
+    /// one, or else the defaults, and makes sure the result is valid. A task's
+    /// own settings thereby override those inherited via its tags.
+    pub(crate) fn resolve(
+        self,
+        inherited: Option<&Policy>,
+    ) -> std::result::Result<ResolvedPolicy, String> {
+        let inherited = inherited.cloned().unwrap_or_default();
+        let policy = Policy {
+            max_retries: self.max_retries.or(inherited.max_retries),
+            initial_backoff_ms: self.initial_backoff_ms.or(inherited.initial_backoff_ms),
+            timeout_ms: self.timeout_ms.or(inherited.timeout_ms),
+        };
+        policy.validate()?;
+
+        Ok(ResolvedPolicy {
+            max_retries: policy.max_retries.unwrap_or(DEFAULT_MAX_RETRIES),
+            initial_backoff_ms: policy
+                .initial_backoff_ms
+                .unwrap_or(DEFAULT_INITIAL_BACKOFF_MS),
+            timeout_ms: policy.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS),
+        })
+    }
+}
+
+/// Settings for delivering a single webhook task, with defaults filled in.
+#[derive(Debug, Clone)]
+pub(crate) struct ResolvedPolicy {
+    pub(crate) max_retries: u32,
+    pub(crate) initial_backoff_ms: u64,
+    pub(crate) timeout_ms: u64,
+}
+
+/// Named policies and the tags mapped to them, loaded from the JSON file
+/// configured via CLI or ENV arguments, e.g.:
+///
+/// ```json
+/// {
+///     "policies": { "critical": { "max_retries": 20, "timeout_ms": 5000 } },
+///     "tags": { "payments": "critical" }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub(crate) struct TagPolicies {
+    #[serde(default)]
+    policies: std::collections::HashMap<String, Policy>,
+    #[serde(default)]
+    tags: std::collections::HashMap<String, String>,
+}
+
+impl TagPolicies {
+    /// Reads the policies from the JSON file at `path` and makes sure every
+    /// policy is valid and every tag is mapped to an existing policy.
+    pub(crate) fn load(path: &std::path::Path) -> std::result::Result<Self, String> {
+        let file = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read tag policies: {e}"))?;
+        let tag_policies = serde_json::from_str::<TagPolicies>(&file)
+            .map_err(|e| format!("failed to parse tag policies: {e}"))?;
+
+        for (name, policy) in &tag_policies.policies {
+            policy
+                .validate()
+                .map_err(|e| format!("tag policy '{name}' is invalid: {e}"))?;
+        }
+        // Tags of tasks are normalized upon creation, so map the normalized tags.
+        let mut tags = std::collections::HashMap::with_capacity(tag_policies.tags.len());
+        for (tag, name) in tag_policies.tags {
+            if !tag_policies.policies.contains_key(&name) {
+                return Err(format!(
+                    "tag '{tag}' is mapped to unknown tag policy '{name}'"
+                ));
+            }
+            let tag = crate::api::normalize_tag(&tag)
+                .map_err(|e| format!("tag '{tag}' of tag policies is invalid: {e}"))?;
+            if tags.insert(tag.clone(), name).is_some() {
+                return Err(format!("tag '{tag}' is mapped to more than one tag policy"));
+            }
+        }
+
+        Ok(TagPolicies {
+            policies: tag_policies.policies,
+            tags,
+        })
+    }
+
+    /// Returns the policy of the first of `tags` that is mapped to one.
+    pub(crate) fn for_tags(&self, tags: &[String]) -> Option<&Policy> {
+        tags.iter()
+            .find_map(|tag| self.tags.get(tag))
+            .and_then(|name| self.policies.get(name))
+    }
+}
@@ -0,0 +1,98 @@
+use tracing::{Level, event};
+
+/// Summary of the state `tasker` left behind upon shutdown, for post-mortems.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct ShutdownReport {
+    shutdown_at: String,
+    /// Number of tasks in each state, per task type. `None` if counting the
+    /// tasks in the database failed.
+    tasks: Option<std::collections::BTreeMap<String, std::collections::BTreeMap<String, i64>>>,
+    /// Number of handlers that were still working on a task when the worker
+    /// stopped, and finished before `tasker` exited.
+    handlers_awaited: usize,
+    /// Number of handlers that were still working on a task when `tasker`
+    /// exited. Their tasks are reset to `todo` upon the next start.
+    handlers_abandoned: usize,
+}
+
+impl ShutdownReport {
+    /// Collects the report from the database behind `db_pool`, given that
+    /// `in_flight_at_stop` handlers were working on a task when the worker
+    /// stopped, of which `task_limiter` reports the ones still working.
+    pub(crate) async fn collect(
+        db_pool: &crate::db::DbPool,
+        in_flight_at_stop: usize,
+        task_limiter: &crate::worker::TaskLimiter,
+    ) -> Self {
+        let tasks = match crate::db::count_task_states(db_pool).await {
+            Ok(counts) => {
+                // List all states of all task types, so that the report shows
+                // explicitly which states are empty.
+                let mut tasks = ["webhook", "hash"]
+                    .into_iter()
+                    .map(|task_type| {
+                        let states = crate::api::TASK_STATES
+                            .into_iter()
+                            .map(|state| (state.to_string(), 0))
+                            .collect();
+                        (task_type.to_string(), states)
+                    })
+                    .collect::<std::collections::BTreeMap<_, std::collections::BTreeMap<_, _>>>();
+                for (task_type, state, count) in counts {
+                    tasks.entry(task_type).or_default().insert(state, count);
+                }
+                Some(tasks)
+            }
+            Err(e) => {
+                event!(
+                    Level::WARN,
+                    "Counting tasks per state for shutdown report failed: {e}"
+                );
+                None
+            }
+        };
+
+        let handlers_abandoned = task_limiter.in_flight().min(in_flight_at_stop);
+        ShutdownReport {
+            shutdown_at: crate::db::timestamp(chrono::Utc::now()),
+            tasks,
+            handlers_awaited: in_flight_at_stop - handlers_abandoned,
+            handlers_abandoned,
+        }
+    }
+
+    /// Logs the report as a single line.
+    pub(crate) fn log(&self) {
+        let tasks = self.tasks.as_ref().map_or_else(
+            || "unknown".to_string(),
+            |tasks| {
+                tasks
+                    .iter()
+                    .map(|(task_type, states)| {
+                        let states = states
+                            .iter()
+                            .map(|(state, count)| format!("{count} {state}"))
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        format!("{task_type} ({states})")
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            },
+        );
+        event!(
+            Level::INFO,
+            "Shutdown report: tasks {tasks}; {} in-flight handlers awaited, {} abandoned",
+            self.handlers_awaited,
+            self.handlers_abandoned,
+        );
+    }
+
+    /// Writes the report as JSON to the file at `path`, replacing the file if
+    /// it exists.
+    pub(crate) fn write(&self, path: &std::path::Path) -> std::result::Result<(), std::io::Error> {
+        let report = serde_json::to_vec_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, report)
+    }
+}
@@ -0,0 +1,92 @@
+use opentelemetry_otlp::WithExportConfig;
+use tracing::{Level, event};
+
+/// Name under which traces are exported.
+const SERVICE_NAME: &str = "tasker";
+
+/// Shuts down the export of traces when dropped, flushing all spans that were
+/// not exported yet.
+pub(crate) struct TelemetryGuard(Option<opentelemetry_sdk::trace::SdkTracerProvider>);
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        let Some(tracer_provider) = self.0.take() else {
+            return;
+        };
+        if let Err(e) = tracer_provider.shutdown() {
+            event!(Level::WARN, "Shutting down trace export failed with: {e}");
+        }
+    }
+}
+
+impl TelemetryGuard {
+    /// Returns a guard for when traces aren't exported.
+    pub(crate) fn disabled() -> Self {
+        TelemetryGuard(None)
+    }
+}
+
+/// Sets up the export of traces via OTLP over HTTP to the collector at
+/// `endpoint`, which is the full URL of the collector's traces endpoint (e.g.
+/// `http://localhost:4318/v1/traces`). Returns the layer forwarding spans to
+/// the exporter, along with the guard shutting down the export. The W3C trace
+/// context propagator is installed, so that outgoing webhook requests carry
+/// the `traceparent` header of their span.
+pub(crate) fn init<S>(
+    endpoint: &str,
+) -> std::result::Result<
+    (
+        tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::SdkTracer>,
+        TelemetryGuard,
+    ),
+    opentelemetry_otlp::ExporterBuildError,
+>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()?;
+    let tracer_provider = opentelemetry_sdk::trace::SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            opentelemetry_sdk::Resource::builder()
+                .with_service_name(SERVICE_NAME)
+                .build(),
+        )
+        .build();
+    let tracer = opentelemetry::trace::TracerProvider::tracer(&tracer_provider, SERVICE_NAME);
+
+    opentelemetry::global::set_text_map_propagator(
+        opentelemetry_sdk::propagation::TraceContextPropagator::new(),
+    );
+
+    Ok((
+        tracing_opentelemetry::layer().with_tracer(tracer),
+        TelemetryGuard(Some(tracer_provider)),
+    ))
+}
+
+/// Inserts the fields of a propagated trace context as headers of a request.
+struct HeaderInjector<'a>(&'a mut reqwest::header::HeaderMap);
+
+impl opentelemetry::propagation::Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (
+            reqwest::header::HeaderName::from_bytes(key.as_bytes()),
+            reqwest::header::HeaderValue::from_str(&value),
+        ) {
+            self.0.insert(name, value);
+        }
+    }
+}
+
+/// Adds the trace context of the current span to `headers`, e.g. as header
+/// `traceparent`. Nothing is added if traces aren't exported.
+pub(crate) fn inject_trace_context(headers: &mut reqwest::header::HeaderMap) {
+    let context = tracing_opentelemetry::OpenTelemetrySpanExt::context(&tracing::Span::current());
+    opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(headers));
+    });
+}
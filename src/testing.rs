@@ -0,0 +1,301 @@
+use clap::Parser;
+
+/// Receiving half of the channel feeding tasks into the worker's `DelayQueue`.
+type RecvTask = tokio::sync::mpsc::Receiver<(tokio::time::Duration, crate::api::Task)>;
+
+/// Environment variable that, if set, holds the URL of a `PostgreSQL` database
+/// the tests store their tasks in instead of an in-memory `SQLite` database.
+const TEST_POSTGRES_URL_VAR: &str = "TASKER_TEST_POSTGRES_URL";
+
+/// The scheduler wired up as in `main`, serving the HTTP API on an ephemeral
+/// local port and storing tasks in an in-memory `SQLite` database that lives as
+/// long as the test. If `TASKER_TEST_POSTGRES_URL` is set, tasks are stored in
+/// a fresh schema of that `PostgreSQL` database instead.
+pub(crate) struct TestApp {
+    pub(crate) url: String,
+    pub(crate) db_pool: crate::db::DbPool,
+    /// Tasks handed to the `DelayQueue`, unless the worker was started and
+    /// receives them instead.
+    pub(crate) recv_task: Option<RecvTask>,
+    client: reqwest::Client,
+    // Initiates shutdown of the HTTP API and the worker upon dropping.
+    _send_shutdown: tokio::sync::broadcast::Sender<()>,
+}
+
+impl TestApp {
+    /// Starts the HTTP API and the worker, configured via the command line
+    /// arguments `args` in addition to the in-memory database.
+    pub(crate) async fn start(args: &[&str]) -> Self {
+        Self::launch(args, true).await
+    }
+
+    /// Starts the HTTP API only, configured as in `start`. Tasks the API hands
+    /// to the `DelayQueue` remain in `recv_task`.
+    pub(crate) async fn without_worker(args: &[&str]) -> Self {
+        Self::launch(args, false).await
+    }
+
+    #[allow(clippy::too_many_lines)]
+    async fn launch(args: &[&str], with_worker: bool) -> Self {
+        let database_url = test_database_url().await;
+        let args = crate::Args::try_parse_from(
+            ["tasker", "--database-url", &database_url]
+                .into_iter()
+                .chain(args.iter().copied()),
+        )
+        .expect("test arguments are valid");
+
+        let host_limiter = std::sync::Arc::new(crate::worker::HostLimiter::new(
+            args.max_connections_per_host,
+            crate::parse_host_connection_limits(&args.host_connection_limit)
+                .expect("host limits are valid"),
+        ));
+        let task_limiter = std::sync::Arc::new(crate::worker::TaskLimiter::new(
+            crate::max_concurrent_tasks(&args).expect("concurrency limit is valid"),
+        ));
+        let throughput = std::sync::Arc::new(crate::worker::Throughput::new(
+            tokio::time::Duration::from_mins(5),
+        ));
+        let cancel_registry = std::sync::Arc::new(crate::worker::CancelRegistry::default());
+        let scheduling_error = std::sync::Arc::new(crate::worker::SchedulingError::default());
+        let worker_alive = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
+        let metrics = std::sync::Arc::new(crate::metrics::Metrics::unregistered(
+            args.metrics_max_hosts,
+        ));
+        let worker_options = crate::worker_options(&args).expect("worker options are valid");
+        let outbound = crate::outbound_queue(&args).expect("outbound limits are valid");
+        let create_rate_limits =
+            crate::create_rate_limits(&args).expect("creation rate limits are valid");
+        let api_config = crate::api_config(&args).expect("API configuration is valid");
+        let idempotency_key_header =
+            crate::idempotency_key_header(&args).expect("idempotency key header is valid");
+        let webhook_client = crate::webhook_client(&args, worker_options.egress_policy.as_ref())
+            .expect("webhook client builds");
+        let hasher = std::sync::Arc::new(crate::hashing::Hasher::new(
+            api_config.hash_limits.clone(),
+            args.argon2_memory_budget_kib,
+        ));
+
+        let db_pool = crate::db::init_open_db(&args.database_url)
+            .await
+            .expect("test database opens");
+
+        let (send_shutdown, _) = tokio::sync::broadcast::channel::<()>(1);
+        let (send_task, recv_task) =
+            tokio::sync::mpsc::channel::<(tokio::time::Duration, crate::api::Task)>(256);
+        let (send_cmd, recv_cmd) = tokio::sync::mpsc::channel::<crate::worker::WorkerCmd>(256);
+
+        let recv_task = if with_worker {
+            let worker_ctx = crate::worker::WorkerCtx::new(
+                db_pool.clone(),
+                send_task.clone(),
+                host_limiter.clone(),
+                task_limiter.clone(),
+                throughput.clone(),
+                scheduling_error.clone(),
+                cancel_registry.clone(),
+                worker_alive.clone(),
+                worker_options,
+                api_config.business_calendar.clone(),
+                metrics.clone(),
+                hasher,
+                idempotency_key_header,
+                webhook_client,
+                outbound.clone(),
+            );
+            tokio::task::spawn(worker_ctx.run(send_shutdown.subscribe(), recv_task, recv_cmd));
+            None
+        } else {
+            Some(recv_task)
+        };
+
+        let api_ctx = crate::api::ApiCtx::new(
+            db_pool.clone(),
+            send_task,
+            send_cmd,
+            host_limiter,
+            task_limiter.clone(),
+            outbound,
+            throughput,
+            scheduling_error,
+            cancel_registry,
+            worker_alive,
+            metrics,
+            None,
+            std::sync::Arc::new(create_rate_limits),
+            api_config,
+        );
+        let router = crate::build_router(api_ctx, args.max_body_bytes);
+
+        let api_sock = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("ephemeral port is available");
+        let url = format!("http://{}", api_sock.local_addr().expect("socket is bound"));
+        let mut recv_shutdown = send_shutdown.subscribe();
+        tokio::task::spawn(async move {
+            axum::serve(api_sock, router)
+                .with_graceful_shutdown(async move {
+                    let _ = recv_shutdown.recv().await;
+                })
+                .await
+        });
+
+        TestApp {
+            url,
+            db_pool,
+            recv_task,
+            client: reqwest::Client::new(),
+            _send_shutdown: send_shutdown,
+        }
+    }
+
+    /// Runs the raw SQL statement `sql` on the database of this app.
+    pub(crate) async fn execute(&self, sql: &str) {
+        match &self.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query(sql)
+                    .execute(db_pool)
+                    .await
+                    .expect("statement runs");
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query(sql)
+                    .execute(db_pool)
+                    .await
+                    .expect("statement runs");
+            }
+        }
+    }
+
+    /// Prepares a request with `method` to `path` of the HTTP API.
+    pub(crate) fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.client.request(method, format!("{}{path}", self.url))
+    }
+
+    /// Sends a `GET` request to `path` of the HTTP API, see `send`.
+    pub(crate) async fn get(&self, path: &str) -> (reqwest::StatusCode, serde_json::Value) {
+        send(self.request(reqwest::Method::GET, path)).await
+    }
+
+    /// Sends a `POST` request with JSON `body` to `path` of the HTTP API, see
+    /// `send`.
+    pub(crate) async fn post(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+    ) -> (reqwest::StatusCode, serde_json::Value) {
+        send(json_request(
+            self.request(reqwest::Method::POST, path),
+            body,
+        ))
+        .await
+    }
+
+    /// Waits until the task with `id` reached `state`, and returns its details
+    /// as responded by `GET /tasks/{id}`. Panics if it didn't within `timeout`.
+    pub(crate) async fn wait_for_state(
+        &self,
+        id: &str,
+        state: &str,
+        timeout: std::time::Duration,
+    ) -> serde_json::Value {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let (_, task) = self.get(&format!("/tasks/{id}")).await;
+            let details = task
+                .as_object()
+                .and_then(|t| t.values().next())
+                .cloned()
+                .unwrap_or_default();
+            if details["state"] == state {
+                return details;
+            }
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "task {id} didn't reach state '{state}' in time: {task}"
+            );
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+    }
+
+    /// Waits until the task with `id` was moved to the dead letters, and returns
+    /// its dead letter as responded by `GET /tasks/dead-letters`. Panics if it
+    /// wasn't within `timeout`.
+    pub(crate) async fn wait_for_dead_letter(
+        &self,
+        id: &str,
+        timeout: std::time::Duration,
+    ) -> serde_json::Value {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let (_, dead_letters) = self.get("/tasks/dead-letters").await;
+            if let Some(dead_letter) = dead_letters
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|d| d["id"] == id)
+            {
+                return dead_letter.clone();
+            }
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "task {id} wasn't moved to the dead letters in time: {dead_letters}"
+            );
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+    }
+}
+
+/// Returns the URL of the database a test stores its tasks in. With a
+/// `PostgreSQL` database configured via `TASKER_TEST_POSTGRES_URL`, each test
+/// gets a schema of its own, so that tests running in parallel don't see each
+/// other's tasks.
+async fn test_database_url() -> String {
+    let Ok(server_url) = std::env::var(TEST_POSTGRES_URL_VAR) else {
+        return "sqlite::memory:".to_string();
+    };
+
+    let schema = format!("test_{}", uuid::Uuid::now_v7().simple());
+    let mut conn = <sqlx::postgres::PgConnection as sqlx::Connection>::connect(&server_url)
+        .await
+        .expect("test database server is reachable");
+    sqlx::query(&format!("CREATE SCHEMA {schema};"))
+        .execute(&mut conn)
+        .await
+        .expect("test schema is created");
+
+    let separator = if server_url.contains('?') { '&' } else { '?' };
+    format!("{server_url}{separator}options=-c%20search_path%3D{schema}")
+}
+
+/// Attaches the JSON `body` to `request`.
+pub(crate) fn json_request(
+    request: reqwest::RequestBuilder,
+    body: &serde_json::Value,
+) -> reqwest::RequestBuilder {
+    request
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body.to_string())
+}
+
+/// Sends `request` and returns the status and JSON body of its response. The
+/// body is `null` if it isn't valid JSON.
+pub(crate) async fn send(
+    request: reqwest::RequestBuilder,
+) -> (reqwest::StatusCode, serde_json::Value) {
+    let resp = request.send().await.expect("HTTP API responds");
+    let status = resp.status();
+    let body = resp.bytes().await.expect("response body arrives");
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+/// Serves `router` as the target of webhook tasks on an ephemeral local port,
+/// and returns its base URL.
+pub(crate) async fn mock_target(router: axum::Router) -> String {
+    let sock = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("ephemeral port is available");
+    let url = format!("http://{}", sock.local_addr().expect("socket is bound"));
+    tokio::task::spawn(async move { axum::serve(sock, router).await });
+    url
+}
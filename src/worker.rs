@@ -1,14 +1,689 @@
 use base64::prelude::*;
-use futures_util::StreamExt;
-use pbkdf2::password_hash::PasswordHasher;
-use tracing::{Level, event};
+use futures_util::{FutureExt, StreamExt};
+use tracing::{Instrument, Level, event};
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct HostStatus {
+    limit: Option<usize>,
+    in_flight: usize,
+    queued: usize,
+}
+
+#[derive(Debug)]
+struct HostSlot {
+    semaphore: Option<std::sync::Arc<tokio::sync::Semaphore>>,
+    status: HostStatus,
+}
+
+/// Limits the number of concurrent webhook requests per target host. The limit
+/// of a host is taken from `host_limits` if configured there, and otherwise
+/// from `default_limit`. A limit of `None` means unlimited. Slots are created
+/// when the first webhook for a host arrives and removed once no webhook for
+/// that host is in flight or queued anymore, which keeps track of the number of
+/// in-flight and queued webhooks per host for visibility.
+#[derive(Debug)]
+pub(crate) struct HostLimiter {
+    default_limit: Option<usize>,
+    host_limits: std::collections::HashMap<String, usize>,
+    slots: std::sync::Mutex<std::collections::HashMap<String, HostSlot>>,
+}
+
+/// Marks one webhook request to `host` as in flight for as long as it lives.
+struct HostPermit {
+    limiter: std::sync::Arc<HostLimiter>,
+    host: String,
+    _permit: Option<tokio::sync::OwnedSemaphorePermit>,
+}
+
+impl Drop for HostPermit {
+    fn drop(&mut self) {
+        let mut slots = self
+            .limiter
+            .slots
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(slot) = slots.get_mut(&self.host) {
+            slot.status.in_flight -= 1;
+            if slot.status.in_flight == 0 && slot.status.queued == 0 {
+                slots.remove(&self.host);
+            }
+        }
+    }
+}
+
+/// Marks one webhook request to `host` as queued for a slot for as long as it
+/// lives, so that a request abandoned while waiting, e.g. because its task was
+/// cancelled, is no longer counted as queued.
+struct HostQueued<'a> {
+    limiter: &'a HostLimiter,
+    host: &'a str,
+}
+
+impl HostQueued<'_> {
+    /// Moves the request from queued to in flight.
+    fn admit(self) {
+        let mut slots = self
+            .limiter
+            .slots
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(slot) = slots.get_mut(self.host) {
+            slot.status.queued -= 1;
+            slot.status.in_flight += 1;
+        }
+        drop(slots);
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for HostQueued<'_> {
+    fn drop(&mut self) {
+        let mut slots = self
+            .limiter
+            .slots
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(slot) = slots.get_mut(self.host) {
+            slot.status.queued -= 1;
+            if slot.status.in_flight == 0 && slot.status.queued == 0 {
+                slots.remove(self.host);
+            }
+        }
+    }
+}
+
+impl HostLimiter {
+    pub(crate) fn new(
+        default_limit: Option<usize>,
+        host_limits: std::collections::HashMap<String, usize>,
+    ) -> Self {
+        HostLimiter {
+            default_limit,
+            host_limits,
+            slots: std::sync::Mutex::new(std::collections::HashMap::new()),
+        }
+    }
+
+    /// Waits until a webhook request to `host` may be sent without exceeding
+    /// the host's limit. The returned permit releases the slot when dropped.
+    async fn acquire(self: &std::sync::Arc<Self>, host: &str) -> HostPermit {
+        let semaphore = {
+            let mut slots = self
+                .slots
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            let slot = slots.entry(host.to_string()).or_insert_with(|| {
+                let limit = self.host_limits.get(host).copied().or(self.default_limit);
+                HostSlot {
+                    semaphore: limit.map(|l| std::sync::Arc::new(tokio::sync::Semaphore::new(l))),
+                    status: HostStatus {
+                        limit,
+                        in_flight: 0,
+                        queued: 0,
+                    },
+                }
+            });
+            slot.status.queued += 1;
+            slot.semaphore.clone()
+        };
+        let queued = HostQueued {
+            limiter: self,
+            host,
+        };
+
+        // The semaphore is never closed, thus acquiring only fails in theory.
+        let permit = match semaphore {
+            Some(s) => s.acquire_owned().await.ok(),
+            None => None,
+        };
+        queued.admit();
+
+        HostPermit {
+            limiter: self.clone(),
+            host: host.to_string(),
+            _permit: permit,
+        }
+    }
+
+    /// Returns the current number of in-flight and queued webhook requests for
+    /// each host that has at least one of either.
+    pub(crate) fn status(&self) -> std::collections::BTreeMap<String, HostStatus> {
+        let slots = self
+            .slots
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        slots
+            .iter()
+            .map(|(host, slot)| (host.clone(), slot.status.clone()))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct TaskLimitStatus {
+    limit: usize,
+    in_flight: usize,
+    available: usize,
+}
+
+/// The limit of a `TaskLimiter` along with the number of permits still to be
+/// retired after the limit was lowered while more tasks were in flight than
+/// permits were available.
+#[derive(Debug)]
+struct TaskLimitState {
+    limit: usize,
+    debt: usize,
+}
+
+/// Limits the number of tasks of any type the worker handles concurrently to
+/// `limit`, so that a burst of ready tasks doesn't spawn an unbounded number of
+/// handlers at once. Handlers beyond the limit wait for a free permit. The
+/// limit can be adjusted at runtime.
+#[derive(Debug)]
+pub(crate) struct TaskLimiter {
+    state: std::sync::Arc<std::sync::Mutex<TaskLimitState>>,
+    semaphore: std::sync::Arc<tokio::sync::Semaphore>,
+}
+
+/// A slot among the tasks the worker handles concurrently, released when
+/// dropped. If the limit was lowered in the meantime, the slot is retired
+/// instead of being released.
+#[derive(Debug)]
+pub(crate) struct TaskPermit {
+    state: std::sync::Arc<std::sync::Mutex<TaskLimitState>>,
+    permit: Option<tokio::sync::OwnedSemaphorePermit>,
+}
+
+impl Drop for TaskPermit {
+    fn drop(&mut self) {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if state.debt > 0
+            && let Some(permit) = self.permit.take()
+        {
+            state.debt -= 1;
+            permit.forget();
+        }
+    }
+}
+
+impl TaskLimiter {
+    pub(crate) fn new(limit: usize) -> Self {
+        TaskLimiter {
+            state: std::sync::Arc::new(std::sync::Mutex::new(TaskLimitState { limit, debt: 0 })),
+            semaphore: std::sync::Arc::new(tokio::sync::Semaphore::new(limit)),
+        }
+    }
+
+    /// Waits until another task may be handled without exceeding the limit. The
+    /// returned permit releases the slot when dropped, or `None` is returned if
+    /// the semaphore was closed, which never happens.
+    async fn acquire(&self) -> Option<TaskPermit> {
+        let permit = self.semaphore.clone().acquire_owned().await.ok()?;
+        Some(TaskPermit {
+            state: self.state.clone(),
+            permit: Some(permit),
+        })
+    }
+
+    /// Changes the limit to `limit`. Raising the limit lets waiting handlers
+    /// start right away. Lowering it retires free slots right away and the
+    /// remaining ones as tasks in flight finish, so tasks in flight are never
+    /// interrupted.
+    pub(crate) fn set_limit(&self, limit: usize) {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if limit > state.limit {
+            let added = limit - state.limit;
+            let repaid = added.min(state.debt);
+            state.debt -= repaid;
+            self.semaphore.add_permits(added - repaid);
+        } else {
+            let removed = state.limit - limit;
+            let forgotten = self.semaphore.forget_permits(removed);
+            state.debt += removed - forgotten;
+        }
+        state.limit = limit;
+    }
+
+    /// Returns the number of tasks currently being handled.
+    pub(crate) fn in_flight(&self) -> usize {
+        let state = self
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        state.limit + state.debt - self.semaphore.available_permits()
+    }
+
+    pub(crate) fn status(&self) -> TaskLimitStatus {
+        let state = self
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let available = self.semaphore.available_permits();
+        TaskLimitStatus {
+            limit: state.limit,
+            in_flight: state.limit + state.debt - available,
+            available,
+        }
+    }
+}
+
+/// Time after which a ready webhook task that didn't fit into the full outbound
+/// queue is yielded by the delay queue again.
+const OUTBOUND_DEFER_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Maximum number of tasks the delay queue yields at the same time that are
+/// buffered to be ordered by priority, before the remaining ones are left to
+/// the next round.
+const MAX_READY_BUFFER: usize = 1024;
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct OutboundStatus {
+    capacity: usize,
+    depth: usize,
+    senders: usize,
+}
+
+/// Bounded queue of ready webhook tasks, separate from the delay queue, which a
+/// fixed pool of `senders` drains. This decouples scheduling from delivery:
+/// webhook tasks are delivered no faster than the senders manage, and ready
+/// tasks that don't fit into the full queue are deferred in the delay queue
+/// instead of piling up.
+#[derive(Debug)]
+pub(crate) struct OutboundQueue {
+    send: tokio::sync::mpsc::Sender<WorkerWebhook>,
+    recv: tokio::sync::Mutex<tokio::sync::mpsc::Receiver<WorkerWebhook>>,
+    capacity: usize,
+    senders: usize,
+}
+
+impl OutboundQueue {
+    pub(crate) fn new(capacity: usize, senders: usize) -> Self {
+        let (send, recv) = tokio::sync::mpsc::channel(capacity);
+        OutboundQueue {
+            send,
+            recv: tokio::sync::Mutex::new(recv),
+            capacity,
+            senders,
+        }
+    }
+
+    /// Returns the number of webhook tasks waiting in the queue for a sender.
+    pub(crate) fn status(&self) -> OutboundStatus {
+        OutboundStatus {
+            capacity: self.capacity,
+            depth: self.capacity - self.send.capacity(),
+            senders: self.senders,
+        }
+    }
+}
+
+/// Keeps track of the instants at which the worker finished handling a task
+/// (either successfully or not) within the most recent `window`, in order to
+/// derive the worker's recent throughput.
+#[derive(Debug)]
+pub(crate) struct Throughput {
+    window: std::time::Duration,
+    started: std::time::Instant,
+    completions: std::sync::Mutex<std::collections::VecDeque<std::time::Instant>>,
+}
+
+impl Throughput {
+    pub(crate) fn new(window: std::time::Duration) -> Self {
+        Throughput {
+            window,
+            started: std::time::Instant::now(),
+            completions: std::sync::Mutex::new(std::collections::VecDeque::new()),
+        }
+    }
+
+    /// Drops all completions that lie further in the past than `window`.
+    fn prune(
+        &self,
+        completions: &mut std::collections::VecDeque<std::time::Instant>,
+        now: std::time::Instant,
+    ) {
+        while completions
+            .front()
+            .is_some_and(|c| now.duration_since(*c) > self.window)
+        {
+            completions.pop_front();
+        }
+    }
+
+    /// Records that the worker finished handling a task just now.
+    fn record(&self) {
+        let now = std::time::Instant::now();
+        let mut completions = self
+            .completions
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        completions.push_back(now);
+        self.prune(&mut completions, now);
+    }
+
+    /// Returns the length of the window over which throughput is measured.
+    pub(crate) fn window(&self) -> std::time::Duration {
+        self.window
+    }
+
+    /// Returns the number of tasks per second the worker finished handling
+    /// within the most recent `window`, or since start if that was more recent.
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn per_sec(&self) -> f64 {
+        let now = std::time::Instant::now();
+        let mut completions = self
+            .completions
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        self.prune(&mut completions, now);
+
+        let observed = now.duration_since(self.started).min(self.window);
+        if observed.is_zero() {
+            return 0.0;
+        }
+        completions.len() as f64 / observed.as_secs_f64()
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct SchedulingErrorStatus {
+    samples: u64,
+    mean_ms: Option<f64>,
+    max_ms: Option<f64>,
+    last_ms: Option<f64>,
+}
+
+#[derive(Debug, Default)]
+struct SchedulingErrorTotals {
+    samples: u64,
+    total: std::time::Duration,
+    max: std::time::Duration,
+    last: std::time::Duration,
+}
+
+/// Keeps track of the scheduling error of the `DelayQueue`, i.e., the time
+/// between the deadline at which a task was intended to be yielded and the
+/// time it actually was. The queue's timer wheel has a fixed granularity of one
+/// millisecond, which tokio doesn't allow configuring, and the worker's load
+/// adds to the error on top of that.
+#[derive(Debug, Default)]
+pub(crate) struct SchedulingError {
+    totals: std::sync::Mutex<SchedulingErrorTotals>,
+}
+
+impl SchedulingError {
+    /// Records that a task intended to be yielded at `deadline` was yielded
+    /// just now.
+    fn record(&self, deadline: tokio::time::Instant) {
+        let error = tokio::time::Instant::now().saturating_duration_since(deadline);
+        let mut totals = self
+            .totals
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        totals.samples += 1;
+        totals.total += error;
+        totals.max = totals.max.max(error);
+        totals.last = error;
+    }
+
+    /// Returns the mean, maximum, and most recent scheduling error observed
+    /// since start, in milliseconds.
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn status(&self) -> SchedulingErrorStatus {
+        let totals = self
+            .totals
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if totals.samples == 0 {
+            return SchedulingErrorStatus::default();
+        }
+        let to_ms = |d: std::time::Duration| d.as_secs_f64() * 1_000.0;
+        SchedulingErrorStatus {
+            samples: totals.samples,
+            mean_ms: Some(to_ms(totals.total) / totals.samples as f64),
+            max_ms: Some(to_ms(totals.max)),
+            last_ms: Some(to_ms(totals.last)),
+        }
+    }
+}
+
+/// Keeps a `CancellationToken` for each webhook task currently being handled,
+/// which allows aborting the task's in-flight request when it gets cancelled.
+/// Tokens are reference-counted per task ID, so that a task accidentally being
+/// handled twice concurrently shares one token.
+#[derive(Debug, Default)]
+pub(crate) struct CancelRegistry {
+    tokens: std::sync::Mutex<
+        std::collections::HashMap<String, (tokio_util::sync::CancellationToken, usize)>,
+    >,
+}
+
+/// Keeps the cancellation token of a task registered for as long as it lives.
+struct CancelGuard {
+    registry: std::sync::Arc<CancelRegistry>,
+    id: String,
+    token: tokio_util::sync::CancellationToken,
+}
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        let mut tokens = self
+            .registry
+            .tokens
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some((_, refs)) = tokens.get_mut(&self.id) {
+            *refs -= 1;
+            if *refs == 0 {
+                tokens.remove(&self.id);
+            }
+        }
+    }
+}
+
+impl CancelRegistry {
+    /// Registers a cancellation token for the task with ID `id`.
+    fn register(self: &std::sync::Arc<Self>, id: &str) -> CancelGuard {
+        let mut tokens = self
+            .tokens
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let (token, refs) = tokens
+            .entry(id.to_string())
+            .or_insert_with(|| (tokio_util::sync::CancellationToken::new(), 0));
+        *refs += 1;
+
+        CancelGuard {
+            registry: self.clone(),
+            id: id.to_string(),
+            token: token.clone(),
+        }
+    }
+
+    /// Triggers the cancellation token of the task with ID `id`. Returns whether
+    /// the task is currently being handled and thus had a token to trigger.
+    pub(crate) fn cancel(&self, id: &str) -> bool {
+        let tokens = self
+            .tokens
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        match tokens.get(id) {
+            Some((token, _)) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns whether the task with ID `id` is currently being handled.
+    pub(crate) fn is_registered(&self, id: &str) -> bool {
+        self.tokens
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .contains_key(id)
+    }
+}
+
+/// Serializes the handling of webhook tasks sharing a shard key, so that such
+/// tasks are delivered one after another, in the order in which they were
+/// handed to their handlers, while tasks with other or no shard keys proceed
+/// in parallel. For each key, only the turn of the task handed over last is
+/// kept, which each newly handed over task waits for. Keys are removed once
+/// their last task finished.
+#[derive(Debug, Default)]
+struct ShardLocks {
+    tails:
+        std::sync::Mutex<std::collections::HashMap<String, std::sync::Arc<tokio::sync::Mutex<()>>>>,
+}
+
+/// The turn of one webhook task among the tasks sharing its shard key. The
+/// next task with the same key may proceed once this turn is dropped.
+struct ShardTurn {
+    locks: std::sync::Arc<ShardLocks>,
+    key: String,
+    prev: Option<std::sync::Arc<tokio::sync::Mutex<()>>>,
+    own: std::sync::Arc<tokio::sync::Mutex<()>>,
+    _guard: tokio::sync::OwnedMutexGuard<()>,
+}
+
+impl Drop for ShardTurn {
+    fn drop(&mut self) {
+        let mut tails = self
+            .locks
+            .tails
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if tails
+            .get(&self.key)
+            .is_some_and(|tail| std::sync::Arc::ptr_eq(tail, &self.own))
+        {
+            tails.remove(&self.key);
+        }
+    }
+}
+
+impl ShardTurn {
+    /// Waits until all tasks with the same shard key handed over before this
+    /// one finished.
+    async fn wait(&mut self) {
+        if let Some(prev) = self.prev.take() {
+            drop(prev.lock().await);
+        }
+    }
+}
+
+impl ShardLocks {
+    /// Hands out the next turn among the tasks with shard key `key`.
+    fn enqueue(self: &std::sync::Arc<Self>, key: &str) -> ShardTurn {
+        let own = std::sync::Arc::new(tokio::sync::Mutex::new(()));
+        let guard = own
+            .clone()
+            .try_lock_owned()
+            .unwrap_or_else(|_| unreachable!("a new lock is never held"));
+        let prev = self
+            .tails
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(key.to_string(), own.clone());
+
+        ShardTurn {
+            locks: self.clone(),
+            key: key.to_string(),
+            prev,
+            own,
+            _guard: guard,
+        }
+    }
+}
+
+/// Returns the first time after `after` at which the cron expression `cron`
+/// matches. Expressions consist of six or seven fields, starting with seconds
+/// and optionally ending with years, and are evaluated in UTC. If `calendar`
+/// is set, a time not falling on one of its business days is rolled forward to
+/// the next business day.
+pub(crate) fn next_cron_run(
+    cron: &str,
+    calendar: Option<&crate::calendar::BusinessCalendar>,
+    after: chrono::DateTime<chrono::Utc>,
+) -> std::result::Result<chrono::DateTime<chrono::Utc>, String> {
+    // Parse errors point at the offending part of the expression on separate
+    // lines, only keep the final line explaining the error.
+    let schedule = <cron::Schedule as std::str::FromStr>::from_str(cron).map_err(|e| {
+        format!(
+            "field 'cron' is not a valid cron expression: {}",
+            e.to_string().lines().last().unwrap_or_default()
+        )
+    })?;
+    let next_run = schedule
+        .after(&after)
+        .next()
+        .ok_or_else(|| "field 'cron' doesn't match any time in the future".to_string())?;
+    match calendar {
+        Some(calendar) => calendar.roll_forward(next_run),
+        None => Ok(next_run),
+    }
+}
+
+/// Longest time to back off between two retries of a webhook task's request.
+const MAX_WEBHOOK_BACKOFF: std::time::Duration = std::time::Duration::from_mins(10);
+
+/// Returns the time to wait before retrying a webhook task's request given the
+/// current `backoff`: with `jitter`, a random time between zero and `backoff`
+/// ("full jitter"), and exactly `backoff` otherwise.
+fn retry_delay(backoff: std::time::Duration, jitter: bool) -> std::time::Duration {
+    if !jitter {
+        return backoff;
+    }
+    let max_micros = u64::try_from(backoff.as_micros()).unwrap_or(u64::MAX);
+    std::time::Duration::from_micros(rand::Rng::gen_range(
+        &mut rand::thread_rng(),
+        0..=max_micros,
+    ))
+}
+
+/// Turns the stored custom headers of a webhook task into the headers to send
+/// along with its requests. Stored headers were validated upon task creation.
+fn webhook_headers(headers: Option<&crate::api::WebhookHeaders>) -> reqwest::header::HeaderMap {
+    headers
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, value)| {
+            Some((
+                reqwest::header::HeaderName::from_bytes(name.as_bytes()).ok()?,
+                reqwest::header::HeaderValue::from_str(value).ok()?,
+            ))
+        })
+        .collect()
+}
 
 #[derive(Debug, Clone)]
 struct WorkerWebhook {
     id: String,
     execution_time: String,
+    method: reqwest::Method,
     url: String,
     body: String,
+    idempotency_key: Option<String>,
+    cron: Option<String>,
+    calendar: Option<String>,
+    priority: i64,
+    max_retries: usize,
+    initial_backoff: std::time::Duration,
+    timeout: std::time::Duration,
+    max_latency: Option<std::time::Duration>,
+    signing_secret: Option<String>,
+    headers: reqwest::header::HeaderMap,
 }
 
 #[derive(Debug, Clone)]
@@ -16,13 +691,189 @@ struct WorkerHash {
     id: String,
     execution_time: String,
     secret: String,
+    algorithm: String,
+    params: serde_json::Value,
+}
+
+/// Longest delay with which a task is inserted into the delay queue, as the
+/// queue panics for deadlines more than about two years in the future. Tasks
+/// due later are queued again once this delay elapsed.
+const MAX_QUEUE_DELAY: std::time::Duration = std::time::Duration::from_hours(24 * 365);
+
+/// Tasks waiting for their execution times. By default, all tasks are kept in
+/// a single `DelayQueue`. If a near-term horizon is configured, only tasks due
+/// within the horizon are kept in the `DelayQueue`, which the worker polls
+/// continuously, while tasks due later are kept in a far-future bucket ordered
+/// by deadline. The far-future bucket is only checked every half horizon, at
+/// which point the tasks becoming due within the horizon are moved into the
+/// `DelayQueue`, so that each task is in the `DelayQueue` at least half a
+/// horizon before it is due. This keeps the `DelayQueue` small even if
+/// millions of tasks are scheduled far into the future.
+struct TaskQueue {
+    delay_queue: tokio_util::time::DelayQueue<crate::api::Task>,
+    /// Delay queue key of each task in `delay_queue`, so that tasks can be
+    /// removed from the queue by ID. As keys are reused by the delay queue once
+    /// their entry expired or was removed, this map always has to mirror the
+    /// queue's current entries exactly.
+    keys: std::collections::HashMap<String, tokio_util::time::delay_queue::Key>,
+    near_term_horizon: Option<std::time::Duration>,
+    /// Tasks due beyond the near-term horizon, by deadline and ID.
+    far: std::collections::BTreeMap<(tokio::time::Instant, String), crate::api::Task>,
+    /// Deadline of each task in `far`.
+    far_deadlines: std::collections::HashMap<String, tokio::time::Instant>,
+}
+
+impl TaskQueue {
+    fn new(near_term_horizon: Option<std::time::Duration>) -> Self {
+        TaskQueue {
+            delay_queue: tokio_util::time::DelayQueue::new(),
+            keys: std::collections::HashMap::new(),
+            near_term_horizon,
+            far: std::collections::BTreeMap::new(),
+            far_deadlines: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Queues `task` to become due after `delay`, replacing any previous entry
+    /// of the task, so that it is only yielded once, at the most recently
+    /// requested time.
+    fn insert(&mut self, task: crate::api::Task, delay: std::time::Duration) {
+        let id = task.id().to_string();
+        self.remove(&id);
+        let delay = delay.min(MAX_QUEUE_DELAY);
+        match self.near_term_horizon {
+            Some(horizon) if delay > horizon => {
+                let deadline = tokio::time::Instant::now() + delay;
+                self.far_deadlines.insert(id.clone(), deadline);
+                self.far.insert((deadline, id), task);
+            }
+            _ => {
+                self.keys.insert(id, self.delay_queue.insert(task, delay));
+            }
+        }
+    }
+
+    /// Removes the task with ID `id` from the queue, if it is queued.
+    fn remove(&mut self, id: &str) {
+        if let Some(key) = self.keys.remove(id) {
+            self.delay_queue.try_remove(&key);
+        }
+        if let Some(deadline) = self.far_deadlines.remove(id) {
+            self.far.remove(&(deadline, id.to_string()));
+        }
+    }
+
+    /// Forgets the delay queue key of a task the delay queue just yielded, whose
+    /// key may be reused for other tasks from now on.
+    fn forget_yielded(&mut self, id: &str) {
+        self.keys.remove(id);
+    }
+
+    /// Removes all tasks from the queue. Returns the number of removed tasks.
+    fn clear(&mut self) -> usize {
+        let cleared = self.keys.len() + self.far.len();
+        self.delay_queue.clear();
+        self.keys.clear();
+        self.far.clear();
+        self.far_deadlines.clear();
+        cleared
+    }
+
+    /// Returns the time at which the task with ID `id` becomes due, if queued.
+    fn deadline(&self, id: &str) -> Option<tokio::time::Instant> {
+        self.keys
+            .get(id)
+            .map(|key| self.delay_queue.deadline(key))
+            .or_else(|| self.far_deadlines.get(id).copied())
+    }
+
+    /// Moves the tasks of the far-future bucket that become due within the
+    /// near-term horizon into the delay queue.
+    fn promote_near_term(&mut self) {
+        let Some(horizon) = self.near_term_horizon else {
+            return;
+        };
+        let until = tokio::time::Instant::now() + horizon;
+        let mut promoted = 0_usize;
+        while let Some(entry) = self.far.first_entry() {
+            if entry.key().0 > until {
+                break;
+            }
+            let ((deadline, id), task) = entry.remove_entry();
+            self.far_deadlines.remove(&id);
+            self.keys
+                .insert(id, self.delay_queue.insert_at(task, deadline));
+            promoted += 1;
+        }
+        if promoted > 0 {
+            event!(
+                Level::DEBUG,
+                "Moved {promoted} tasks becoming due soon into delay queue, {} remain far-future",
+                self.far.len(),
+            );
+        }
+    }
+}
+
+/// Returns the delay with which a task yielded by the delay queue needs to be
+/// queued again, because its execution time lay beyond `MAX_QUEUE_DELAY` when
+/// it was queued. Returns `None` if the task is due now.
+fn remaining_queue_delay(execution_time: &str) -> Option<std::time::Duration> {
+    let execution_time = chrono::DateTime::parse_from_rfc3339(execution_time).ok()?;
+    let remaining = (execution_time - chrono::Utc::now().fixed_offset())
+        .to_std()
+        .ok()?;
+    (remaining > std::time::Duration::from_secs(1)).then(|| remaining.min(MAX_QUEUE_DELAY))
+}
+
+/// Extracts the target host of a webhook task from its URL.
+pub(crate) fn webhook_host(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Replaces the placeholders in a webhook task's body with their values for
+/// the current fire of the task. Currently, the only placeholder is
+/// `{{sequence}}`, which is replaced with the task's sequence number.
+fn render_body(body: &str, sequence: i64) -> String {
+    body.replace("{{sequence}}", &sequence.to_string())
+}
+
+/// Sleeps until the supplied execution time has arrived, returning immediately
+/// if it lies in the past. The `DelayQueue` yields tasks with millisecond
+/// granularity, so this computes the exact remaining duration in order to
+/// honor the full precision of the execution time instead of waiting in fixed
+/// steps. A leap second (`:60`) in the execution time is represented by chrono
+/// as an extension of the preceding second, which keeps the remaining duration
+/// well-defined.
+async fn sleep_until_execution_time(execution_time: chrono::DateTime<chrono::FixedOffset>) {
+    let remaining = (execution_time - chrono::Utc::now().fixed_offset())
+        .to_std()
+        .unwrap_or_default();
+    tokio::time::sleep_until(tokio::time::Instant::now() + remaining).await;
 }
 
-#[allow(clippy::too_many_lines)]
 /// Handles a webhook task by ensuring it is time to execute it or otherwise
-/// resetting its state to 'todo'. Upon successful POST of the task's body to
-/// the task's URL, prints the obtained HTTP status code.
-async fn handle_webhook(db_pool: sqlx::sqlite::SqlitePool, task: WorkerWebhook) {
+/// resetting its state to 'todo'. Upon successfully sending the task's body to
+/// the task's URL via the task's HTTP method, prints the obtained HTTP status
+/// code. Requests to the same host are subject to the host's concurrency limit
+/// in the worker's `host_limiter`. If traces are exported, the task is handled
+/// within a span carrying its ID and URL.
+async fn handle_webhook(ctx: WorkerCtx, task: WorkerWebhook) {
+    let span = ctx.webhook_span(&task);
+    claim_webhook(ctx, task).instrument(span).await;
+}
+
+/// Claims a webhook task once it is time to execute it and delivers it, see
+/// `handle_webhook`.
+#[allow(clippy::too_many_lines)]
+async fn claim_webhook(ctx: WorkerCtx, task: WorkerWebhook) {
+    // Wait for a free slot among the tasks the worker handles concurrently. The
+    // slot is held until handling this task finished.
+    let _permit = ctx.task_limiter.acquire().await;
+
     // Parse 'execution_time' field from webhooks database as RFC 3339 datetime.
     // This can't fail, as we're only ever inserting valid RFC 3339 datetimes
     // through the HTTP API.
@@ -35,16 +886,41 @@ async fn handle_webhook(db_pool: sqlx::sqlite::SqlitePool, task: WorkerWebhook)
 
         // In case of failure, permanently mark this task's state as 'failed'.
         let task_id = task.id.clone();
-        match sqlx::query!(
-            "UPDATE webhooks \
-            SET state = 'failed' \
-            WHERE id = $1 AND state = 'todo';",
-            task_id,
-        )
-        .execute(&db_pool)
-        .await
-        {
-            Ok(_) => (),
+        let completed_at = crate::db::timestamp(chrono::Utc::now());
+        let updated = crate::db::retry_transient(|| async {
+            match &ctx.db_pool {
+                crate::db::DbPool::Sqlite(db_pool) => sqlx::query!(
+                    "UPDATE webhooks \
+                    SET state = 'failed', completed_at = $2 \
+                    WHERE id = $1 AND state = 'todo';",
+                    task_id,
+                    completed_at,
+                )
+                .execute(db_pool)
+                .await
+                .map(|r| r.rows_affected()),
+                crate::db::DbPool::Postgres(db_pool) => sqlx::query(
+                    "UPDATE webhooks \
+                    SET state = 'failed', completed_at = $2 \
+                    WHERE id = $1 AND state = 'todo';",
+                )
+                .bind(&task_id)
+                .bind(&completed_at)
+                .execute(db_pool)
+                .await
+                .map(|r| r.rows_affected()),
+            }
+        })
+        .await;
+        match updated {
+            Ok(rows) => {
+                // The task may have been cancelled or deleted in the meantime,
+                // in which case it didn't finish here.
+                if rows == 1 {
+                    audit_webhook(&task.id, "todo", "failed", "malformed execution time");
+                    ctx.webhook_finished(&task.url, "failed");
+                }
+            }
             Err(e) => {
                 event!(
                     Level::WARN,
@@ -56,29 +932,63 @@ async fn handle_webhook(db_pool: sqlx::sqlite::SqlitePool, task: WorkerWebhook)
         return;
     };
 
-    // If the time to handle this webhook task has not yet come, wait a bit.
-    while chrono::Utc::now().fixed_offset() < execution_time {
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-    }
+    // If the time to handle this webhook task has not yet come, sleep until it has.
+    sleep_until_execution_time(execution_time).await;
+
+    // Register a cancellation token for this task before claiming it below. A
+    // cancellation request either finds this task still in state `todo` and
+    // prevents the claim, or finds the token and aborts the in-flight request.
+    let cancel_guard = ctx.cancel_registry.register(&task.id);
 
     // Immediately mark this task's state as `in_progress` as long as it is still in
     // state `todo`. Due to `SQLite`'s isolation features (serializing writes, i.e.,
     // parallel writers need to take turns), this means that no two tokio tasks
     // entering this handler at the same time will also both proceed beyond this
     // "barrier". Only one of them will while the other won't due to the now
-    // incorrect `state = 'todo'` condition. This prevents the situation where the
-    // same task is handled by more than one worker task concurrently.
+    // incorrect `state = 'todo'` condition. On `PostgreSQL`, the second `UPDATE`
+    // waits for the row lock of the first and then re-evaluates its condition
+    // against the committed row, with the same outcome. This prevents the
+    // situation where the same task is handled by more than one worker task
+    // concurrently. The task's sequence counter is incremented as part of the
+    // same statement, so each fire of a task observes a distinct sequence number.
+    // The task is only claimed for the execution time it was queued for, so that
+    // a stale queue entry, e.g. one left behind by reloading the queue while the
+    // task was rescheduled, never handles the task at another time.
     let task_id = task.id.clone();
-    let res = match sqlx::query!(
-        "UPDATE webhooks \
-        SET state = 'in_progress' \
-        WHERE id = $1 AND state = 'todo';",
-        task_id,
-    )
-    .execute(&db_pool)
-    .await
-    {
-        Ok(r) => r,
+    let started_at = crate::db::timestamp(chrono::Utc::now());
+    let claimed = crate::db::retry_transient(|| async {
+        match &ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_scalar!(
+                    "UPDATE webhooks \
+                    SET state = 'in_progress', sequence = sequence + 1, started_at = $3 \
+                    WHERE id = $1 AND state = 'todo' AND execution_time = $2 \
+                    RETURNING sequence;",
+                    task_id,
+                    task.execution_time,
+                    started_at,
+                )
+                .fetch_optional(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_scalar(
+                    "UPDATE webhooks \
+                    SET state = 'in_progress', sequence = sequence + 1, started_at = $3 \
+                    WHERE id = $1 AND state = 'todo' AND execution_time = $2 \
+                    RETURNING sequence;",
+                )
+                .bind(&task_id)
+                .bind(&task.execution_time)
+                .bind(&started_at)
+                .fetch_optional(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    let sequence = match claimed {
+        Ok(s) => s,
         Err(e) => {
             event!(
                 Level::WARN,
@@ -89,64 +999,311 @@ async fn handle_webhook(db_pool: sqlx::sqlite::SqlitePool, task: WorkerWebhook)
         }
     };
 
-    if res.rows_affected() != 1 {
+    let Some(sequence) = sequence else {
         event!(
             Level::DEBUG,
-            "Another task is already handling the POST request to '{}'...",
+            "Another task is already handling the {} request to '{}'...",
+            &task.method,
             &task.url,
         );
         return;
+    };
+    audit_webhook(&task.id, "todo", "in_progress", "claimed");
+
+    deliver_webhook(ctx, task, sequence, cancel_guard).await;
+}
+
+/// Handles a webhook task with a shard key as `handle_webhook` does, once all
+/// tasks with the same key that were handed over before it finished. Its
+/// `turn` is held until the task was delivered, including all retries.
+async fn handle_sharded_webhook(ctx: WorkerCtx, task: WorkerWebhook, mut turn: ShardTurn) {
+    turn.wait().await;
+    handle_webhook(ctx, task).await;
+    drop(turn);
+}
+
+/// Records a state transition of the webhook task with ID `id` caused by the
+/// worker in the audit log.
+fn audit_webhook(id: &str, from: &str, to: &str, cause: &str) {
+    crate::audit::record(crate::audit::Actor::Worker, "webhook", id, from, to, cause);
+}
+
+/// Records a state transition of the hash task with ID `id` caused by the
+/// worker in the audit log.
+fn audit_hash(id: &str, from: &str, to: &str, cause: &str) {
+    crate::audit::record(crate::audit::Actor::Worker, "hash", id, from, to, cause);
+}
+
+/// Moves a webhook task whose target host resolved to an address blocked by
+/// the egress policy to the dead letters, without sending any request.
+async fn refuse_webhook(ctx: &WorkerCtx, task: &WorkerWebhook, blocked: &crate::egress::Blocked) {
+    event!(
+        Level::WARN,
+        "Refusing to send {} request of webhook task '{}' to '{}': {blocked}",
+        &task.method,
+        &task.id,
+        &task.url,
+    );
+
+    let reason = format!("Refused to send {} request: {blocked}", &task.method);
+    match crate::db::retry_transient(|| {
+        crate::db::dead_letter_webhook(&ctx.db_pool, &task.id, &reason, &[])
+    })
+    .await
+    {
+        Ok(()) => {
+            audit_webhook(&task.id, "in_progress", "failed", &reason);
+            ctx.webhook_finished(&task.url, "failed");
+        }
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Worker failed to move webhook task '{}' to dead letters: {e}",
+                &task.id,
+            );
+        }
+    }
+}
+
+/// Header carrying the signature of the body of requests of webhook tasks with
+/// a signing secret.
+const SIGNATURE_HEADER: &str = "X-Signature-256";
+
+/// Signs the rendered `body` of a webhook request with the task's signing
+/// `secret`, as `sha256=` followed by the hex-encoded HMAC-SHA256 of the body.
+fn sign_body(secret: &str, body: &[u8]) -> String {
+    let mut mac = <hmac::Hmac<sha2::Sha256> as hmac::Mac>::new_from_slice(secret.as_bytes())
+        .unwrap_or_else(|_| unreachable!("HMAC accepts keys of any length"));
+    hmac::Mac::update(&mut mac, body);
+    let signature = hmac::Mac::finalize(mac).into_bytes();
+    let hex = signature
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = std::fmt::Write::write_fmt(&mut hex, format_args!("{byte:02x}"));
+            hex
+        });
+    format!("sha256={hex}")
+}
+
+/// Delivers a webhook task that was claimed for its fire with number
+/// `sequence` by sending the task's body in a request with the task's HTTP
+/// method to the task's URL, and finalizes the task's state according to the
+/// outcome. Requests to the same host are subject to the host's concurrency
+/// limit in the worker's `host_limiter`. The in-flight request is aborted if
+/// the task gets cancelled via `cancel_guard`. Tasks whose delivery still fails
+/// after all retries are moved to the dead letters.
+#[allow(clippy::too_many_lines)]
+async fn deliver_webhook(
+    ctx: WorkerCtx,
+    task: WorkerWebhook,
+    sequence: i64,
+    cancel_guard: CancelGuard,
+) {
+    // Refuse to send any request to a host resolving to a blocked address, if
+    // private ranges are blocked.
+    if let Some(egress_policy) = &ctx.egress_policy
+        && let Err(blocked) = egress_policy.check(&task.url).await
+    {
+        refuse_webhook(&ctx, &task, &blocked).await;
+        return;
     }
 
-    // The time to handle this webhook task has arrived, handle it.
-    event!(Level::DEBUG, "Handling POST request to '{}'...", &task.url);
+    // The rendered body is shared by all attempts, so that retries of tasks with
+    // large bodies don't copy the body again for every request.
+    let body = bytes::Bytes::from(render_body(&task.body, sequence));
+    let signature = task
+        .signing_secret
+        .as_deref()
+        .map(|secret| sign_body(secret, &body));
 
-    let mut tries: usize = 1;
-    let mut backoff_f: u64 = 1;
-    let mut res = reqwest::Client::new()
-        .post(&task.url)
-        .body(task.body.clone())
-        .send()
-        .await;
+    // Deliver the webhook, unless the task gets cancelled in the meantime, in
+    // which case the in-flight request is aborted by dropping it.
+    let delivery = async {
+        // Wait for a free slot for the target host of this webhook task. The slot
+        // is held across all retries and released once delivery finished.
+        let _host_permit = ctx.host_limiter.acquire(&webhook_host(&task.url)).await;
 
-    while res.is_err() && tries <= 5 {
+        // The time to handle this webhook task has arrived, handle it.
         event!(
             Level::DEBUG,
-            "Attempt {tries} / 5 to send POST to '{}' failed, backing off and retrying...",
+            "Handling {} request to '{}'...",
+            &task.method,
             &task.url
         );
-        let () = tokio::time::sleep(tokio::time::Duration::from_millis(100 * backoff_f)).await;
-        res = reqwest::Client::new()
-            .post(&task.url)
-            .body(task.body.clone())
-            .send()
-            .await;
-        tries += 1;
-        backoff_f *= 2;
-    }
 
-    let res = match res {
-        Ok(r) => r,
+        // Every attempt carries the trace context of this delivery, if traces are
+        // exported, so that the target can continue the trace.
+        let mut headers = task.headers.clone();
+        crate::telemetry::inject_trace_context(&mut headers);
+
+        // Every attempt carries the task's idempotency key, if it has one, so that
+        // the target can recognize retried deliveries, and the signature of the
+        // body, if the task has a signing secret.
+        let send = || {
+            let request = ctx
+                .webhook_client
+                .request(task.method.clone(), &task.url)
+                .headers(headers.clone())
+                .timeout(task.timeout)
+                .body(body.clone());
+            let request = match &task.idempotency_key {
+                Some(key) => request.header(&ctx.idempotency_key_header, key),
+                None => request,
+            };
+            match &signature {
+                Some(signature) => request.header(SIGNATURE_HEADER, signature),
+                None => request,
+            }
+            .send()
+        };
+
+        // Every attempt measures the time until the response arrived, and fails
+        // if that exceeded the task's latency bound, if any.
+        let attempt = || async {
+            let started = tokio::time::Instant::now();
+            let res = send().await.map_err(AttemptError::Request)?;
+            let latency = started.elapsed();
+            ctx.metrics.webhook_request(latency);
+            match task.max_latency {
+                Some(max_latency) if latency > max_latency => Err(AttemptError::TooSlow {
+                    latency,
+                    max_latency,
+                }),
+                _ => Ok((res, latency)),
+            }
+        };
+
+        // Retry failed requests as often as the task's retry policy allows,
+        // doubling the backoff after each retry, with jitter if configured.
+        let mut tries: usize = 1;
+        let mut backoff = task.initial_backoff;
+        let mut failed_attempts = Vec::new();
+        let mut res = attempt().await;
+
+        // A first request that failed to connect is retried once after the
+        // connect grace, without counting as a failed attempt.
+        if let Some(grace) = ctx.connect_grace
+            && matches!(&res, Err(AttemptError::Request(e)) if e.is_connect())
+        {
+            event!(
+                Level::DEBUG,
+                "First {} request to '{}' failed to connect, retrying after connect grace...",
+                &task.method,
+                &task.url
+            );
+            let () = tokio::time::sleep(grace).await;
+            res = attempt().await;
+        }
+
+        while let Err(e) = &res {
+            failed_attempts.push(crate::db::FailedAttempt::now(tries, e));
+            if tries > task.max_retries {
+                break;
+            }
+
+            event!(
+                Level::DEBUG,
+                "Attempt {tries} / {} to send {} to '{}' failed, backing off and retrying...",
+                task.max_retries + 1,
+                &task.method,
+                &task.url
+            );
+            let () = tokio::time::sleep(retry_delay(backoff, ctx.retry_jitter)).await;
+            res = attempt().await;
+            tries += 1;
+            backoff = (backoff * 2).min(MAX_WEBHOOK_BACKOFF);
+        }
+
+        (tries, res, failed_attempts)
+    };
+
+    let (tries, res, failed_attempts) = tokio::select! {
+        delivered = delivery => delivered,
+        () = cancel_guard.token.cancelled() => {
+            event!(
+                Level::INFO,
+                "Webhook task '{}' was cancelled, aborted {} request to '{}'",
+                &task.id,
+                &task.method,
+                &task.url,
+            );
+
+            // Finalize this task's state to 'cancelled'.
+            let task_id = task.id.clone();
+            let updated = crate::db::retry_transient(|| async {
+                match &ctx.db_pool {
+                    crate::db::DbPool::Sqlite(db_pool) => sqlx::query!(
+                        "UPDATE webhooks \
+                        SET state = 'cancelled' \
+                        WHERE id = $1 AND state = 'in_progress';",
+                        task_id,
+                    )
+                    .execute(db_pool)
+                    .await
+                    .map(|r| r.rows_affected()),
+                    crate::db::DbPool::Postgres(db_pool) => sqlx::query(
+                        "UPDATE webhooks \
+                        SET state = 'cancelled' \
+                        WHERE id = $1 AND state = 'in_progress';",
+                    )
+                    .bind(&task_id)
+                    .execute(db_pool)
+                    .await
+                    .map(|r| r.rows_affected()),
+                }
+            })
+            .await;
+            match updated {
+                Ok(rows) => {
+                    if rows > 0 {
+                        audit_webhook(&task.id, "in_progress", "cancelled", "cancelled in flight");
+                    }
+                    ctx.webhook_finished(&task.url, "cancelled");
+                    event!(
+                        Level::DEBUG,
+                        "Worker set 'state' for webhook task '{}' to 'cancelled'",
+                        &task.id,
+                    );
+                }
+                Err(e) => {
+                    event!(
+                        Level::WARN,
+                        "Worker failed to set 'state' for webhook task '{}' to 'cancelled': {e}",
+                        &task.id,
+                    );
+                }
+            }
+            return;
+        }
+    };
+
+    let (res, latency) = match res {
+        Ok(r) => r,
         Err(e) => {
             event!(
                 Level::WARN,
-                "Attempt {tries} / 5 to send POST to '{}' failed with (no further retries): {e}",
+                "Attempt {tries} / {} to send {} to '{}' failed with (no further retries): {e}",
+                task.max_retries + 1,
+                &task.method,
                 &task.url
             );
-            let task_id = task.id.clone();
-            match sqlx::query!(
-                "UPDATE webhooks \
-                SET state = 'failed' \
-                WHERE id = $1;",
-                task_id,
-            )
-            .execute(&db_pool)
+
+            // Retries are exhausted, move this task to the dead letters.
+            let reason = format!(
+                "Sending {} request failed after {tries} attempts: {e}",
+                &task.method
+            );
+            match crate::db::retry_transient(|| {
+                crate::db::dead_letter_webhook(&ctx.db_pool, &task.id, &reason, &failed_attempts)
+            })
             .await
             {
-                Ok(_) => {
+                Ok(()) => {
+                    audit_webhook(&task.id, "in_progress", "failed", &reason);
+                    ctx.webhook_finished(&task.url, "failed");
                     event!(
                         Level::DEBUG,
-                        "Worker set 'state' for webhook task '{}' to 'failed'",
+                        "Worker moved webhook task '{}' to dead letters",
                         &task.id,
                     );
                     return;
@@ -154,7 +1311,7 @@ async fn handle_webhook(db_pool: sqlx::sqlite::SqlitePool, task: WorkerWebhook)
                 Err(e) => {
                     event!(
                         Level::WARN,
-                        "Worker failed to set 'state' for webhook task '{}' to 'failed': {e}",
+                        "Worker failed to move webhook task '{}' to dead letters: {e}",
                         &task.id,
                     );
                     return;
@@ -165,23 +1322,69 @@ async fn handle_webhook(db_pool: sqlx::sqlite::SqlitePool, task: WorkerWebhook)
 
     event!(
         Level::INFO,
-        "POST request to '{}' yielded HTTP status code: {}",
+        "{} request to '{}' yielded HTTP status code: {}",
+        &task.method,
         &task.url,
         res.status().as_str(),
     );
 
+    // Keep the status code and the (truncated) response body as the result of
+    // this task. A body that can't be read doesn't render the delivery
+    // unsuccessful.
+    let outcome = WebhookOutcome {
+        status_code: i64::from(res.status().as_u16()),
+        latency_ms: i64::try_from(latency.as_millis()).unwrap_or(i64::MAX),
+        response_body: res.text().await.ok().map(truncate_response_body),
+        completed_at: crate::db::timestamp(chrono::Utc::now()),
+    };
+
+    // Recurring tasks are scheduled to fire again, as long as their cron
+    // expression matches any time in the future.
+    if let Some(cron) = &task.cron
+        && reschedule_recurring(&ctx, &task, cron, &outcome).await
+    {
+        return;
+    }
+
     // Request was successful, mark this task's state as 'done'.
     let task_id = task.id.clone();
-    match sqlx::query!(
-        "UPDATE webhooks \
-        SET state = 'done' \
-        WHERE id = $1;",
-        task_id,
-    )
-    .execute(&db_pool)
-    .await
-    {
-        Ok(_) => {
+    let updated = crate::db::retry_transient(|| async {
+        match &ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => sqlx::query!(
+                "UPDATE webhooks \
+                SET state = 'done', last_status_code = $2, last_response_body = $3, \
+                last_latency_ms = $4, completed_at = $5 \
+                WHERE id = $1;",
+                task_id,
+                outcome.status_code,
+                outcome.response_body,
+                outcome.latency_ms,
+                outcome.completed_at,
+            )
+            .execute(db_pool)
+            .await
+            .map(|_| ()),
+            crate::db::DbPool::Postgres(db_pool) => sqlx::query(
+                "UPDATE webhooks \
+                SET state = 'done', last_status_code = $2, last_response_body = $3, \
+                last_latency_ms = $4, completed_at = $5 \
+                WHERE id = $1;",
+            )
+            .bind(&task_id)
+            .bind(outcome.status_code)
+            .bind(&outcome.response_body)
+            .bind(outcome.latency_ms)
+            .bind(&outcome.completed_at)
+            .execute(db_pool)
+            .await
+            .map(|_| ()),
+        }
+    })
+    .await;
+    match updated {
+        Ok(()) => {
+            audit_webhook(&task.id, "in_progress", "done", "delivered");
+            ctx.webhook_finished(&task.url, "done");
             event!(
                 Level::DEBUG,
                 "Worker set 'state' for webhook task '{}' to 'done'",
@@ -198,11 +1401,186 @@ async fn handle_webhook(db_pool: sqlx::sqlite::SqlitePool, task: WorkerWebhook)
     }
 }
 
-#[allow(clippy::too_many_lines)]
+/// Why a single attempt at delivering a webhook task failed.
+#[derive(Debug)]
+enum AttemptError {
+    /// No response was received.
+    Request(reqwest::Error),
+    /// A response was received, but only after the task's latency bound.
+    TooSlow {
+        latency: std::time::Duration,
+        max_latency: std::time::Duration,
+    },
+}
+
+impl std::fmt::Display for AttemptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttemptError::Request(e) => write!(f, "{e}"),
+            AttemptError::TooSlow {
+                latency,
+                max_latency,
+            } => write!(
+                f,
+                "response took {} ms, exceeding the maximum latency of {} ms",
+                latency.as_millis(),
+                max_latency.as_millis()
+            ),
+        }
+    }
+}
+
+/// Maximum length in bytes of the response body kept as the result of a
+/// webhook task. Longer bodies are truncated.
+const MAX_RESPONSE_BODY_LEN: usize = 8 * 1024;
+
+/// Truncates `body` to at most `MAX_RESPONSE_BODY_LEN` bytes, without splitting
+/// a character.
+fn truncate_response_body(mut body: String) -> String {
+    if body.len() > MAX_RESPONSE_BODY_LEN {
+        let mut end = MAX_RESPONSE_BODY_LEN;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        body.truncate(end);
+    }
+    body
+}
+
+/// The result of a webhook task's request that got a response, kept alongside
+/// the task.
+#[derive(Debug)]
+struct WebhookOutcome {
+    status_code: i64,
+    latency_ms: i64,
+    response_body: Option<String>,
+    completed_at: String,
+}
+
+/// Schedules the recurring webhook `task` whose request was just successful to
+/// fire again at the next time its `cron` expression matches: its state is
+/// reset to 'todo', its execution time set to the next run, and it is queued
+/// again. Returns whether the task was rescheduled, which it isn't if its
+/// expression doesn't match any time in the future anymore.
+async fn reschedule_recurring(
+    ctx: &WorkerCtx,
+    task: &WorkerWebhook,
+    cron: &str,
+    outcome: &WebhookOutcome,
+) -> bool {
+    // Only the business calendar exists, which `calendar` was validated to name.
+    let calendar = task.calendar.as_ref().map(|_| &*ctx.business_calendar);
+    let next_run = match next_cron_run(cron, calendar, chrono::Utc::now()) {
+        Ok(n) => n,
+        Err(e) => {
+            event!(
+                Level::INFO,
+                "Recurring webhook task '{}' won't fire again: {e}",
+                &task.id,
+            );
+            return false;
+        }
+    };
+
+    let execution_time = next_run.to_rfc3339();
+    let updated = crate::db::retry_transient(|| async {
+        match &ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_as!(
+                    crate::api::ApiWebhook,
+                    "UPDATE webhooks \
+                    SET state = 'todo', execution_time = $2, last_status_code = $3, \
+                    last_response_body = $4, last_latency_ms = $5, completed_at = $6 \
+                    WHERE id = $1 AND state = 'in_progress' \
+                    RETURNING id, state, execution_time, method, url, body, idempotency_key, cron, calendar, \
+                    max_retries, initial_backoff_ms, timeout_ms, max_latency_ms, signing_secret, \
+                    priority, shard_key, last_status_code, last_response_body, last_latency_ms, \
+                    created_at, started_at, completed_at, \
+                    headers AS \"headers: sqlx::types::Json<crate::api::WebhookHeaders>\", \
+                    tags AS \"tags: sqlx::types::Json<Vec<String>>\";",
+                    task.id,
+                    execution_time,
+                    outcome.status_code,
+                    outcome.response_body,
+                    outcome.latency_ms,
+                    outcome.completed_at,
+                )
+                .fetch_optional(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_as(&format!(
+                    "UPDATE webhooks \
+                    SET state = 'todo', execution_time = $2, last_status_code = $3, \
+                    last_response_body = $4, last_latency_ms = $5, completed_at = $6 \
+                    WHERE id = $1 AND state = 'in_progress' \
+                    RETURNING {};",
+                    crate::db::PG_WEBHOOK_COLUMNS
+                ))
+                .bind(&task.id)
+                .bind(&execution_time)
+                .bind(outcome.status_code)
+                .bind(&outcome.response_body)
+                .bind(outcome.latency_ms)
+                .bind(&outcome.completed_at)
+                .fetch_optional(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    let rescheduled = match updated {
+        Ok(Some(wh)) => wh,
+        // The task was cancelled or deleted while its request was in flight.
+        Ok(None) => return true,
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Worker failed to reschedule recurring webhook task '{}': {e}",
+                &task.id,
+            );
+            return true;
+        }
+    };
+    audit_webhook(
+        &task.id,
+        "in_progress",
+        "todo",
+        "rescheduled for next cron run",
+    );
+    ctx.webhook_finished(&task.url, "done");
+
+    let dur_from_now = (next_run - chrono::Utc::now()).to_std().unwrap_or_default();
+    if let Err(e) = ctx
+        .send_task
+        .send((dur_from_now, crate::api::Task::Webhook(rescheduled)))
+        .await
+    {
+        event!(
+            Level::WARN,
+            "Worker failed to queue recurring webhook task '{}' again: {e}",
+            &task.id,
+        );
+        return true;
+    }
+
+    event!(
+        Level::DEBUG,
+        "Worker rescheduled recurring webhook task '{}' for {execution_time}",
+        &task.id,
+    );
+    true
+}
+
 /// Handles a hash task by ensuring it is time to execute it or otherwise
 /// resetting its state to 'todo'. Upon obtaining the desired hash of the secret
 /// value, prints it in base64.
-async fn handle_hash(db_pool: sqlx::sqlite::SqlitePool, task: WorkerHash) {
+#[allow(clippy::too_many_lines)]
+async fn handle_hash(ctx: WorkerCtx, task: WorkerHash) {
+    // Wait for a free slot among the tasks the worker handles concurrently, as
+    // in `handle_webhook`.
+    let _permit = ctx.task_limiter.acquire().await;
+
     // Parse 'execution_time' field from hashes database as RFC 3339 datetime.
     // This can't fail, as we're only ever inserting valid RFC 3339 datetimes
     // through the HTTP API.
@@ -215,16 +1593,41 @@ async fn handle_hash(db_pool: sqlx::sqlite::SqlitePool, task: WorkerHash) {
 
         // In case of failure, permanently mark this task's state as 'failed'.
         let task_id = task.id.clone();
-        match sqlx::query!(
-            "UPDATE hashes \
-            SET state = 'failed' \
-            WHERE id = $1 AND state = 'todo';",
-            task_id,
-        )
-        .execute(&db_pool)
-        .await
-        {
-            Ok(_) => (),
+        let completed_at = crate::db::timestamp(chrono::Utc::now());
+        let updated = crate::db::retry_transient(|| async {
+            match &ctx.db_pool {
+                crate::db::DbPool::Sqlite(db_pool) => sqlx::query!(
+                    "UPDATE hashes \
+                    SET state = 'failed', completed_at = $2 \
+                    WHERE id = $1 AND state = 'todo';",
+                    task_id,
+                    completed_at,
+                )
+                .execute(db_pool)
+                .await
+                .map(|r| r.rows_affected()),
+                crate::db::DbPool::Postgres(db_pool) => sqlx::query(
+                    "UPDATE hashes \
+                    SET state = 'failed', completed_at = $2 \
+                    WHERE id = $1 AND state = 'todo';",
+                )
+                .bind(&task_id)
+                .bind(&completed_at)
+                .execute(db_pool)
+                .await
+                .map(|r| r.rows_affected()),
+            }
+        })
+        .await;
+        match updated {
+            Ok(rows) => {
+                // The task may have been cancelled or deleted in the meantime,
+                // in which case it didn't finish here.
+                if rows == 1 {
+                    audit_hash(&task.id, "todo", "failed", "malformed execution time");
+                    ctx.hash_finished("failed");
+                }
+            }
             Err(e) => {
                 event!(
                     Level::WARN,
@@ -236,28 +1639,50 @@ async fn handle_hash(db_pool: sqlx::sqlite::SqlitePool, task: WorkerHash) {
         return;
     };
 
-    // If the time to handle this hash task has not yet come, wait a bit.
-    while chrono::Utc::now().fixed_offset() < execution_time {
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-    }
+    // If the time to handle this hash task has not yet come, sleep until it has.
+    sleep_until_execution_time(execution_time).await;
 
     // Immediately mark this task's state as `in_progress` as long as it is still in
     // state `todo`. Due to `SQLite`'s isolation features (serializing writes, i.e.,
     // parallel writers need to take turns), this means that no two tokio tasks
     // entering this handler at the same time will also both proceed beyond this
     // "barrier". Only one of them will while the other won't due to the now
-    // incorrect `state = 'todo'` condition. This prevents the situation where the
-    // same task is handled by more than one worker task concurrently.
+    // incorrect `state = 'todo'` condition. On `PostgreSQL`, the second `UPDATE`
+    // waits for the row lock of the first and then re-evaluates its condition
+    // against the committed row, with the same outcome. This prevents the
+    // situation where the same task is handled by more than one worker task
+    // concurrently. As for webhook tasks, the task is only claimed for the
+    // execution time it was queued for.
     let task_id = task.id.clone();
-    let res = match sqlx::query!(
-        "UPDATE hashes \
-        SET state = 'in_progress' \
-        WHERE id = $1 AND state = 'todo';",
-        task_id,
-    )
-    .execute(&db_pool)
-    .await
-    {
+    let started_at = crate::db::timestamp(chrono::Utc::now());
+    let claimed = crate::db::retry_transient(|| async {
+        match &ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => sqlx::query!(
+                "UPDATE hashes \
+                SET state = 'in_progress', started_at = $3 \
+                WHERE id = $1 AND state = 'todo' AND execution_time = $2;",
+                task_id,
+                task.execution_time,
+                started_at,
+            )
+            .execute(db_pool)
+            .await
+            .map(|r| r.rows_affected()),
+            crate::db::DbPool::Postgres(db_pool) => sqlx::query(
+                "UPDATE hashes \
+                SET state = 'in_progress', started_at = $3 \
+                WHERE id = $1 AND state = 'todo' AND execution_time = $2;",
+            )
+            .bind(&task_id)
+            .bind(&task.execution_time)
+            .bind(&started_at)
+            .execute(db_pool)
+            .await
+            .map(|r| r.rows_affected()),
+        }
+    })
+    .await;
+    let rows = match claimed {
         Ok(r) => r,
         Err(e) => {
             event!(
@@ -269,7 +1694,7 @@ async fn handle_hash(db_pool: sqlx::sqlite::SqlitePool, task: WorkerHash) {
         }
     };
 
-    if res.rows_affected() != 1 {
+    if rows != 1 {
         event!(
             Level::DEBUG,
             "Another task is already handling the hash task for '{}'...",
@@ -277,52 +1702,59 @@ async fn handle_hash(db_pool: sqlx::sqlite::SqlitePool, task: WorkerHash) {
         );
         return;
     }
+    audit_hash(&task.id, "todo", "in_progress", "claimed");
 
+    compute_hash(ctx, task).await;
+}
+
+/// Computes the hash of a hash task that was claimed for handling, and
+/// finalizes the task's state according to the outcome.
+#[allow(clippy::too_many_lines)]
+async fn compute_hash(ctx: WorkerCtx, task: WorkerHash) {
     // Time to handle this hash task has arrived, handle it.
     event!(Level::DEBUG, "Handling hash task for '{}'...", &task.secret);
 
-    let secret = task.secret.as_bytes().to_vec();
-    let hash = match tokio::task::spawn_blocking(move || {
-        let salt = pbkdf2::password_hash::SaltString::generate(&mut rand::rngs::OsRng);
-        match pbkdf2::Pbkdf2.hash_password_customized(
-            &secret,
-            None,
-            None,
-            pbkdf2::Params {
-                rounds: 600_000,
-                output_length: 32,
-            },
-            &salt,
-        ) {
-            Ok(h) => h.to_string(),
-            Err(e) => e.to_string(),
+    // Parse the parameters configuring the hash computation and compute the hash.
+    // Parsing only fails if the limits for the parameters were lowered since the
+    // task was inserted through the HTTP API.
+    let hash = match task.algorithm.parse::<crate::hashing::Algorithm>() {
+        Ok(algorithm) => {
+            ctx.hasher
+                .compute(
+                    task.secret.as_bytes().to_vec(),
+                    algorithm,
+                    task.params.clone(),
+                )
+                .await
         }
-    })
-    .await
-    {
+        Err(e) => Err(e),
+    };
+
+    let hash = match hash {
         Ok(h) => h,
         Err(e) => {
             event!(
                 Level::WARN,
-                "Computing the PBKDF2 hash value for secret '{}' failed: {e}",
+                "Computing the {} hash value for secret '{}' failed: {e}",
+                &task.algorithm,
                 &task.secret,
             );
 
-            // Finalize this task's state to 'failed'.
-            let task_id = task.id.clone();
-            match sqlx::query!(
-                "UPDATE hashes \
-                SET state = 'failed' \
-                WHERE id = $1;",
-                task_id,
-            )
-            .execute(&db_pool)
+            // Computing a hash isn't retried, as it would fail the same way again.
+            // Move this task to the dead letters.
+            let failed_attempts = [crate::db::FailedAttempt::now(1, &e)];
+            let reason = format!("Computing the {} hash failed: {e}", &task.algorithm);
+            match crate::db::retry_transient(|| {
+                crate::db::dead_letter_hash(&ctx.db_pool, &task.id, &reason, &failed_attempts)
+            })
             .await
             {
-                Ok(_) => {
+                Ok(()) => {
+                    audit_hash(&task.id, "in_progress", "failed", &reason);
+                    ctx.hash_finished("failed");
                     event!(
                         Level::DEBUG,
-                        "Worker set 'state' for hash task '{}' to 'failed'",
+                        "Worker moved hash task '{}' to dead letters",
                         &task.id,
                     );
                     return;
@@ -330,7 +1762,7 @@ async fn handle_hash(db_pool: sqlx::sqlite::SqlitePool, task: WorkerHash) {
                 Err(e) => {
                     event!(
                         Level::WARN,
-                        "Worker failed to set 'state' for hash task '{}' to 'failed': {e}",
+                        "Worker failed to move hash task '{}' to dead letters: {e}",
                         &task.id,
                     );
                     return;
@@ -342,23 +1774,47 @@ async fn handle_hash(db_pool: sqlx::sqlite::SqlitePool, task: WorkerHash) {
     let hash_base64 = BASE64_STANDARD.encode(hash);
     event!(
         Level::INFO,
-        "Base64-encoded hash of secret '{}' obtained with PBKDF2: '{}'",
+        "Base64-encoded hash of secret '{}' obtained with {}: '{}'",
         &task.secret,
+        &task.algorithm,
         hash_base64,
     );
 
-    // Request was successful, mark this task's state as 'done'.
+    // Request was successful, mark this task's state as 'done' and keep the hash
+    // as the result of this task.
     let task_id = task.id.clone();
-    match sqlx::query!(
-        "UPDATE hashes \
-        SET state = 'done' \
-        WHERE id = $1;",
-        task_id,
-    )
-    .execute(&db_pool)
-    .await
-    {
-        Ok(_) => {
+    let completed_at = crate::db::timestamp(chrono::Utc::now());
+    let updated = crate::db::retry_transient(|| async {
+        match &ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => sqlx::query!(
+                "UPDATE hashes \
+                SET state = 'done', result = $2, completed_at = $3 \
+                WHERE id = $1;",
+                task_id,
+                hash_base64,
+                completed_at,
+            )
+            .execute(db_pool)
+            .await
+            .map(|_| ()),
+            crate::db::DbPool::Postgres(db_pool) => sqlx::query(
+                "UPDATE hashes \
+                SET state = 'done', result = $2, completed_at = $3 \
+                WHERE id = $1;",
+            )
+            .bind(&task_id)
+            .bind(&hash_base64)
+            .bind(&completed_at)
+            .execute(db_pool)
+            .await
+            .map(|_| ()),
+        }
+    })
+    .await;
+    match updated {
+        Ok(()) => {
+            audit_hash(&task.id, "in_progress", "done", "computed");
+            ctx.hash_finished("done");
             event!(
                 Level::DEBUG,
                 "Worker set 'state' for hash task '{}' to 'done'",
@@ -375,54 +1831,1210 @@ async fn handle_hash(db_pool: sqlx::sqlite::SqlitePool, task: WorkerHash) {
     }
 }
 
+/// Commands the API can send to the worker concerning the tasks in its delay
+/// queue.
+#[derive(Debug)]
+pub(crate) enum WorkerCmd {
+    /// Removes the task with ID `id` from the delay queue, if present.
+    Remove { id: String },
+    /// Replies with the deadline at which the task with ID `id` will be yielded
+    /// by the delay queue, if present.
+    Inspect {
+        id: String,
+        reply: tokio::sync::oneshot::Sender<Option<tokio::time::Instant>>,
+    },
+    /// Removes all tasks from the delay queue and replies with their number.
+    Clear {
+        reply: tokio::sync::oneshot::Sender<usize>,
+    },
+}
+
+/// Claims a batch of ready webhook tasks in a single statement, instead of one
+/// statement per task as `handle_webhook` does, and delivers each claimed task.
+/// This reduces write contention on the database when many tasks become due at
+/// once. As in `handle_webhook`, only tasks still in state `todo` are claimed,
+/// so no task is handled more than once.
+async fn handle_webhook_batch(ctx: WorkerCtx, tasks: Vec<WorkerWebhook>) {
+    let mut latest_execution_time = None;
+    let mut claimable = std::collections::HashMap::with_capacity(tasks.len());
+    for task in tasks {
+        let Ok(execution_time) = chrono::DateTime::parse_from_rfc3339(&task.execution_time) else {
+            // Leave marking the task as 'failed' to the single-task handler.
+            let ids = vec![task.id.clone()];
+            ctx.spawn_handler(HandlerKind::Webhook, ids, handle_webhook(ctx.clone(), task));
+            continue;
+        };
+        latest_execution_time = latest_execution_time.max(Some(execution_time));
+
+        // Register a cancellation token for each task before claiming the batch,
+        // for the same reasons as in `handle_webhook`.
+        let cancel_guard = ctx.cancel_registry.register(&task.id);
+        claimable.insert(task.id.clone(), (task, cancel_guard));
+    }
+
+    // All tasks of a batch were yielded by the delay queue at about the same
+    // time, so wait until the latest execution time among them has come.
+    let Some(latest_execution_time) = latest_execution_time else {
+        return;
+    };
+    sleep_until_execution_time(latest_execution_time).await;
+
+    // As in `handle_webhook`, tasks are only claimed for the execution times
+    // they were queued for.
+    let ids = serde_json::Value::from(
+        claimable
+            .values()
+            .map(|(task, _)| vec![task.id.clone(), task.execution_time.clone()])
+            .collect::<Vec<_>>(),
+    )
+    .to_string();
+    let started_at = crate::db::timestamp(chrono::Utc::now());
+    let updated = crate::db::retry_transient(|| async {
+        match &ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => sqlx::query!(
+                "UPDATE webhooks \
+                SET state = 'in_progress', sequence = sequence + 1, started_at = $2 \
+                WHERE ( id, execution_time ) IN ( \
+                    SELECT json_extract(value, '$[0]'), json_extract(value, '$[1]') FROM json_each($1) \
+                ) AND state = 'todo' \
+                RETURNING id, sequence;",
+                ids,
+                started_at,
+            )
+            .fetch_all(db_pool)
+            .await
+            .map(|rows| {
+                rows.into_iter()
+                    .map(|row| (row.id, row.sequence))
+                    .collect::<Vec<_>>()
+            }),
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_as::<_, (String, i64)>(
+                    "UPDATE webhooks \
+                    SET state = 'in_progress', sequence = sequence + 1, started_at = $2 \
+                    WHERE ( id, execution_time ) IN ( \
+                        SELECT value->>0, value->>1 FROM jsonb_array_elements($1::JSONB) \
+                    ) AND state = 'todo' \
+                    RETURNING id, sequence;",
+                )
+                .bind(&ids)
+                .bind(&started_at)
+                .fetch_all(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    let mut claimed = match updated {
+        Ok(c) => c,
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Worker failed to set 'state' for batch of {} webhook tasks to 'in_progress': {e}",
+                claimable.len(),
+            );
+            return;
+        }
+    };
+
+    event!(
+        Level::DEBUG,
+        "Claimed {} out of a batch of {} webhook tasks",
+        claimed.len(),
+        claimable.len(),
+    );
+    for (id, _) in &claimed {
+        audit_webhook(id, "todo", "in_progress", "claimed as part of a batch");
+    }
+
+    // Deliver each claimed task as soon as a slot among the tasks the worker
+    // handles concurrently is free, holding the slot until delivery finished.
+    // Tasks of higher priority get a slot first.
+    claimed.sort_by_key(|(id, _)| {
+        std::cmp::Reverse(claimable.get(id).map_or(0, |(task, _)| task.priority))
+    });
+    for (id, sequence) in claimed {
+        if let Some((task, cancel_guard)) = claimable.remove(&id) {
+            let permit = ctx.task_limiter.acquire().await;
+            let handler_ctx = ctx.clone();
+            let span = ctx.webhook_span(&task);
+            ctx.spawn_handler(HandlerKind::Webhook, vec![id], async move {
+                deliver_webhook(handler_ctx, task, sequence, cancel_guard)
+                    .instrument(span)
+                    .await;
+                drop(permit);
+            });
+        }
+    }
+}
+
+/// Claims a batch of ready hash tasks in a single statement and computes the
+/// hash of each claimed task, analogous to `handle_webhook_batch`.
+async fn handle_hash_batch(ctx: WorkerCtx, tasks: Vec<WorkerHash>) {
+    let mut latest_execution_time = None;
+    let mut claimable = std::collections::HashMap::with_capacity(tasks.len());
+    for task in tasks {
+        let Ok(execution_time) = chrono::DateTime::parse_from_rfc3339(&task.execution_time) else {
+            // Leave marking the task as 'failed' to the single-task handler.
+            let ids = vec![task.id.clone()];
+            ctx.spawn_handler(HandlerKind::Hash, ids, handle_hash(ctx.clone(), task));
+            continue;
+        };
+        latest_execution_time = latest_execution_time.max(Some(execution_time));
+        claimable.insert(task.id.clone(), task);
+    }
+
+    let Some(latest_execution_time) = latest_execution_time else {
+        return;
+    };
+    sleep_until_execution_time(latest_execution_time).await;
+
+    let ids = serde_json::Value::from(
+        claimable
+            .values()
+            .map(|task| vec![task.id.clone(), task.execution_time.clone()])
+            .collect::<Vec<_>>(),
+    )
+    .to_string();
+    let started_at = crate::db::timestamp(chrono::Utc::now());
+    let updated = crate::db::retry_transient(|| async {
+        match &ctx.db_pool {
+            crate::db::DbPool::Sqlite(db_pool) => {
+                sqlx::query_scalar!(
+                    "UPDATE hashes \
+                    SET state = 'in_progress', started_at = $2 \
+                    WHERE ( id, execution_time ) IN ( \
+                        SELECT json_extract(value, '$[0]'), json_extract(value, '$[1]') FROM json_each($1) \
+                    ) AND state = 'todo' \
+                    RETURNING id;",
+                    ids,
+                    started_at,
+                )
+                .fetch_all(db_pool)
+                .await
+            }
+            crate::db::DbPool::Postgres(db_pool) => {
+                sqlx::query_scalar(
+                    "UPDATE hashes \
+                    SET state = 'in_progress', started_at = $2 \
+                    WHERE ( id, execution_time ) IN ( \
+                        SELECT value->>0, value->>1 FROM jsonb_array_elements($1::JSONB) \
+                    ) AND state = 'todo' \
+                    RETURNING id;",
+                )
+                .bind(&ids)
+                .bind(&started_at)
+                .fetch_all(db_pool)
+                .await
+            }
+        }
+    })
+    .await;
+    let claimed = match updated {
+        Ok(c) => c,
+        Err(e) => {
+            event!(
+                Level::WARN,
+                "Worker failed to set 'state' for batch of {} hash tasks to 'in_progress': {e}",
+                claimable.len(),
+            );
+            return;
+        }
+    };
+
+    event!(
+        Level::DEBUG,
+        "Claimed {} out of a batch of {} hash tasks",
+        claimed.len(),
+        claimable.len(),
+    );
+    for id in &claimed {
+        audit_hash(id, "todo", "in_progress", "claimed as part of a batch");
+    }
+
+    for id in claimed {
+        if let Some(task) = claimable.remove(&id) {
+            let permit = ctx.task_limiter.acquire().await;
+            let handler_ctx = ctx.clone();
+            ctx.spawn_handler(HandlerKind::Hash, vec![id], async move {
+                compute_hash(handler_ctx, task).await;
+                drop(permit);
+            });
+        }
+    }
+}
+
+/// Converts a webhook task yielded by the delay queue into the form handled by
+/// the worker.
+fn worker_webhook(wh: crate::api::ApiWebhook) -> WorkerWebhook {
+    WorkerWebhook {
+        id: wh.id,
+        execution_time: wh.execution_time,
+        // Stored methods were validated upon task creation.
+        method: reqwest::Method::from_bytes(wh.method.as_bytes()).unwrap_or(reqwest::Method::POST),
+        url: wh.url,
+        body: wh.body,
+        idempotency_key: wh.idempotency_key,
+        cron: wh.cron,
+        calendar: wh.calendar,
+        priority: wh.priority,
+        max_retries: usize::try_from(wh.max_retries).unwrap_or_default(),
+        initial_backoff: std::time::Duration::from_millis(
+            u64::try_from(wh.initial_backoff_ms).unwrap_or_default(),
+        ),
+        // Tasks stored before timeouts were filled in upon creation fall back to
+        // the default timeout.
+        timeout: std::time::Duration::from_millis(
+            wh.timeout_ms
+                .and_then(|t| u64::try_from(t).ok())
+                .unwrap_or(crate::policy::DEFAULT_TIMEOUT_MS),
+        ),
+        max_latency: wh
+            .max_latency_ms
+            .and_then(|l| u64::try_from(l).ok())
+            .map(std::time::Duration::from_millis),
+        signing_secret: wh.signing_secret,
+        headers: webhook_headers(wh.headers.as_deref()),
+    }
+}
+
+/// How the worker recovers the tasks of a handler that panicked, which would
+/// otherwise remain in state `in_progress` until the next start: either by
+/// marking them as `failed`, or by resetting them to `todo` and queueing them
+/// again after `PANIC_REQUEUE_DELAY`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum PanicPolicy {
+    FailTask,
+    Requeue,
+}
+
+/// Time after which tasks of a panicked handler are handled again under
+/// `PanicPolicy::Requeue`, so that a task that reliably panics its handler
+/// doesn't keep the worker busy.
+const PANIC_REQUEUE_DELAY: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// The type of the tasks a handler works on, for recovering them if the
+/// handler panics.
+#[derive(Debug, Clone, Copy)]
+enum HandlerKind {
+    Webhook,
+    Hash,
+}
+
+impl HandlerKind {
+    fn name(self) -> &'static str {
+        match self {
+            HandlerKind::Webhook => "webhook",
+            HandlerKind::Hash => "hash",
+        }
+    }
+}
+
+/// Returns the message a panic was raised with, if it carries one.
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("no message")
+}
+
+/// Marks the worker as not alive anymore once dropped, which happens both when
+/// `WorkerCtx::run` returns and when it panics.
+struct AliveGuard(std::sync::Arc<std::sync::atomic::AtomicBool>);
+
+impl Drop for AliveGuard {
+    fn drop(&mut self) {
+        self.0.store(false, std::sync::atomic::Ordering::Release);
+    }
+}
+
+/// Settings of how the worker handles tasks, as configured upon start.
+#[derive(Debug, Clone)]
+pub(crate) struct WorkerOptions {
+    /// Maximum number of ready tasks of the same type claimed at once.
+    pub(crate) claim_batch_size: usize,
+    /// Time to wait upon shutdown for handlers still working on a task.
+    pub(crate) shutdown_grace: std::time::Duration,
+    /// How to recover the tasks of a handler that panicked.
+    pub(crate) panic_policy: PanicPolicy,
+    /// Whether to back off for a random time up to the current backoff before
+    /// retrying a webhook task's request.
+    pub(crate) retry_jitter: bool,
+    /// Time beyond which tasks are kept in the far-future bucket instead of the
+    /// delay queue, if any.
+    pub(crate) near_term_horizon: Option<std::time::Duration>,
+    /// Time to wait before retrying the first request of a webhook task once,
+    /// without counting a failed attempt, if it failed to connect, if any.
+    pub(crate) connect_grace: Option<std::time::Duration>,
+    /// Policy on which addresses webhook requests may be sent to, if private
+    /// ranges are blocked.
+    pub(crate) egress_policy: Option<std::sync::Arc<crate::egress::EgressPolicy>>,
+    /// Whether to handle each webhook task within a span that is exported as
+    /// part of a trace.
+    pub(crate) trace_webhooks: bool,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct WorkerCtx {
-    db_pool: sqlx::sqlite::SqlitePool,
+    db_pool: crate::db::DbPool,
+    send_task: tokio::sync::mpsc::Sender<(tokio::time::Duration, crate::api::Task)>,
+    host_limiter: std::sync::Arc<HostLimiter>,
+    task_limiter: std::sync::Arc<TaskLimiter>,
+    throughput: std::sync::Arc<Throughput>,
+    scheduling_error: std::sync::Arc<SchedulingError>,
+    cancel_registry: std::sync::Arc<CancelRegistry>,
+    alive: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    claim_batch_size: usize,
+    shutdown_grace: std::time::Duration,
+    panic_policy: PanicPolicy,
+    retry_jitter: bool,
+    near_term_horizon: Option<std::time::Duration>,
+    connect_grace: Option<std::time::Duration>,
+    egress_policy: Option<std::sync::Arc<crate::egress::EgressPolicy>>,
+    trace_webhooks: bool,
+    business_calendar: std::sync::Arc<crate::calendar::BusinessCalendar>,
+    metrics: std::sync::Arc<crate::metrics::Metrics>,
+    hasher: std::sync::Arc<crate::hashing::Hasher>,
+    idempotency_key_header: reqwest::header::HeaderName,
+    webhook_client: reqwest::Client,
+    outbound: Option<std::sync::Arc<OutboundQueue>>,
+    shard_locks: std::sync::Arc<ShardLocks>,
+    /// Tracks all spawned handlers, so that shutdown can await them.
+    handlers: tokio_util::task::TaskTracker,
 }
 
 impl WorkerCtx {
-    pub(crate) fn new(db_pool: sqlx::sqlite::SqlitePool) -> Self {
-        WorkerCtx { db_pool }
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        db_pool: crate::db::DbPool,
+        send_task: tokio::sync::mpsc::Sender<(tokio::time::Duration, crate::api::Task)>,
+        host_limiter: std::sync::Arc<HostLimiter>,
+        task_limiter: std::sync::Arc<TaskLimiter>,
+        throughput: std::sync::Arc<Throughput>,
+        scheduling_error: std::sync::Arc<SchedulingError>,
+        cancel_registry: std::sync::Arc<CancelRegistry>,
+        alive: std::sync::Arc<std::sync::atomic::AtomicBool>,
+        options: WorkerOptions,
+        business_calendar: std::sync::Arc<crate::calendar::BusinessCalendar>,
+        metrics: std::sync::Arc<crate::metrics::Metrics>,
+        hasher: std::sync::Arc<crate::hashing::Hasher>,
+        idempotency_key_header: reqwest::header::HeaderName,
+        webhook_client: reqwest::Client,
+        outbound: Option<std::sync::Arc<OutboundQueue>>,
+    ) -> Self {
+        let WorkerOptions {
+            claim_batch_size,
+            shutdown_grace,
+            panic_policy,
+            retry_jitter,
+            near_term_horizon,
+            connect_grace,
+            egress_policy,
+            trace_webhooks,
+        } = options;
+        WorkerCtx {
+            db_pool,
+            send_task,
+            host_limiter,
+            task_limiter,
+            throughput,
+            scheduling_error,
+            cancel_registry,
+            alive,
+            claim_batch_size,
+            shutdown_grace,
+            panic_policy,
+            retry_jitter,
+            near_term_horizon,
+            connect_grace,
+            egress_policy,
+            trace_webhooks,
+            business_calendar,
+            metrics,
+            hasher,
+            idempotency_key_header,
+            webhook_client,
+            outbound,
+            shard_locks: std::sync::Arc::new(ShardLocks::default()),
+            handlers: tokio_util::task::TaskTracker::new(),
+        }
+    }
+
+    /// Records that a webhook task to `url` reached terminal state `outcome`.
+    fn webhook_finished(&self, url: &str, outcome: &'static str) {
+        self.throughput.record();
+        self.metrics.webhook_finished(&webhook_host(url), outcome);
+    }
+
+    /// Returns the span within which webhook task `task` is handled, if traces
+    /// are exported.
+    fn webhook_span(&self, task: &WorkerWebhook) -> tracing::Span {
+        if self.trace_webhooks {
+            tracing::info_span!("handle_webhook", task_id = %task.id, url = %task.url)
+        } else {
+            tracing::Span::none()
+        }
+    }
+
+    /// Records that a hash task reached terminal state `outcome`.
+    fn hash_finished(&self, outcome: &'static str) {
+        self.throughput.record();
+        self.metrics.hash_finished(outcome);
+    }
+
+    /// Spawns `handler` working on the tasks of type `kind` with IDs `ids`, and
+    /// tracks it for shutdown. If the handler panics, the panic is logged and
+    /// those of the tasks it left in state `in_progress` are recovered as
+    /// configured by the panic policy.
+    fn spawn_handler(
+        &self,
+        kind: HandlerKind,
+        ids: Vec<String>,
+        handler: impl Future<Output = ()> + Send + 'static,
+    ) {
+        let ctx = self.clone();
+        self.handlers.spawn(async move {
+            let Err(e) = tokio::task::spawn(handler).await else {
+                return;
+            };
+            let Ok(payload) = e.try_into_panic() else {
+                return;
+            };
+            event!(
+                Level::ERROR,
+                "Handler of {} tasks {ids:?} panicked: {}",
+                kind.name(),
+                panic_message(payload.as_ref()),
+            );
+            for id in &ids {
+                ctx.recover_panicked(kind, id).await;
+            }
+        });
+    }
+
+    /// Recovers the task of type `kind` with ID `id` after its handler panicked,
+    /// if the task is still in state `in_progress`.
+    #[allow(clippy::too_many_lines)]
+    async fn recover_panicked(&self, kind: HandlerKind, id: &str) {
+        let completed_at = crate::db::timestamp(chrono::Utc::now());
+        let res = crate::db::retry_transient(|| async {
+            match (kind, self.panic_policy) {
+                (HandlerKind::Webhook, PanicPolicy::FailTask) => match &self.db_pool {
+                    crate::db::DbPool::Sqlite(db_pool) => sqlx::query!(
+                        "UPDATE webhooks \
+                        SET state = 'failed', completed_at = $2 \
+                        WHERE id = $1 AND state = 'in_progress';",
+                        id,
+                        completed_at,
+                    )
+                    .execute(db_pool)
+                    .await
+                    .map(|r| r.rows_affected()),
+                    crate::db::DbPool::Postgres(db_pool) => sqlx::query(
+                        "UPDATE webhooks \
+                        SET state = 'failed', completed_at = $2 \
+                        WHERE id = $1 AND state = 'in_progress';",
+                    )
+                    .bind(id)
+                    .bind(&completed_at)
+                    .execute(db_pool)
+                    .await
+                    .map(|r| r.rows_affected()),
+                }
+                .map(|rows| {
+                    if rows > 0 {
+                        audit_webhook(id, "in_progress", "failed", "handler panicked");
+                    }
+                    None
+                }),
+                (HandlerKind::Hash, PanicPolicy::FailTask) => match &self.db_pool {
+                    crate::db::DbPool::Sqlite(db_pool) => sqlx::query!(
+                        "UPDATE hashes \
+                        SET state = 'failed', completed_at = $2 \
+                        WHERE id = $1 AND state = 'in_progress';",
+                        id,
+                        completed_at,
+                    )
+                    .execute(db_pool)
+                    .await
+                    .map(|r| r.rows_affected()),
+                    crate::db::DbPool::Postgres(db_pool) => sqlx::query(
+                        "UPDATE hashes \
+                        SET state = 'failed', completed_at = $2 \
+                        WHERE id = $1 AND state = 'in_progress';",
+                    )
+                    .bind(id)
+                    .bind(&completed_at)
+                    .execute(db_pool)
+                    .await
+                    .map(|r| r.rows_affected()),
+                }
+                .map(|rows| {
+                    if rows > 0 {
+                        audit_hash(id, "in_progress", "failed", "handler panicked");
+                    }
+                    None
+                }),
+                (HandlerKind::Webhook, PanicPolicy::Requeue) => match &self.db_pool {
+                    crate::db::DbPool::Sqlite(db_pool) => {
+                        sqlx::query_as!(
+                            crate::api::ApiWebhook,
+                            "UPDATE webhooks \
+                            SET state = 'todo' \
+                            WHERE id = $1 AND state = 'in_progress' \
+                            RETURNING id, state, execution_time, method, url, body, idempotency_key, \
+                            cron, calendar, max_retries, initial_backoff_ms, timeout_ms, max_latency_ms, \
+                            signing_secret, \
+                            priority, shard_key, last_status_code, last_response_body, last_latency_ms, \
+                            created_at, started_at, completed_at, \
+                            headers AS \"headers: sqlx::types::Json<crate::api::WebhookHeaders>\", \
+                            tags AS \"tags: sqlx::types::Json<Vec<String>>\";",
+                            id,
+                        )
+                        .fetch_optional(db_pool)
+                        .await
+                    }
+                    crate::db::DbPool::Postgres(db_pool) => {
+                        sqlx::query_as(&format!(
+                            "UPDATE webhooks \
+                            SET state = 'todo' \
+                            WHERE id = $1 AND state = 'in_progress' \
+                            RETURNING {};",
+                            crate::db::PG_WEBHOOK_COLUMNS
+                        ))
+                        .bind(id)
+                        .fetch_optional(db_pool)
+                        .await
+                    }
+                }
+                .map(|wh| wh.map(crate::api::Task::Webhook)),
+                (HandlerKind::Hash, PanicPolicy::Requeue) => match &self.db_pool {
+                    crate::db::DbPool::Sqlite(db_pool) => {
+                        sqlx::query_as!(
+                            crate::api::ApiHash,
+                            "UPDATE hashes \
+                            SET state = 'todo' \
+                            WHERE id = $1 AND state = 'in_progress' \
+                            RETURNING id, state, execution_time, secret, algorithm, \
+                            params AS \"params: sqlx::types::Json<serde_json::Value>\", result, \
+                            created_at, started_at, completed_at;",
+                            id,
+                        )
+                        .fetch_optional(db_pool)
+                        .await
+                    }
+                    crate::db::DbPool::Postgres(db_pool) => {
+                        sqlx::query_as(&format!(
+                            "UPDATE hashes \
+                            SET state = 'todo' \
+                            WHERE id = $1 AND state = 'in_progress' \
+                            RETURNING {};",
+                            crate::db::PG_HASH_COLUMNS
+                        ))
+                        .bind(id)
+                        .fetch_optional(db_pool)
+                        .await
+                    }
+                }
+                .map(|h| h.map(crate::api::Task::Hash)),
+            }
+        })
+        .await;
+
+        match res {
+            Ok(None) => {}
+            Ok(Some(task)) => {
+                crate::audit::record(
+                    crate::audit::Actor::Worker,
+                    kind.name(),
+                    id,
+                    "in_progress",
+                    "todo",
+                    "handler panicked",
+                );
+                if let Err(e) = self.send_task.send((PANIC_REQUEUE_DELAY, task)).await {
+                    event!(
+                        Level::WARN,
+                        "Worker failed to queue task '{id}' again after its handler panicked: {e}",
+                    );
+                }
+            }
+            Err(e) => {
+                event!(
+                    Level::WARN,
+                    "Worker failed to recover task '{id}' after its handler panicked: {e}",
+                );
+            }
+        }
     }
 
+    /// Hands the tasks yielded by the delay queue at the same time to their
+    /// handlers. Tasks of the same type are claimed as a batch if more than one
+    /// of them is ready, and one by one otherwise. If an outbound queue is
+    /// configured, webhook tasks are enqueued there instead, and those that
+    /// don't fit anymore are returned to be deferred. Webhook tasks with a
+    /// shard key are always handled one by one, after all tasks with the same
+    /// key handed over before them.
+    fn dispatch(&self, tasks: Vec<crate::api::Task>) -> Vec<crate::api::Task> {
+        let mut webhooks = Vec::new();
+        let mut hashes = Vec::new();
+        let mut deferred = Vec::new();
+        for task in tasks {
+            match task {
+                crate::api::Task::Webhook(wh) if wh.shard_key.is_some() => {
+                    event!(
+                        Level::DEBUG,
+                        "A webhook task with a shard key is ready now!"
+                    );
+                    let turn = self
+                        .shard_locks
+                        .enqueue(wh.shard_key.as_deref().unwrap_or_default());
+                    let ids = vec![wh.id.clone()];
+                    let handler = handle_sharded_webhook(self.clone(), worker_webhook(wh), turn);
+                    self.spawn_handler(HandlerKind::Webhook, ids, handler);
+                }
+                crate::api::Task::Webhook(wh) => {
+                    event!(Level::DEBUG, "A webhook task is ready now!");
+                    match &self.outbound {
+                        Some(outbound) => match outbound.send.try_reserve() {
+                            Ok(permit) => permit.send(worker_webhook(wh)),
+                            Err(_) => deferred.push(crate::api::Task::Webhook(wh)),
+                        },
+                        None => webhooks.push(worker_webhook(wh)),
+                    }
+                }
+                crate::api::Task::Hash(h) => {
+                    event!(Level::DEBUG, "A hash task is ready now!");
+                    hashes.push(WorkerHash {
+                        id: h.id,
+                        execution_time: h.execution_time,
+                        secret: h.secret,
+                        algorithm: h.algorithm,
+                        params: h.params.0,
+                    });
+                }
+            }
+        }
+
+        if webhooks.len() > 1 {
+            let ids = webhooks.iter().map(|wh| wh.id.clone()).collect();
+            self.spawn_handler(
+                HandlerKind::Webhook,
+                ids,
+                handle_webhook_batch(self.clone(), webhooks),
+            );
+        } else if let Some(wh) = webhooks.pop() {
+            let ids = vec![wh.id.clone()];
+            self.spawn_handler(HandlerKind::Webhook, ids, handle_webhook(self.clone(), wh));
+        }
+
+        if hashes.len() > 1 {
+            let ids = hashes.iter().map(|h| h.id.clone()).collect();
+            self.spawn_handler(
+                HandlerKind::Hash,
+                ids,
+                handle_hash_batch(self.clone(), hashes),
+            );
+        } else if let Some(h) = hashes.pop() {
+            let ids = vec![h.id.clone()];
+            self.spawn_handler(HandlerKind::Hash, ids, handle_hash(self.clone(), h));
+        }
+
+        deferred
+    }
+
+    /// Spawns the fixed pool of senders draining the outbound queue, if one is
+    /// configured. Each sender handles one webhook task at a time, until the
+    /// worker shuts down.
+    fn spawn_senders(&self, recv_shutdown: &tokio::sync::broadcast::Receiver<()>) {
+        let Some(outbound) = &self.outbound else {
+            return;
+        };
+        for _ in 0..outbound.senders {
+            let ctx = self.clone();
+            let outbound = outbound.clone();
+            let mut recv_shutdown = recv_shutdown.resubscribe();
+            self.handlers.spawn(async move {
+                loop {
+                    let task = tokio::select! {
+                        task = async { outbound.recv.lock().await.recv().await } => task,
+                        _ = recv_shutdown.recv() => None,
+                    };
+                    let Some(task) = task else {
+                        return;
+                    };
+
+                    // Handle each task in its own handler, so that a panic neither
+                    // leaves the task in state `in_progress` nor shrinks the pool.
+                    let (send_done, recv_done) = tokio::sync::oneshot::channel();
+                    let ids = vec![task.id.clone()];
+                    let handler = handle_webhook(ctx.clone(), task);
+                    ctx.spawn_handler(HandlerKind::Webhook, ids, async move {
+                        handler.await;
+                        let _ = send_done.send(());
+                    });
+                    let _ = recv_done.await;
+                }
+            });
+        }
+    }
+
+    /// Handles queued tasks at their execution times until shutdown is
+    /// initiated. Upon shutdown, no further tasks are started, and handlers
+    /// still working on a task are awaited for up to the shutdown grace period.
+    /// Returns the number of tasks still being handled when shutdown was
+    /// initiated.
     pub(crate) async fn run(
         self,
         mut recv_shutdown: tokio::sync::broadcast::Receiver<()>,
         mut recv_task: tokio::sync::mpsc::Receiver<(tokio::time::Duration, crate::api::Task)>,
-    ) {
-        let mut delay_queue = tokio_util::time::DelayQueue::<crate::api::Task>::new();
+        mut recv_cmd: tokio::sync::mpsc::Receiver<WorkerCmd>,
+    ) -> usize {
+        // Report the worker as alive for as long as this loop runs, so that the
+        // API stops accepting tasks that would never be handled otherwise.
+        self.alive.store(true, std::sync::atomic::Ordering::Release);
+        let _alive_guard = AliveGuard(self.alive.clone());
+
+        let mut queue = TaskQueue::new(self.near_term_horizon);
+
+        // Check the far-future bucket every half horizon, if there is one.
+        let mut promote_interval =
+            tokio::time::interval(self.near_term_horizon.unwrap_or(MAX_QUEUE_DELAY) / 2);
+        promote_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        self.spawn_senders(&recv_shutdown);
+
         loop {
             tokio::select! {
                 Some((at_time, task)) = recv_task.recv() => {
                     event!(Level::DEBUG, "Inserting task into delay queue...");
-                    delay_queue.insert(task, at_time);
+                    queue.insert(task, at_time);
                 }
-                Some(ready) = delay_queue.next() => {
-                    match ready.get_ref() {
-                        crate::api::Task::Webhook(wh) => {
-                            event!(Level::DEBUG, "A webhook task is ready now!");
-                            tokio::task::spawn(handle_webhook(self.db_pool.clone(), WorkerWebhook{
-                                id: wh.id.clone(),
-                                execution_time: wh.execution_time.clone(),
-                                url: wh.url.clone(),
-                                body: wh.body.clone(),
-                            }));
+                Some(cmd) = recv_cmd.recv() => {
+                    match cmd {
+                        WorkerCmd::Remove { id } => {
+                            event!(Level::DEBUG, "Removing task '{id}' from delay queue...");
+                            queue.remove(&id);
+                        }
+                        WorkerCmd::Inspect { id, reply } => {
+                            let _ = reply.send(queue.deadline(&id));
                         }
-                        crate::api::Task::Hash(h) => {
-                            event!(Level::DEBUG, "A hash task is ready now!");
-                            tokio::task::spawn(handle_hash(self.db_pool.clone(), WorkerHash{
-                                id: h.id.clone(),
-                                execution_time: h.execution_time.clone(),
-                                secret: h.secret.clone(),
-                            }));
+                        WorkerCmd::Clear { reply } => {
+                            event!(Level::DEBUG, "Clearing delay queue...");
+                            let _ = reply.send(queue.clear());
                         }
                     }
                 }
+                _ = promote_interval.tick(), if self.near_term_horizon.is_some() => {
+                    queue.promote_near_term();
+                }
+                Some(ready) = queue.delay_queue.next() => {
+                    // Collect further tasks that are ready right away as well, so that
+                    // they can be handled in order of their priority and claimed
+                    // together.
+                    let mut batch = vec![ready];
+                    while batch.len() < MAX_READY_BUFFER.max(self.claim_batch_size) {
+                        match queue.delay_queue.next().now_or_never() {
+                            Some(Some(ready)) => batch.push(ready),
+                            _ => break,
+                        }
+                    }
+
+                    let mut tasks = Vec::with_capacity(batch.len());
+                    for ready in batch {
+                        self.scheduling_error.record(ready.deadline());
+                        let task = ready.into_inner();
+
+                        queue.forget_yielded(task.id());
+
+                        // Tasks due later than the longest delay the queue supports
+                        // are queued again for the remaining time.
+                        if let Some(remaining) = remaining_queue_delay(task.execution_time()) {
+                            queue.insert(task, remaining);
+                            continue;
+                        }
+                        tasks.push(task);
+                    }
+
+                    // Hand the tasks to their handlers highest priority first, in
+                    // batches of up to the configured size. Tasks of equal priority
+                    // keep the order in which the delay queue yielded them.
+                    tasks.sort_by_key(|task| std::cmp::Reverse(task.priority()));
+                    let mut deferred = Vec::new();
+                    while !tasks.is_empty() {
+                        let rest = tasks.split_off(self.claim_batch_size.min(tasks.len()));
+                        deferred.extend(self.dispatch(tasks));
+                        tasks = rest;
+                    }
+
+                    // Webhook tasks not fitting into the full outbound queue are
+                    // queued again shortly after.
+                    for task in deferred {
+                        event!(
+                            Level::DEBUG,
+                            "Outbound queue is full, deferring webhook task '{}'...",
+                            task.id(),
+                        );
+                        queue.insert(task, OUTBOUND_DEFER_DELAY);
+                    }
+                }
                 _ = recv_shutdown.recv() => {
-                    event!(Level::DEBUG, "Worker shutting down...");
-                    return;
+                    let in_flight = self.task_limiter.in_flight();
+                    event!(
+                        Level::DEBUG,
+                        "Worker shutting down with {in_flight} tasks in flight...",
+                    );
+
+                    // Stop accepting tasks, so that handlers queueing recurring tasks
+                    // again don't wait for the stopped loop, and give the handlers
+                    // still working on a task the grace period to finish.
+                    drop(recv_task);
+                    self.handlers.close();
+                    if tokio::time::timeout(self.shutdown_grace, self.handlers.wait())
+                        .await
+                        .is_err()
+                    {
+                        event!(
+                            Level::WARN,
+                            "Worker abandons {} tasks still in flight after grace period",
+                            self.task_limiter.in_flight(),
+                        );
+                    }
+                    return in_flight;
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test(start_paused = true)]
+    async fn sleeps_until_sub_second_execution_time() {
+        let offset = std::time::Duration::from_micros(1_234_567);
+        let execution_time =
+            chrono::Utc::now().fixed_offset() + chrono::TimeDelta::from_std(offset).unwrap();
+
+        let start = tokio::time::Instant::now();
+        super::sleep_until_execution_time(execution_time).await;
+        let elapsed = start.elapsed();
+
+        // Tokio's timer fires at millisecond granularity.
+        let tolerance = std::time::Duration::from_millis(1);
+        assert!(
+            elapsed + tolerance >= offset && elapsed <= offset + tolerance,
+            "slept {elapsed:?} instead of {offset:?}"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn returns_immediately_for_past_execution_time() {
+        let execution_time = chrono::Utc::now().fixed_offset() - chrono::TimeDelta::seconds(5);
+
+        let start = tokio::time::Instant::now();
+        super::sleep_until_execution_time(execution_time).await;
+        assert_eq!(start.elapsed(), std::time::Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn scheduling_error_summarizes_samples() {
+        let scheduling_error = super::SchedulingError::default();
+        let status = scheduling_error.status();
+        assert_eq!(status.samples, 0);
+        assert!(status.mean_ms.is_none() && status.max_ms.is_none());
+
+        let now = tokio::time::Instant::now();
+        scheduling_error.record(now - std::time::Duration::from_millis(4));
+        scheduling_error.record(now - std::time::Duration::from_millis(2));
+        // Tasks yielded before their deadline count as no error.
+        scheduling_error.record(now + std::time::Duration::from_millis(1));
+
+        let status = scheduling_error.status();
+        assert_eq!(status.samples, 3);
+        let close_to =
+            |ms: Option<f64>, expected: f64| ms.is_some_and(|ms| (ms - expected).abs() < 1e-9);
+        assert!(close_to(status.mean_ms, 2.0), "{status:?}");
+        assert!(close_to(status.max_ms, 4.0), "{status:?}");
+        assert!(close_to(status.last_ms, 0.0), "{status:?}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn delay_queue_yields_within_a_millisecond() {
+        let mut delay_queue = tokio_util::time::DelayQueue::new();
+        let delay = std::time::Duration::from_micros(7_300);
+        delay_queue.insert((), delay);
+
+        let start = tokio::time::Instant::now();
+        let expired = futures_util::StreamExt::next(&mut delay_queue)
+            .await
+            .unwrap();
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed >= delay && elapsed < delay + std::time::Duration::from_millis(1),
+            "yielded after {elapsed:?} instead of {delay:?}"
+        );
+
+        let scheduling_error = super::SchedulingError::default();
+        scheduling_error.record(expired.deadline());
+        let status = scheduling_error.status();
+        assert!(status.max_ms.is_some_and(|ms| ms < 1.0), "{status:?}");
+    }
+
+    /// Returns the request creating a cheap hash task due at `execution_time`.
+    fn hash_at(execution_time: chrono::DateTime<chrono::Utc>) -> serde_json::Value {
+        serde_json::json!({
+            "hash": {
+                "secret": "correct-horse-battery-staple",
+                "params": { "rounds": 1000 },
+                "execution_time": execution_time.to_rfc3339(),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn queues_tasks_due_beyond_delay_queue_limit() {
+        let app = crate::testing::TestApp::start(&[]).await;
+        let now = chrono::Utc::now();
+
+        let (status, distant) = app
+            .post(
+                "/tasks/new",
+                &hash_at(now + chrono::TimeDelta::days(3 * 365)),
+            )
+            .await;
+        assert_eq!(status, reqwest::StatusCode::CREATED, "{distant}");
+        let (status, soon) = app
+            .post("/tasks/new", &hash_at(now + chrono::TimeDelta::seconds(1)))
+            .await;
+        assert_eq!(status, reqwest::StatusCode::CREATED, "{soon}");
+
+        // The worker survived queueing the distant task and handles the other.
+        let soon_id = soon["id"].as_str().unwrap();
+        app.wait_for_state(soon_id, "done", std::time::Duration::from_secs(10))
+            .await;
+        let distant_id = distant["id"].as_str().unwrap();
+        app.wait_for_state(distant_id, "todo", std::time::Duration::ZERO)
+            .await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn requeues_tasks_due_beyond_delay_queue_limit() {
+        let execution_time = (chrono::Utc::now() + chrono::TimeDelta::days(3 * 365)).to_rfc3339();
+        let delay = super::remaining_queue_delay(&execution_time).unwrap();
+        assert_eq!(delay, super::MAX_QUEUE_DELAY);
+
+        let mut delay_queue = tokio_util::time::DelayQueue::new();
+        delay_queue.insert(execution_time, delay);
+        let expired = futures_util::StreamExt::next(&mut delay_queue)
+            .await
+            .unwrap();
+
+        // Only tokio's paused clock advanced by a year, so the task is still due
+        // more than two years from now and is queued for the longest delay again.
+        let remaining = super::remaining_queue_delay(expired.get_ref()).unwrap();
+        assert_eq!(remaining, super::MAX_QUEUE_DELAY);
+        delay_queue.insert(expired.into_inner(), remaining);
+        assert_eq!(delay_queue.len(), 1);
+    }
+
+    /// Returns the URL of a local port nobody listens on.
+    async fn closed_port_url() -> String {
+        let sock = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        format!("http://{}/", sock.local_addr().unwrap())
+    }
+
+    #[tokio::test]
+    async fn webhook_without_retries_fails_upon_first_connection_error() {
+        let app = crate::testing::TestApp::start(&[]).await;
+        let (status, body) = app
+            .post(
+                "/tasks/new",
+                &serde_json::json!({
+                    "webhook": {
+                        "url": closed_port_url().await,
+                        "body": "{}",
+                        "execution_time": (chrono::Utc::now() + chrono::TimeDelta::milliseconds(100)).to_rfc3339(),
+                        "max_retries": 0,
+                        "initial_backoff_ms": 60_000,
+                    }
+                }),
+            )
+            .await;
+        assert_eq!(status, reqwest::StatusCode::CREATED, "{body}");
+        let id = body["id"].as_str().unwrap();
+
+        // A retry would only take place after the initial backoff of a minute.
+        let dead_letter = app
+            .wait_for_dead_letter(id, std::time::Duration::from_secs(10))
+            .await;
+        assert_eq!(
+            dead_letter["attempts"].as_array().map(Vec::len),
+            Some(1),
+            "{dead_letter}"
+        );
+    }
+
+    #[tokio::test]
+    async fn hash_result_is_returned_once_done() {
+        let app = crate::testing::TestApp::start(&[]).await;
+        let execution_time = chrono::Utc::now() + chrono::TimeDelta::milliseconds(300);
+        let (status, body) = app
+            .post(
+                "/tasks/new",
+                &serde_json::json!({
+                    "hash": {
+                        "secret": "secret",
+                        "algorithm": "pbkdf2",
+                        "params": { "rounds": 1000 },
+                        "execution_time": execution_time.to_rfc3339(),
+                    }
+                }),
+            )
+            .await;
+        assert_eq!(status, reqwest::StatusCode::CREATED, "{body}");
+        let id = body["id"].as_str().unwrap();
+
+        let (_, pending) = app.get(&format!("/tasks/{id}")).await;
+        assert_eq!(pending["hash"]["state"], "todo", "{pending}");
+        assert!(pending["hash"]["result"].is_null(), "{pending}");
+
+        let done = app
+            .wait_for_state(id, "done", std::time::Duration::from_secs(10))
+            .await;
+        // The result holds the hash in PHC string format, encoded in base64.
+        let result = base64::Engine::decode(
+            &base64::prelude::BASE64_STANDARD,
+            done["result"].as_str().unwrap(),
+        )
+        .unwrap();
+        let result = String::from_utf8(result).unwrap();
+        let hash = pbkdf2::password_hash::PasswordHash::new(&result).unwrap();
+        assert!(
+            pbkdf2::password_hash::PasswordVerifier::verify_password(
+                &pbkdf2::Pbkdf2,
+                b"secret",
+                &hash
+            )
+            .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn webhook_request_times_out_against_hanging_target() {
+        let target = crate::testing::mock_target(axum::Router::new().route(
+            "/",
+            axum::routing::post(|| async {
+                tokio::time::sleep(std::time::Duration::from_mins(1)).await;
+                axum::http::StatusCode::NO_CONTENT
+            }),
+        ))
+        .await;
+        let app = crate::testing::TestApp::start(&[]).await;
+        let (status, body) = app
+            .post(
+                "/tasks/new",
+                &serde_json::json!({
+                    "webhook": {
+                        "url": format!("{target}/"),
+                        "body": "{}",
+                        "execution_time": chrono::Utc::now().to_rfc3339(),
+                        "timeout_ms": 100,
+                        "max_retries": 1,
+                        "initial_backoff_ms": 1,
+                    }
+                }),
+            )
+            .await;
+        assert_eq!(status, reqwest::StatusCode::CREATED, "{body}");
+        let id = body["id"].as_str().unwrap();
+
+        // Both attempts time out long before the target would respond.
+        let dead_letter = app
+            .wait_for_dead_letter(id, std::time::Duration::from_secs(10))
+            .await;
+        assert_eq!(
+            dead_letter["attempts"].as_array().map(Vec::len),
+            Some(2),
+            "{dead_letter}"
+        );
+    }
+
+    #[test]
+    fn next_cron_run_skips_holidays_and_weekends() {
+        let calendar = crate::calendar::BusinessCalendar::new(&["2026-12-25".parse().unwrap()]);
+        let after = "2026-12-24T10:00:00Z".parse().unwrap();
+
+        // Daily at 9:00 would next fire on the holiday on Friday.
+        assert_eq!(
+            super::next_cron_run("0 0 9 * * *", None, after),
+            Ok("2026-12-25T09:00:00Z".parse().unwrap())
+        );
+        assert_eq!(
+            super::next_cron_run("0 0 9 * * *", Some(&calendar), after),
+            Ok("2026-12-28T09:00:00Z".parse().unwrap())
+        );
+    }
+
+    /// Returns a hash task with ID `id` for queueing.
+    fn hash_task(id: &str) -> crate::api::Task {
+        crate::api::Task::Hash(crate::api::ApiHash {
+            id: id.to_string(),
+            state: "todo".to_string(),
+            execution_time: chrono::Utc::now().to_rfc3339(),
+            secret: "secret".to_string(),
+            algorithm: "pbkdf2".to_string(),
+            params: sqlx::types::Json(serde_json::json!({})),
+            result: None,
+            created_at: None,
+            started_at: None,
+            completed_at: None,
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn queued_task_fires_close_to_its_deadline() {
+        let tolerance = std::time::Duration::from_millis(50);
+        let delay = std::time::Duration::from_millis(1_234);
+        let mut queue = super::TaskQueue::new(None);
+        queue.insert(hash_task("near"), delay);
+
+        let start = tokio::time::Instant::now();
+        let expired = futures_util::StreamExt::next(&mut queue.delay_queue)
+            .await
+            .unwrap();
+        let elapsed = start.elapsed();
+        assert_eq!(expired.get_ref().id(), "near");
+        assert!(
+            elapsed >= delay && elapsed < delay + tolerance,
+            "fired after {elapsed:?} instead of {delay:?}"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn far_future_task_fires_close_to_its_deadline() {
+        let tolerance = std::time::Duration::from_millis(50);
+        let horizon = std::time::Duration::from_mins(1);
+        let delay = std::time::Duration::from_millis(90_321);
+        let mut queue = super::TaskQueue::new(Some(horizon));
+        let start = tokio::time::Instant::now();
+        queue.insert(hash_task("far"), delay);
+        assert!(queue.delay_queue.is_empty());
+
+        // The worker promotes tasks becoming due within the horizon every half
+        // horizon.
+        while queue.delay_queue.is_empty() {
+            tokio::time::sleep(horizon / 2).await;
+            queue.promote_near_term();
+        }
+        let expired = futures_util::StreamExt::next(&mut queue.delay_queue)
+            .await
+            .unwrap();
+        let elapsed = start.elapsed();
+        assert_eq!(expired.get_ref().id(), "far");
+        assert!(
+            elapsed >= delay && elapsed < delay + tolerance,
+            "fired after {elapsed:?} instead of {delay:?}"
+        );
+    }
+}